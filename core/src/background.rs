@@ -1,11 +1,111 @@
-use crate::Color;
+use crate::{Color, Point, Vector};
 
 /// The background of some element.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Background {
     /// A solid color
     Color(Color),
-    // TODO: Add gradient and image variants
+    /// A linear gradient
+    ///
+    /// Like a CSS `linear-gradient`, it spans the bounds of the element along
+    /// its direction, so the corners of the element get its start and end
+    /// colors.
+    LinearGradient {
+        /// The direction of the gradient, from its start to its end color
+        ///
+        /// Only its orientation matters, not its length.
+        direction: Vector,
+        /// The color at the start of the gradient
+        start: Color,
+        /// The color at the end of the gradient
+        end: Color,
+    },
+    /// A radial gradient
+    ///
+    /// Its center is relative to the top-left corner of the bounds of the
+    /// element, so it moves along with it.
+    RadialGradient {
+        /// The center of the gradient
+        center: Point,
+        /// The radius of the gradient
+        radius: f32,
+        /// The color at the center of the gradient
+        start: Color,
+        /// The color at the radius of the gradient and beyond
+        end: Color,
+    },
+}
+
+impl Background {
+    /// Creates a [`Background::LinearGradient`] from an angle in degrees,
+    /// following the convention of CSS.
+    ///
+    /// The angle points towards the end color and runs clockwise from the
+    /// top of the element:
+    ///
+    /// ```text
+    ///             0°
+    ///             ^
+    ///             |
+    ///   270° <----+----> 90°
+    ///             |
+    ///             v
+    ///            180°
+    /// ```
+    pub fn linear_gradient_deg(
+        angle_degrees: f32,
+        start: Color,
+        end: Color,
+    ) -> Self {
+        Background::LinearGradient {
+            direction: Self::linear_gradient_direction(angle_degrees),
+            start,
+            end,
+        }
+    }
+
+    /// Returns the direction of a linear gradient with the given angle in
+    /// degrees, following the convention of
+    /// [`Background::linear_gradient_deg`].
+    pub fn linear_gradient_direction(angle_degrees: f32) -> Vector {
+        let (sin, cos) = angle_degrees.to_radians().sin_cos();
+
+        // The Y axis points down
+        Vector::new(sin, -cos)
+    }
+
+    /// Multiplies the alpha channel of every color of the [`Background`] by
+    /// the given factor.
+    pub fn scale_alpha(self, factor: f32) -> Self {
+        let scale = |color: Color| Color {
+            a: color.a * factor,
+            ..color
+        };
+
+        match self {
+            Background::Color(color) => Background::Color(scale(color)),
+            Background::LinearGradient {
+                direction,
+                start,
+                end,
+            } => Background::LinearGradient {
+                direction,
+                start: scale(start),
+                end: scale(end),
+            },
+            Background::RadialGradient {
+                center,
+                radius,
+                start,
+                end,
+            } => Background::RadialGradient {
+                center,
+                radius,
+                start: scale(start),
+                end: scale(end),
+            },
+        }
+    }
 }
 
 impl From<Color> for Background {
@@ -19,3 +119,52 @@ impl From<Color> for Option<Background> {
         Some(Background::from(color))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn direction(angle_degrees: f32) -> Vector {
+        match Background::linear_gradient_deg(
+            angle_degrees,
+            Color::BLACK,
+            Color::WHITE,
+        ) {
+            Background::LinearGradient { direction, .. } => Vector::new(
+                (direction.x * 1e6).round() / 1e6,
+                (direction.y * 1e6).round() / 1e6,
+            ),
+            background => {
+                panic!("expected a linear gradient: {:?}", background)
+            }
+        }
+    }
+
+    #[test]
+    fn angles_follow_css_convention() {
+        assert_eq!(direction(0.0), Vector::new(0.0, -1.0));
+        assert_eq!(direction(90.0), Vector::new(1.0, 0.0));
+        assert_eq!(direction(180.0), Vector::new(0.0, 1.0));
+        assert_eq!(direction(270.0), Vector::new(-1.0, 0.0));
+    }
+
+    #[test]
+    fn scaling_alpha_scales_every_color() {
+        let background = Background::linear_gradient_deg(
+            90.0,
+            Color::from_rgba(1.0, 0.0, 0.0, 0.8),
+            Color::WHITE,
+        )
+        .scale_alpha(0.5);
+
+        match background {
+            Background::LinearGradient { start, end, .. } => {
+                assert_eq!(start, Color::from_rgba(1.0, 0.0, 0.0, 0.4));
+                assert_eq!(end, Color::from_rgba(1.0, 1.0, 1.0, 0.5));
+            }
+            background => {
+                panic!("expected a linear gradient: {:?}", background)
+            }
+        }
+    }
+}
@@ -109,6 +109,14 @@ impl Color {
         ]
     }
 
+    /// Converts the [`Color`] into its linear values, with the color channels
+    /// multiplied by the alpha channel.
+    pub fn into_linear_premultiplied(self) -> [f32; 4] {
+        let [r, g, b, a] = self.into_linear();
+
+        [r * a, g * a, b * a, a]
+    }
+
     /// Inverts the [`Color`] in-place.
     pub fn invert(&mut self) {
         self.r = 1.0f32 - self.r;
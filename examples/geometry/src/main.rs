@@ -59,7 +59,7 @@ mod rainbow {
             cursor_position: Point,
             _viewport: &Rectangle,
         ) {
-            use iced_graphics::triangle::Mesh2D;
+            use iced_graphics::triangle::{AlphaMode, Mesh2D};
             use iced_native::Renderer as _;
 
             let b = layout.bounds();
@@ -142,6 +142,7 @@ mod rainbow {
                         0, 7, 8, // BL
                         0, 8, 1, // L
                     ],
+                    alpha_mode: AlphaMode::Straight,
                 },
             };
 
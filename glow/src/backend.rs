@@ -1,13 +1,15 @@
 #[cfg(any(feature = "image", feature = "svg"))]
 use crate::image;
 use crate::quad;
+use crate::shadow;
 use crate::text;
 use crate::{program, triangle};
 use crate::{Settings, Transformation, Viewport};
 
 use iced_graphics::backend;
 use iced_graphics::font;
-use iced_graphics::{Layer, Primitive};
+use iced_graphics::triangle::AlphaMode;
+use iced_graphics::{layer, Layer, Primitive};
 use iced_native::alignment;
 use iced_native::{Font, Size};
 
@@ -20,6 +22,7 @@ pub struct Backend {
     #[cfg(any(feature = "image", feature = "svg"))]
     image_pipeline: image::Pipeline,
     quad_pipeline: quad::Pipeline,
+    shadow_pipeline: shadow::Pipeline,
     text_pipeline: text::Pipeline,
     triangle_pipeline: triangle::Pipeline,
     default_text_size: u16,
@@ -39,12 +42,14 @@ impl Backend {
         #[cfg(any(feature = "image", feature = "svg"))]
         let image_pipeline = image::Pipeline::new(gl, &shader_version);
         let quad_pipeline = quad::Pipeline::new(gl, &shader_version);
+        let shadow_pipeline = shadow::Pipeline::new(gl, &shader_version);
         let triangle_pipeline = triangle::Pipeline::new(gl, &shader_version);
 
         Self {
             #[cfg(any(feature = "image", feature = "svg"))]
             image_pipeline,
             quad_pipeline,
+            shadow_pipeline,
             text_pipeline,
             triangle_pipeline,
             default_text_size: settings.default_text_size,
@@ -99,6 +104,20 @@ impl Backend {
 
         bounds.height = bounds.height.min(target_height);
 
+        if !layer.shadows.is_empty() {
+            let scaled = transformation
+                * Transformation::scale(scale_factor, scale_factor);
+
+            self.shadow_pipeline.draw(
+                gl,
+                target_height,
+                scaled,
+                scale_factor,
+                &layer.shadows,
+                bounds,
+            );
+        }
+
         if !layer.quads.is_empty() {
             self.quad_pipeline.draw(
                 gl,
@@ -110,6 +129,54 @@ impl Backend {
             );
         }
 
+        #[cfg(feature = "image")]
+        if !layer.quad_images.is_empty() {
+            let scaled = transformation
+                * Transformation::scale(scale_factor, scale_factor);
+
+            self.image_pipeline.draw_quad_images(
+                gl,
+                target_height,
+                scaled,
+                scale_factor,
+                &layer.quads,
+                &layer.quad_images,
+                bounds,
+            );
+        }
+
+        // Every batch is drawn with a single instanced draw call
+        for batch in &layer.quad_batches {
+            self.quad_pipeline.draw(
+                gl,
+                target_height,
+                &batch.quads(),
+                transformation,
+                scale_factor,
+                bounds,
+            );
+        }
+
+        // Layers are generated with the default settings
+        let arcs: Vec<_> = layer
+            .arcs
+            .iter()
+            .filter_map(|arc| arc.mesh(AlphaMode::default(), layer.bounds))
+            .collect();
+
+        if !arcs.is_empty() {
+            let scaled = transformation
+                * Transformation::scale(scale_factor, scale_factor);
+
+            self.triangle_pipeline.draw(
+                &arcs,
+                gl,
+                target_height,
+                scaled,
+                scale_factor,
+            );
+        }
+
         if !layer.meshes.is_empty() {
             let scaled = transformation
                 * Transformation::scale(scale_factor, scale_factor);
@@ -123,6 +190,25 @@ impl Backend {
             );
         }
 
+        if !layer.meshes_3d.is_empty() {
+            let scaled = transformation
+                * Transformation::scale(scale_factor, scale_factor);
+
+            let meshes: Vec<_> = layer
+                .meshes_3d
+                .iter()
+                .map(layer::Mesh3D::flattened)
+                .collect();
+
+            self.triangle_pipeline.draw(
+                &meshes,
+                gl,
+                target_height,
+                scaled,
+                scale_factor,
+            );
+        }
+
         #[cfg(any(feature = "image", feature = "svg"))]
         if !layer.images.is_empty() {
             let scaled = transformation
@@ -139,7 +225,22 @@ impl Backend {
         }
 
         if !layer.text.is_empty() {
-            for text in layer.text.iter() {
+            // Shadows are drawn as copies of their text right behind it
+            //
+            // TODO: Blur text shadows
+            let texts = layer.text.iter().flat_map(|text| {
+                text.shadow
+                    .map(|shadow| layer::Text {
+                        bounds: text.bounds + shadow.offset,
+                        color: shadow.color,
+                        shadow: None,
+                        ..*text
+                    })
+                    .into_iter()
+                    .chain(std::iter::once(*text))
+            });
+
+            for text in texts {
                 // Target physical coordinates directly to avoid blurry text
                 let text = glow_glyph::Section {
                     // TODO: We `round` here to avoid rerasterizing text when
@@ -214,6 +315,17 @@ impl Backend {
                 },
             );
         }
+
+        if let Some(tint) = layer.tint_quad() {
+            self.quad_pipeline.draw(
+                gl,
+                target_height,
+                &[tint],
+                transformation,
+                scale_factor,
+                bounds,
+            );
+        }
     }
 }
 
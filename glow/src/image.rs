@@ -13,9 +13,12 @@ use iced_graphics::image::raster;
 #[cfg(feature = "svg")]
 use iced_graphics::image::vector;
 
+use iced_graphics::image::storage::Entry as _;
+use iced_graphics::image::Filter;
 use iced_graphics::layer;
 use iced_graphics::Rectangle;
 use iced_graphics::Size;
+use iced_graphics::Vector;
 
 use glow::HasContext;
 
@@ -27,6 +30,12 @@ pub(crate) struct Pipeline {
     vertex_array: <glow::Context as HasContext>::VertexArray,
     vertex_buffer: <glow::Context as HasContext>::Buffer,
     transform_location: <glow::Context as HasContext>::UniformLocation,
+    source_location: <glow::Context as HasContext>::UniformLocation,
+    color_location: <glow::Context as HasContext>::UniformLocation,
+    replace_color_location: <glow::Context as HasContext>::UniformLocation,
+    clip_location: <glow::Context as HasContext>::UniformLocation,
+    clip_radius_location: <glow::Context as HasContext>::UniformLocation,
+    screen_height_location: <glow::Context as HasContext>::UniformLocation,
     storage: Storage,
     #[cfg(feature = "image")]
     raster_cache: RefCell<raster::Cache<Storage>>,
@@ -62,6 +71,30 @@ impl Pipeline {
             unsafe { gl.get_uniform_location(program, "u_Transform") }
                 .expect("Get transform location");
 
+        let source_location =
+            unsafe { gl.get_uniform_location(program, "u_Source") }
+                .expect("Get source location");
+
+        let color_location =
+            unsafe { gl.get_uniform_location(program, "u_Color") }
+                .expect("Get color location");
+
+        let replace_color_location =
+            unsafe { gl.get_uniform_location(program, "u_ReplaceColor") }
+                .expect("Get replace color location");
+
+        let clip_location =
+            unsafe { gl.get_uniform_location(program, "u_Clip") }
+                .expect("Get clip location");
+
+        let clip_radius_location =
+            unsafe { gl.get_uniform_location(program, "u_ClipRadius") }
+                .expect("Get clip radius location");
+
+        let screen_height_location =
+            unsafe { gl.get_uniform_location(program, "u_ScreenHeight") }
+                .expect("Get screen height location");
+
         unsafe {
             gl.use_program(Some(program));
 
@@ -72,6 +105,13 @@ impl Pipeline {
                 &transform,
             );
 
+            gl.uniform_4_f32(Some(&source_location), 0.0, 0.0, 1.0, 1.0);
+            gl.uniform_4_f32(Some(&color_location), 1.0, 1.0, 1.0, 1.0);
+            gl.uniform_1_f32(Some(&replace_color_location), 0.0);
+            gl.uniform_4_f32(Some(&clip_location), 0.0, 0.0, 0.0, 0.0);
+            gl.uniform_4_f32(Some(&clip_radius_location), 0.0, 0.0, 0.0, 0.0);
+            gl.uniform_1_f32(Some(&screen_height_location), 0.0);
+
             gl.use_program(None);
         }
 
@@ -115,6 +155,12 @@ impl Pipeline {
             vertex_array,
             vertex_buffer,
             transform_location,
+            source_location,
+            color_location,
+            replace_color_location,
+            clip_location,
+            clip_radius_location,
+            screen_height_location,
             storage: Storage::default(),
             #[cfg(feature = "image")]
             raster_cache: RefCell::new(raster::Cache::default()),
@@ -148,12 +194,7 @@ impl Pipeline {
         images: &[layer::Image],
         layer_bounds: Rectangle<u32>,
     ) {
-        unsafe {
-            gl.use_program(Some(self.program));
-            gl.bind_vertex_array(Some(self.vertex_array));
-            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vertex_buffer));
-            gl.enable(glow::SCISSOR_TEST);
-        }
+        self.begin(gl, target_height, layer_bounds);
 
         #[cfg(feature = "image")]
         let mut raster_cache = self.raster_cache.borrow_mut();
@@ -162,18 +203,51 @@ impl Pipeline {
         let mut vector_cache = self.vector_cache.borrow_mut();
 
         for image in images {
-            let (entry, bounds) = match &image {
+            let (entry, color, replace_color, rotation, filter) = match &image {
                 #[cfg(feature = "image")]
-                layer::Image::Raster { handle, bounds } => (
+                layer::Image::Raster {
+                    handle,
+                    tint,
+                    opacity,
+                    rotation,
+                    filter,
+                    ..
+                } => {
+                    let [r, g, b, a] = *tint;
+
+                    (
+                        raster_cache.upload(handle, &mut gl, &mut self.storage),
+                        [r, g, b, a * opacity],
+                        false,
+                        *rotation,
+                        *filter,
+                    )
+                }
+                #[cfg(feature = "image")]
+                layer::Image::NinePatch { handle, .. } => (
                     raster_cache.upload(handle, &mut gl, &mut self.storage),
-                    bounds,
+                    [1.0; 4],
+                    false,
+                    0.0,
+                    Filter::Linear,
                 ),
                 #[cfg(not(feature = "image"))]
-                layer::Image::Raster { handle: _, bounds } => (None, bounds),
+                layer::Image::Raster { .. }
+                | layer::Image::NinePatch { .. } => {
+                    (None, [1.0; 4], false, 0.0, Filter::Linear)
+                }
 
                 #[cfg(feature = "svg")]
-                layer::Image::Vector { handle, bounds } => {
+                layer::Image::Vector {
+                    handle,
+                    bounds,
+                    opacity,
+                    color,
+                    rotation,
+                } => {
                     let size = [bounds.width, bounds.height];
+                    let [r, g, b, a] = color.unwrap_or([1.0; 4]);
+
                     (
                         vector_cache.upload(
                             handle,
@@ -182,45 +256,164 @@ impl Pipeline {
                             &mut gl,
                             &mut self.storage,
                         ),
-                        bounds,
+                        [r, g, b, a * opacity],
+                        color.is_some(),
+                        *rotation,
+                        Filter::Linear,
                     )
                 }
 
                 #[cfg(not(feature = "svg"))]
-                layer::Image::Vector { handle: _, bounds } => (None, bounds),
+                layer::Image::Vector { .. } => {
+                    (None, [1.0; 4], false, 0.0, Filter::Linear)
+                }
+            };
+
+            let entry = match entry {
+                Some(entry) => entry,
+                None => continue,
             };
 
             unsafe {
-                gl.scissor(
-                    layer_bounds.x as i32,
-                    (target_height - (layer_bounds.y + layer_bounds.height))
-                        as i32,
-                    layer_bounds.width as i32,
-                    layer_bounds.height as i32,
+                let [r, g, b, a] = color;
+                gl.uniform_4_f32(Some(&self.color_location), r, g, b, a);
+                gl.uniform_1_f32(
+                    Some(&self.replace_color_location),
+                    if replace_color { 1.0 } else { 0.0 },
                 );
+            }
 
-                if let Some(storage::Entry { texture, .. }) = entry {
-                    gl.bind_texture(glow::TEXTURE_2D, Some(*texture))
-                } else {
-                    continue;
-                }
+            let center = image.bounds().center();
+            let rotate = Transformation::translate(center.x, center.y)
+                * Transformation::from_decomposed(
+                    Vector::new(0.0, 0.0),
+                    rotation,
+                    Vector::new(1.0, 1.0),
+                )
+                * Transformation::translate(-center.x, -center.y);
 
-                let translate = Transformation::translate(bounds.x, bounds.y);
-                let scale = Transformation::scale(bounds.width, bounds.height);
-                let transformation = transformation * translate * scale;
-                let matrix: [f32; 16] = transformation.into();
-                gl.uniform_matrix_4_f32_slice(
-                    Some(&self.transform_location),
-                    false,
-                    &matrix,
-                );
+            let Size { width, height } = entry.size();
 
-                gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+            draw_slices(
+                gl,
+                &self.transform_location,
+                &self.source_location,
+                entry,
+                filter,
+                transformation * rotate,
+                image.slices(Size::new(width as f32, height as f32)),
+            );
+        }
+
+        self.end(gl);
+    }
+
+    /// Draws the images filling the given quads, clipped by the inner edge
+    /// of their borders.
+    #[cfg(feature = "image")]
+    pub fn draw_quad_images(
+        &mut self,
+        mut gl: &glow::Context,
+        target_height: u32,
+        transformation: Transformation,
+        scale_factor: f32,
+        quads: &[layer::Quad],
+        images: &[layer::quad::Image],
+        layer_bounds: Rectangle<u32>,
+    ) {
+        self.begin(gl, target_height, layer_bounds);
+
+        let mut raster_cache = self.raster_cache.borrow_mut();
+
+        unsafe {
+            gl.uniform_4_f32(Some(&self.color_location), 1.0, 1.0, 1.0, 1.0);
+            gl.uniform_1_f32(Some(&self.replace_color_location), 0.0);
+        }
+
+        for image in images {
+            let entry = match raster_cache.upload(
+                &image.handle,
+                &mut gl,
+                &mut self.storage,
+            ) {
+                Some(entry) => entry,
+                None => continue,
+            };
 
-                gl.bind_texture(glow::TEXTURE_2D, None);
+            let quad = &quads[image.quad];
+            let layer::RoundedClip {
+                bounds,
+                border_radius,
+            } = image.clip(quad);
+
+            // Fragments are clipped in physical pixels
+            let [top_left, top_right, bottom_right, bottom_left] =
+                border_radius.map(|radius| radius * scale_factor);
+
+            unsafe {
+                gl.uniform_4_f32(
+                    Some(&self.clip_location),
+                    bounds.x * scale_factor,
+                    bounds.y * scale_factor,
+                    bounds.width * scale_factor,
+                    bounds.height * scale_factor,
+                );
+                gl.uniform_4_f32(
+                    Some(&self.clip_radius_location),
+                    top_left,
+                    top_right,
+                    bottom_right,
+                    bottom_left,
+                );
             }
+
+            let Size { width, height } = entry.size();
+
+            draw_slices(
+                gl,
+                &self.transform_location,
+                &self.source_location,
+                entry,
+                Filter::Linear,
+                transformation,
+                image.slices(quad, Size::new(width as f32, height as f32)),
+            );
+        }
+
+        unsafe {
+            gl.uniform_4_f32(Some(&self.clip_location), 0.0, 0.0, 0.0, 0.0);
+        }
+
+        self.end(gl);
+    }
+
+    fn begin(
+        &self,
+        gl: &glow::Context,
+        target_height: u32,
+        layer_bounds: Rectangle<u32>,
+    ) {
+        unsafe {
+            gl.use_program(Some(self.program));
+            gl.bind_vertex_array(Some(self.vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vertex_buffer));
+            gl.enable(glow::SCISSOR_TEST);
+
+            gl.scissor(
+                layer_bounds.x as i32,
+                (target_height - (layer_bounds.y + layer_bounds.height)) as i32,
+                layer_bounds.width as i32,
+                layer_bounds.height as i32,
+            );
+
+            gl.uniform_1_f32(
+                Some(&self.screen_height_location),
+                target_height as f32,
+            );
         }
+    }
 
+    fn end(&self, gl: &glow::Context) {
         unsafe {
             gl.bind_buffer(glow::ARRAY_BUFFER, None);
             gl.bind_vertex_array(None);
@@ -241,3 +434,70 @@ impl Pipeline {
             .trim(&mut self.storage, &mut gl);
     }
 }
+
+/// Draws the slices of the image stored in the given entry.
+fn draw_slices(
+    gl: &glow::Context,
+    transform_location: &<glow::Context as HasContext>::UniformLocation,
+    source_location: &<glow::Context as HasContext>::UniformLocation,
+    entry: &storage::Entry,
+    filter: Filter,
+    transformation: Transformation,
+    slices: impl Iterator<Item = layer::Slice>,
+) {
+    let Size { width, height } = entry.size();
+    let (width, height) = (width as f32, height as f32);
+
+    unsafe {
+        gl.bind_texture(glow::TEXTURE_2D, Some(entry.texture));
+
+        let filter = match filter {
+            Filter::Nearest => glow::NEAREST,
+            Filter::Linear => glow::LINEAR,
+        };
+
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            filter as _,
+        );
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MAG_FILTER,
+            filter as _,
+        );
+    }
+
+    for layer::Slice { source, bounds } in slices {
+        let translate = Transformation::translate(bounds.x, bounds.y);
+        let scale = Transformation::scale(bounds.width, bounds.height);
+        let matrix: [f32; 16] = (transformation * translate * scale).into();
+
+        // Only the centers of the texels of the source region are sampled,
+        // so linear filtering never bleeds into its neighbors
+        let inset_x = (source.width / 2.0).min(0.5);
+        let inset_y = (source.height / 2.0).min(0.5);
+
+        unsafe {
+            gl.uniform_matrix_4_f32_slice(
+                Some(transform_location),
+                false,
+                &matrix,
+            );
+
+            gl.uniform_4_f32(
+                Some(source_location),
+                (source.x + inset_x) / width,
+                (source.y + inset_y) / height,
+                (source.width - inset_x * 2.0) / width,
+                (source.height - inset_y * 2.0) / height,
+            );
+
+            gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+        }
+    }
+
+    unsafe {
+        gl.bind_texture(glow::TEXTURE_2D, None);
+    }
+}
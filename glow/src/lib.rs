@@ -28,6 +28,7 @@ mod backend;
 mod image;
 mod program;
 mod quad;
+mod shadow;
 mod text;
 mod triangle;
 
@@ -264,7 +264,7 @@ unsafe fn create_buffers(
     gl.enable_vertex_attrib_array(5);
     gl.vertex_attrib_pointer_f32(
         5,
-        1,
+        4,
         glow::FLOAT,
         false,
         stride,
@@ -278,7 +278,7 @@ unsafe fn create_buffers(
         glow::FLOAT,
         false,
         stride,
-        4 * (2 + 2 + 4 + 4 + 4 + 1),
+        4 * (2 + 2 + 4 + 4 + 4 + 4),
     );
 
     gl.bind_vertex_array(None);
@@ -309,8 +309,8 @@ pub struct Vertex {
     /// The border radius of the [`Vertex`].
     pub border_radius: [f32; 4],
 
-    /// The border width of the [`Vertex`].
-    pub border_width: f32,
+    /// The border width of each side of the [`Vertex`].
+    pub border_width: [f32; 4],
 
     /// The __quad__ position of the [`Vertex`].
     pub q_position: [f32; 2],
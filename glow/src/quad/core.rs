@@ -229,7 +229,7 @@ unsafe fn create_instance_buffer(
     gl.enable_vertex_attrib_array(5);
     gl.vertex_attrib_pointer_f32(
         5,
-        1,
+        4,
         glow::FLOAT,
         false,
         stride,
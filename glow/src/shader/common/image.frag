@@ -7,6 +7,11 @@ precision mediump float;
 #endif
 
 uniform sampler2D tex;
+uniform vec4 u_Color;
+uniform float u_ReplaceColor;
+uniform vec4 u_Clip;
+uniform vec4 u_ClipRadius;
+uniform float u_ScreenHeight;
 in vec2 tex_pos;
 
 #ifdef HIGHER_THAN_300
@@ -17,6 +22,56 @@ out vec4 fragColor;
 #define texture texture2D
 #endif
 
+float fDistance(vec2 frag_coord, vec2 position, vec2 size, float radius)
+{
+    vec2 inner_size = size - vec2(radius, radius) * 2.0;
+    vec2 top_left = position + vec2(radius, radius);
+    vec2 bottom_right = top_left + inner_size;
+
+    vec2 top_left_distance = top_left - frag_coord;
+    vec2 bottom_right_distance = frag_coord - bottom_right;
+
+    vec2 distance = vec2(
+        max(max(top_left_distance.x, bottom_right_distance.x), 0.0),
+        max(max(top_left_distance.y, bottom_right_distance.y), 0.0)
+    );
+
+    return sqrt(distance.x * distance.x + distance.y * distance.y);
+}
+
+float selectBorderRadius(vec4 radi, vec2 position, vec2 center)
+{
+    float rx = radi.x;
+    float ry = radi.y;
+    rx = position.x > center.x ? radi.y : radi.x;
+    ry = position.x > center.x ? radi.z : radi.w;
+    rx = position.y > center.y ? ry : rx;
+    return rx;
+}
+
 void main() {
-    gl_FragColor = texture(tex, tex_pos);
+    vec4 color = texture(tex, tex_pos);
+
+    if (u_ReplaceColor > 0.5) {
+        color = vec4(u_Color.rgb, color.a * u_Color.a);
+    } else {
+        color = color * u_Color;
+    }
+
+    // Images filling a quad are clipped by the inner edge of its border
+    if (u_Clip.z > 0.0) {
+        vec2 fragCoord = vec2(gl_FragCoord.x, u_ScreenHeight - gl_FragCoord.y);
+
+        float radius = selectBorderRadius(
+            u_ClipRadius,
+            fragCoord,
+            u_Clip.xy + u_Clip.zw * 0.5
+        );
+
+        float d = fDistance(fragCoord, u_Clip.xy, u_Clip.zw, radius);
+
+        color.a *= 1.0 - smoothstep(max(radius - 0.5, 0.0), radius + 0.5, d);
+    }
+
+    gl_FragColor = color;
 }
@@ -0,0 +1,82 @@
+#ifdef GL_ES
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+precision highp float;
+#else
+precision mediump float;
+#endif
+#endif
+
+uniform vec4 u_Color;
+uniform vec4 u_Bounds;
+uniform vec4 u_BoundsRadius;
+uniform vec4 u_Shape;
+uniform vec4 u_ShapeRadius;
+uniform float u_Blur;
+uniform float u_Inset;
+uniform float u_Glow;
+uniform float u_ScreenHeight;
+
+#ifdef HIGHER_THAN_300
+out vec4 fragColor;
+#define gl_FragColor fragColor
+#endif
+
+float selectBorderRadius(vec4 radi, vec2 position, vec2 center)
+{
+    float rx = radi.x;
+    float ry = radi.y;
+    rx = position.x > center.x ? radi.y : radi.x;
+    ry = position.x > center.x ? radi.z : radi.w;
+    rx = position.y > center.y ? ry : rx;
+    return rx;
+}
+
+// The signed distance to a rounded rectangle, negative inside of it
+float roundedBoxDistance(vec2 frag_coord, vec4 box, vec4 radi)
+{
+    vec2 half_size = box.zw * 0.5;
+    vec2 center = box.xy + half_size;
+
+    float radius = min(
+        selectBorderRadius(radi, frag_coord, center),
+        min(half_size.x, half_size.y)
+    );
+
+    vec2 q = abs(frag_coord - center) - half_size + vec2(radius, radius);
+
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2(0.0, 0.0))) - radius;
+}
+
+// The coverage of a shape whose edges fade out over the given extent
+float coverage(float d, float extent)
+{
+    float fade = max(extent, 0.5);
+
+    return 1.0 - smoothstep(-fade, fade, d);
+}
+
+void main() {
+    vec2 fragCoord = vec2(gl_FragCoord.x, u_ScreenHeight - gl_FragCoord.y);
+
+    float shape = coverage(
+        roundedBoxDistance(fragCoord, u_Shape, u_ShapeRadius),
+        u_Blur
+    );
+
+    float bounds = coverage(
+        roundedBoxDistance(fragCoord, u_Bounds, u_BoundsRadius),
+        0.0
+    );
+
+    float alpha = shape;
+
+    if (u_Inset > 0.5) {
+        // Inset shadows fill the bounds around their blurred hole
+        alpha = (1.0 - shape) * bounds;
+    } else if (u_Glow > 0.5) {
+        // Glows leave their bounds unfilled
+        alpha = shape * (1.0 - bounds);
+    }
+
+    gl_FragColor = vec4(u_Color.rgb, u_Color.a * alpha);
+}
@@ -0,0 +1,7 @@
+uniform mat4 u_Transform;
+
+in vec2 i_Position;
+
+void main() {
+    gl_Position = u_Transform * vec4(i_Position, 0.0, 1.0);
+}
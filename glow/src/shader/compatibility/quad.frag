@@ -13,7 +13,7 @@ varying vec4 v_BorderColor;
 varying vec2 v_Pos;
 varying vec2 v_Scale;
 varying vec4 v_BorderRadius;
-varying float v_BorderWidth;
+varying vec4 v_BorderWidth;
 
 float _distance(vec2 frag_coord, vec2 position, vec2 size, float radius)
 {
@@ -52,12 +52,18 @@ void main() {
         (v_Pos + v_Scale * 0.5).xy
     );
 
-    float internal_border = max(border_radius - v_BorderWidth, 0.0);
+    // Top, right, bottom, and left; like the CSS `border-width` attribute
+    float max_border_width = max(
+        max(v_BorderWidth.x, v_BorderWidth.y),
+        max(v_BorderWidth.z, v_BorderWidth.w)
+    );
+
+    float internal_border = max(border_radius - max_border_width, 0.0);
 
     float internal_distance = _distance(
         fragCoord,
-        v_Pos + vec2(v_BorderWidth),
-        v_Scale - vec2(v_BorderWidth * 2.0),
+        v_Pos + v_BorderWidth.wx,
+        v_Scale - (v_BorderWidth.yx + v_BorderWidth.wz),
         internal_border
     );
 
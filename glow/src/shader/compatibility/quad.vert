@@ -6,7 +6,7 @@ attribute vec2 i_Scale;
 attribute vec4 i_Color;
 attribute vec4 i_BorderColor;
 attribute vec4 i_BorderRadius;
-attribute float i_BorderWidth;
+attribute vec4 i_BorderWidth;
 attribute vec2 q_Pos;
 
 varying vec4 v_Color;
@@ -14,7 +14,7 @@ varying vec4 v_BorderColor;
 varying vec2 v_Pos;
 varying vec2 v_Scale;
 varying vec4 v_BorderRadius;
-varying float v_BorderWidth;
+varying vec4 v_BorderWidth;
 
 
 void main() {
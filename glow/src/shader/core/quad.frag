@@ -18,7 +18,7 @@ in vec4 v_BorderColor;
 in vec2 v_Pos;
 in vec2 v_Scale;
 in vec4 v_BorderRadius;
-in float v_BorderWidth;
+in vec4 v_BorderWidth;
 
 float fDistance(vec2 frag_coord, vec2 position, vec2 size, float radius)
 {
@@ -59,14 +59,20 @@ void main() {
         (v_Pos + v_Scale * 0.5).xy
     );
 
+    // Top, right, bottom, and left; like the CSS `border-width` attribute
+    float max_border_width = max(
+        max(v_BorderWidth.x, v_BorderWidth.y),
+        max(v_BorderWidth.z, v_BorderWidth.w)
+    );
+
     // TODO: Remove branching (?)
-    if(v_BorderWidth > 0.0) {
-        float internal_border = max(border_radius - v_BorderWidth, 0.0);
+    if(max_border_width > 0.0) {
+        float internal_border = max(border_radius - max_border_width, 0.0);
 
         float internal_distance = fDistance(
             fragCoord,
-            v_Pos + vec2(v_BorderWidth),
-            v_Scale - vec2(v_BorderWidth * 2.0),
+            v_Pos + v_BorderWidth.wx,
+            v_Scale - (v_BorderWidth.yx + v_BorderWidth.wz),
             internal_border
         );
 
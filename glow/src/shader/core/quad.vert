@@ -6,14 +6,14 @@ in vec2 i_Scale;
 in vec4 i_Color;
 in vec4 i_BorderColor;
 in vec4 i_BorderRadius;
-in float i_BorderWidth;
+in vec4 i_BorderWidth;
 
 out vec4 v_Color;
 out vec4 v_BorderColor;
 out vec2 v_Pos;
 out vec2 v_Scale;
 out vec4 v_BorderRadius;
-out float v_BorderWidth;
+out vec4 v_BorderWidth;
 
 vec2 positions[4] = vec2[](
     vec2(0.0, 0.0),
@@ -0,0 +1,208 @@
+use crate::program::{self, Shader};
+use crate::Transformation;
+
+use iced_graphics::layer;
+use iced_graphics::Rectangle;
+
+use glow::HasContext;
+
+#[derive(Debug)]
+pub(crate) struct Pipeline {
+    program: <glow::Context as HasContext>::Program,
+    vertex_array: <glow::Context as HasContext>::VertexArray,
+    vertex_buffer: <glow::Context as HasContext>::Buffer,
+    transform_location: <glow::Context as HasContext>::UniformLocation,
+    color_location: <glow::Context as HasContext>::UniformLocation,
+    bounds_location: <glow::Context as HasContext>::UniformLocation,
+    bounds_radius_location: <glow::Context as HasContext>::UniformLocation,
+    shape_location: <glow::Context as HasContext>::UniformLocation,
+    shape_radius_location: <glow::Context as HasContext>::UniformLocation,
+    blur_location: <glow::Context as HasContext>::UniformLocation,
+    inset_location: <glow::Context as HasContext>::UniformLocation,
+    glow_location: <glow::Context as HasContext>::UniformLocation,
+    screen_height_location: <glow::Context as HasContext>::UniformLocation,
+}
+
+impl Pipeline {
+    pub fn new(
+        gl: &glow::Context,
+        shader_version: &program::Version,
+    ) -> Pipeline {
+        let program = unsafe {
+            let vertex_shader = Shader::vertex(
+                gl,
+                shader_version,
+                include_str!("shader/common/shadow.vert"),
+            );
+            let fragment_shader = Shader::fragment(
+                gl,
+                shader_version,
+                include_str!("shader/common/shadow.frag"),
+            );
+
+            program::create(
+                gl,
+                &[vertex_shader, fragment_shader],
+                &[(0, "i_Position")],
+            )
+        };
+
+        let location = |name: &str| {
+            unsafe { gl.get_uniform_location(program, name) }
+                .unwrap_or_else(|| panic!("Get {} location", name))
+        };
+
+        let vertex_buffer =
+            unsafe { gl.create_buffer().expect("Create vertex buffer") };
+        let vertex_array =
+            unsafe { gl.create_vertex_array().expect("Create vertex array") };
+
+        unsafe {
+            gl.bind_vertex_array(Some(vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vertex_buffer));
+
+            let vertices = &[0u8, 0, 1, 0, 0, 1, 1, 1];
+            gl.buffer_data_size(
+                glow::ARRAY_BUFFER,
+                vertices.len() as i32,
+                glow::STATIC_DRAW,
+            );
+            gl.buffer_sub_data_u8_slice(
+                glow::ARRAY_BUFFER,
+                0,
+                bytemuck::cast_slice(vertices),
+            );
+
+            gl.enable_vertex_attrib_array(0);
+            gl.vertex_attrib_pointer_f32(
+                0,
+                2,
+                glow::UNSIGNED_BYTE,
+                false,
+                0,
+                0,
+            );
+
+            gl.bind_buffer(glow::ARRAY_BUFFER, None);
+            gl.bind_vertex_array(None);
+        }
+
+        Pipeline {
+            program,
+            vertex_array,
+            vertex_buffer,
+            transform_location: location("u_Transform"),
+            color_location: location("u_Color"),
+            bounds_location: location("u_Bounds"),
+            bounds_radius_location: location("u_BoundsRadius"),
+            shape_location: location("u_Shape"),
+            shape_radius_location: location("u_ShapeRadius"),
+            blur_location: location("u_Blur"),
+            inset_location: location("u_Inset"),
+            glow_location: location("u_Glow"),
+            screen_height_location: location("u_ScreenHeight"),
+        }
+    }
+
+    /// Draws the given shadows, each one as a quad covering its visible
+    /// bounds.
+    pub fn draw(
+        &self,
+        gl: &glow::Context,
+        target_height: u32,
+        transformation: Transformation,
+        scale_factor: f32,
+        shadows: &[layer::Shadow],
+        layer_bounds: Rectangle<u32>,
+    ) {
+        unsafe {
+            gl.use_program(Some(self.program));
+            gl.bind_vertex_array(Some(self.vertex_array));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vertex_buffer));
+            gl.enable(glow::SCISSOR_TEST);
+
+            gl.scissor(
+                layer_bounds.x as i32,
+                (target_height - (layer_bounds.y + layer_bounds.height)) as i32,
+                layer_bounds.width as i32,
+                layer_bounds.height as i32,
+            );
+
+            gl.uniform_1_f32(
+                Some(&self.screen_height_location),
+                target_height as f32,
+            );
+        }
+
+        for shadow in shadows {
+            let visible_bounds = shadow.visible_bounds();
+            let (shape, shape_radius) = shadow.shape();
+
+            let matrix: [f32; 16] = (transformation
+                * Transformation::translate(
+                    visible_bounds.x,
+                    visible_bounds.y,
+                )
+                * Transformation::scale(
+                    visible_bounds.width,
+                    visible_bounds.height,
+                ))
+            .into();
+
+            // Fragments are shaded in physical pixels
+            let physical = |bounds: Rectangle| {
+                [bounds.x, bounds.y, bounds.width, bounds.height]
+                    .map(|value| value * scale_factor)
+            };
+
+            unsafe {
+                gl.uniform_matrix_4_f32_slice(
+                    Some(&self.transform_location),
+                    false,
+                    &matrix,
+                );
+                gl.uniform_4_f32_slice(
+                    Some(&self.color_location),
+                    &shadow.color,
+                );
+                gl.uniform_4_f32_slice(
+                    Some(&self.bounds_location),
+                    &physical(shadow.bounds),
+                );
+                gl.uniform_4_f32_slice(
+                    Some(&self.bounds_radius_location),
+                    &shadow.border_radius.map(|radius| radius * scale_factor),
+                );
+                gl.uniform_4_f32_slice(
+                    Some(&self.shape_location),
+                    &physical(shape),
+                );
+                gl.uniform_4_f32_slice(
+                    Some(&self.shape_radius_location),
+                    &shape_radius.map(|radius| radius * scale_factor),
+                );
+                gl.uniform_1_f32(
+                    Some(&self.blur_location),
+                    shadow.blur_radius * scale_factor,
+                );
+                gl.uniform_1_f32(
+                    Some(&self.inset_location),
+                    if shadow.inset { 1.0 } else { 0.0 },
+                );
+                gl.uniform_1_f32(
+                    Some(&self.glow_location),
+                    if shadow.glow { 1.0 } else { 0.0 },
+                );
+
+                gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+            }
+        }
+
+        unsafe {
+            gl.bind_buffer(glow::ARRAY_BUFFER, None);
+            gl.bind_vertex_array(None);
+            gl.use_program(None);
+            gl.disable(glow::SCISSOR_TEST);
+        }
+    }
+}
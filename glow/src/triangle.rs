@@ -190,8 +190,8 @@ impl Pipeline {
                         self.gradient.uniforms.transform = transform;
                     }
 
-                    if &self.gradient.uniforms.gradient != *gradient {
-                        match gradient {
+                    if &self.gradient.uniforms.gradient != gradient.as_ref() {
+                        match gradient.as_ref() {
                             Gradient::Linear(linear) => {
                                 gl.uniform_4_f32(
                                     Some(
@@ -251,7 +251,8 @@ impl Pipeline {
                             }
                         }
 
-                        self.gradient.uniforms.gradient = (*gradient).clone();
+                        self.gradient.uniforms.gradient =
+                            gradient.as_ref().clone();
                     }
 
                     gl.draw_elements_base_vertex(
@@ -0,0 +1,195 @@
+//! Measures the allocations and the time spent generating the layers of a
+//! realistic frame, with and without a [`Pool`], and with and without the
+//! capacities of a layer being estimated up front.
+//!
+//! Run it with `cargo bench -p iced_graphics --bench layers`.
+use iced_graphics::alignment;
+use iced_graphics::layer::{Pool, Settings};
+use iced_graphics::{
+    Background, BorderAlignment, ClipMode, Color, Font, Layer, LineHeight,
+    Point, Primitive, Rectangle, Size, Truncate, Viewport,
+};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const FRAMES: u32 = 1_000;
+
+fn main() {
+    let viewport = Viewport::with_physical_size(Size::new(1920, 1080), 1.0);
+    let primitives = frame(100);
+
+    report("fresh layers", || {
+        let layers = Layer::generate(&primitives, &viewport);
+
+        drop(layers);
+    });
+
+    let mut pool = Pool::new();
+
+    report("pooled layers", || {
+        let layers = Layer::generate_pooled(
+            &primitives,
+            &viewport,
+            Settings::default(),
+            &mut pool,
+        );
+
+        pool.recycle(layers);
+    });
+
+    // Only the capacities of the first layer are estimated, so the same rows
+    // inside a clip grow their layer on demand
+    let rows: Vec<_> = (0..1_000).map(row).collect();
+    let clipped_rows = [Primitive::Clip {
+        bounds: Rectangle::with_size(viewport.logical_size()),
+        antialias: false,
+        mode: ClipMode::Intersect,
+        content: Box::new(Primitive::Group {
+            primitives: (0..1_000).map(row).collect(),
+        }),
+    }];
+
+    report("estimated capacities", || {
+        let layers = Layer::generate(&rows, &viewport);
+
+        drop(layers);
+    });
+
+    report("grown capacities", || {
+        let layers = Layer::generate(&clipped_rows, &viewport);
+
+        drop(layers);
+    });
+}
+
+/// Runs the given frame many times and prints the average amount of
+/// allocations and the average time it took.
+fn report(name: &str, mut frame: impl FnMut()) {
+    // Warm up, filling any pool first
+    frame();
+
+    let allocations = ALLOCATOR.allocations.load(Ordering::Relaxed);
+    let reallocations = ALLOCATOR.reallocations.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    for _ in 0..FRAMES {
+        frame();
+    }
+
+    let elapsed: Duration = start.elapsed() / FRAMES;
+
+    println!(
+        "{name}: {} allocations, {} reallocations, {elapsed:?} per frame",
+        (ALLOCATOR.allocations.load(Ordering::Relaxed) - allocations)
+            / FRAMES as usize,
+        (ALLOCATOR.reallocations.load(Ordering::Relaxed) - reallocations)
+            / FRAMES as usize,
+    );
+}
+
+/// A list of rows, where every tenth row is clipped.
+fn frame(rows: usize) -> Vec<Primitive> {
+    (0..rows)
+        .map(|i| {
+            if i % 10 == 0 {
+                Primitive::Clip {
+                    bounds: row_bounds(i),
+                    antialias: false,
+                    mode: ClipMode::Intersect,
+                    content: Box::new(row(i)),
+                }
+            } else {
+                row(i)
+            }
+        })
+        .collect()
+}
+
+/// A row with a background, a label, and an icon.
+fn row(i: usize) -> Primitive {
+    let bounds = row_bounds(i);
+
+    Primitive::Group {
+        primitives: vec![
+            quad(bounds, Color::from_rgb(0.9, 0.9, 0.9)),
+            text(bounds),
+            quad(
+                Rectangle::new(bounds.position(), Size::new(8.0, 8.0)),
+                Color::BLACK,
+            ),
+        ],
+    }
+}
+
+fn row_bounds(i: usize) -> Rectangle {
+    Rectangle::new(Point::new(0.0, i as f32 * 10.0), Size::new(400.0, 10.0))
+}
+
+fn quad(bounds: Rectangle, color: Color) -> Primitive {
+    Primitive::Quad {
+        bounds,
+        background: Background::Color(color).into(),
+        border_radius: [2.0; 4],
+        border_width: 1.0.into(),
+        border_color: Color::BLACK.into(),
+        border_alignment: BorderAlignment::Inside,
+        corner_smoothing: 0.0,
+    }
+}
+
+fn text(bounds: Rectangle) -> Primitive {
+    Primitive::Text {
+        content: String::from("Lorem ipsum"),
+        bounds,
+        color: Color::BLACK,
+        size: 16.0,
+        letter_spacing: 0.0,
+        line_height: LineHeight::default(),
+        font: Font::Default,
+        horizontal_alignment: alignment::Horizontal::Left,
+        vertical_alignment: alignment::Vertical::Top,
+        truncate: Truncate::None,
+        max_lines: None,
+        background: None,
+        shadow: None,
+        snap_baselines: false,
+    }
+}
+
+/// The system allocator, counting every allocation and reallocation.
+struct Counting {
+    allocations: AtomicUsize,
+    reallocations: AtomicUsize,
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting {
+    allocations: AtomicUsize::new(0),
+    reallocations: AtomicUsize::new(0),
+};
+
+#[allow(unsafe_code)]
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = self.allocations.fetch_add(1, Ordering::Relaxed);
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        let _ = self.reallocations.fetch_add(1, Ordering::Relaxed);
+
+        System.realloc(ptr, layout, new_size)
+    }
+}
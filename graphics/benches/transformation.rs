@@ -0,0 +1,61 @@
+//! Compares transforming 10k points one by one with transforming them in
+//! bulk.
+//!
+//! Run it with `cargo bench -p iced_graphics --bench transformation`.
+use iced_graphics::{Point, Transformation, TranslateScale, Vector};
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const POINTS: usize = 10_000;
+const ITERATIONS: u32 = 10_000;
+
+fn main() {
+    let points: Vec<_> = (0..POINTS)
+        .map(|i| Point::new(i as f32 * 0.5, (POINTS - i) as f32 * 0.25))
+        .collect();
+
+    let mut out = vec![Point::ORIGIN; POINTS];
+
+    let transformation =
+        Transformation::translate(12.0, -3.0) * Transformation::scale(2.0, 0.5);
+
+    report("Transformation::transform_point", || {
+        let transformation = black_box(transformation);
+
+        for (point, out) in points.iter().zip(&mut out) {
+            *out = transformation.transform_point(*point);
+        }
+    });
+
+    report("Transformation::transform_points", || {
+        black_box(transformation).transform_points(&points, &mut out);
+    });
+
+    let translate_scale = TranslateScale::new(Vector::new(5.0, 2.0), 1.5);
+
+    report("TranslateScale::transform_point", || {
+        let translate_scale = black_box(translate_scale);
+
+        for (point, out) in points.iter().zip(&mut out) {
+            *out = translate_scale.transform_point(*point);
+        }
+    });
+
+    report("TranslateScale::transform_points", || {
+        black_box(translate_scale).transform_points(&points, &mut out);
+    });
+}
+
+/// Runs the given closure many times and prints the average time it took.
+fn report(name: &str, mut transform: impl FnMut()) {
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        transform();
+    }
+
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+
+    println!("{name}: {elapsed:?} per {POINTS} points");
+}
@@ -0,0 +1,102 @@
+use crate::{Color, Point};
+
+/// The color of the border of a quad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BorderColor {
+    /// A solid color
+    Solid(Color),
+    /// A linear gradient between two points
+    ///
+    /// The points are expressed in the same coordinate system as the bounds
+    /// of the quad.
+    Gradient {
+        /// The point where the gradient starts
+        start: Point,
+        /// The point where the gradient ends
+        end: Point,
+        /// The color at the start of the gradient
+        start_color: Color,
+        /// The color at the end of the gradient
+        end_color: Color,
+    },
+}
+
+impl Default for BorderColor {
+    fn default() -> Self {
+        BorderColor::Solid(Color::TRANSPARENT)
+    }
+}
+
+impl From<Color> for BorderColor {
+    fn from(color: Color) -> Self {
+        BorderColor::Solid(color)
+    }
+}
+
+/// The width of each side of the border of a quad.
+///
+/// The widths follow the order of the CSS `border-width` attribute: top,
+/// right, bottom, and left.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BorderWidth(pub [f32; 4]);
+
+impl BorderWidth {
+    /// Creates a [`BorderWidth`] with the same width on every side.
+    pub const fn uniform(width: f32) -> Self {
+        Self([width; 4])
+    }
+
+    /// Returns the width of the widest side of the [`BorderWidth`].
+    pub fn max(self) -> f32 {
+        self.0.into_iter().fold(0.0, f32::max)
+    }
+
+    /// Returns whether no side of the [`BorderWidth`] is visible.
+    pub fn is_zero(self) -> bool {
+        self.0.iter().all(|width| *width <= 0.0)
+    }
+}
+
+impl From<f32> for BorderWidth {
+    fn from(width: f32) -> Self {
+        Self::uniform(width)
+    }
+}
+
+impl From<[f32; 4]> for BorderWidth {
+    fn from(widths: [f32; 4]) -> Self {
+        Self(widths)
+    }
+}
+
+/// The position of the border of a quad relative to its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BorderAlignment {
+    /// The border is drawn inside the bounds, so the outer edge of the quad
+    /// stays at its bounds
+    ///
+    /// This is how borders have always been drawn.
+    Inside,
+    /// The border straddles the bounds
+    Center,
+    /// The border is drawn outside the bounds
+    Outside,
+}
+
+impl BorderAlignment {
+    /// Returns how far a border of the given width extends beyond the bounds
+    /// of its quad.
+    pub fn outset(self, border_width: f32) -> f32 {
+        match self {
+            BorderAlignment::Inside => 0.0,
+            BorderAlignment::Center => border_width / 2.0,
+            BorderAlignment::Outside => border_width,
+        }
+    }
+}
+
+impl Default for BorderAlignment {
+    fn default() -> Self {
+        BorderAlignment::Inside
+    }
+}
@@ -0,0 +1,15 @@
+/// The way a clip combines with the clipping bounds of its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipMode {
+    /// The clip is intersected with the clipping bounds of its parent
+    Intersect,
+    /// The clip replaces the clipping bounds of its parent, so its contents
+    /// can escape them; it is still limited to the viewport
+    Replace,
+}
+
+impl Default for ClipMode {
+    fn default() -> Self {
+        ClipMode::Intersect
+    }
+}
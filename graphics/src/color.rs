@@ -0,0 +1,67 @@
+//! Convert colors between the sRGB and linear color spaces.
+use crate::Color;
+
+/// Converts the given sRGB [`Color`] into its linear RGBA components.
+///
+/// The alpha component is kept as is.
+pub fn into_linear(color: Color) -> [f32; 4] {
+    color.into_linear()
+}
+
+/// Converts the given linear RGBA components into an sRGB [`Color`].
+///
+/// This is the inverse of [`into_linear`]. The alpha component is kept as is.
+pub fn from_linear(rgba: [f32; 4]) -> Color {
+    // As described in:
+    // https://en.wikipedia.org/wiki/SRGB#The_forward_transformation_(CIE_XYZ_to_sRGB)
+    fn gamma_component(u: f32) -> f32 {
+        if u < 0.0031308 {
+            u * 12.92
+        } else {
+            1.055 * u.powf(1.0 / 2.4) - 0.055
+        }
+    }
+
+    let [r, g, b, a] = rgba;
+
+    Color::from_rgba(
+        gamma_component(r),
+        gamma_component(g),
+        gamma_component(b),
+        a,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(color: Color) {
+        let result = from_linear(into_linear(color));
+
+        for (a, b) in [
+            (result.r, color.r),
+            (result.g, color.g),
+            (result.b, color.b),
+            (result.a, color.a),
+        ] {
+            assert!((a - b).abs() < 1.0e-5, "{:?} != {:?}", result, color);
+        }
+    }
+
+    #[test]
+    fn colors_round_trip_through_linear() {
+        assert_round_trip(Color::from_rgb(1.0, 0.0, 0.0));
+        assert_round_trip(Color::from_rgba(0.5, 0.5, 0.5, 0.5));
+        assert_round_trip(Color::from_rgb(0.02, 0.03, 0.05));
+    }
+
+    #[test]
+    fn gray_is_darker_in_linear_space() {
+        let [r, g, b, a] = into_linear(Color::from_rgb(0.5, 0.5, 0.5));
+
+        assert!((r - 0.214).abs() < 1.0e-3);
+        assert_eq!((r, a), (g, 1.0));
+        assert_eq!(g, b);
+    }
+}
@@ -7,4 +7,7 @@ pub mod vector;
 
 pub mod storage;
 
+mod filter;
+
+pub use filter::Filter;
 pub use storage::Storage;
@@ -0,0 +1,14 @@
+/// The sampling method used to draw a raster image at a different size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Filter {
+    /// The closest texel is sampled, keeping the edges of pixel art sharp
+    Nearest,
+    /// The closest texels are blended linearly, which suits photos
+    Linear,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::Linear
+    }
+}
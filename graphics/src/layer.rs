@@ -1,20 +1,197 @@
 //! Organize rendering primitives into a flattened list of layers.
+mod arc;
+mod builder;
+mod clip;
+mod hash;
+mod hit;
 mod image;
-mod quad;
+mod overlay;
+mod pool;
+mod shadow;
+mod snapshot;
+mod stats;
 mod text;
+mod tree;
 
 pub mod mesh;
+pub mod quad;
+pub mod validation;
 
-pub use image::Image;
-pub use mesh::Mesh;
+pub use arc::Arc;
+pub use builder::LayerBuilder;
+pub use clip::RoundedClip;
+pub use hash::hash_layers;
+pub use hit::Hit;
+pub use image::{Image, Slice};
+pub use mesh::{Mesh, Mesh3D};
+pub use overlay::{OverlayStyle, PerformanceGraphStyle};
+pub use pool::Pool;
 pub use quad::Quad;
-pub use text::Text;
+pub use shadow::Shadow;
+pub use stats::{Counts, LayerStats};
+pub use text::{Text, TextShadow};
+pub use tree::LayerTree;
+pub use validation::{validate_layers, ValidationError};
 
 use crate::alignment;
+use crate::triangle::{self, AlphaMode};
 use crate::{
-    Background, Font, Point, Primitive, Rectangle, Size, Vector, Viewport,
+    Background, BorderColor, ClipMode, Color, LineHeight, Paint, Point,
+    Primitive, Rectangle, Size, Transformation, TranslateScale, Truncate,
+    Vector, Viewport,
 };
 
+use std::borrow::Cow;
+
+/// The settings used to generate a list of layers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Settings {
+    /// The [`AlphaMode`] of the colors of the generated quads and meshes.
+    ///
+    /// Colors are always linearized first. Then, the colors of quads, borders,
+    /// quad gradients, shadows, arcs, marquees, and tints are encoded in this
+    /// [`AlphaMode`]; with [`AlphaMode::Premultiplied`], they match
+    /// [`Color::into_linear_premultiplied`]. Text colors and image tints are
+    /// always straight.
+    ///
+    /// Mesh vertex colors encoded in a different [`AlphaMode`] are converted.
+    pub alpha_mode: AlphaMode,
+
+    /// Whether the scale of the current transformation is applied to the
+    /// vertices of meshes during generation, with [`Mesh::transformed`].
+    ///
+    /// Otherwise, meshes borrow the buffers of their primitives and only
+    /// their origin is transformed, leaving the rest to the renderer.
+    pub transform_meshes: bool,
+
+    /// Whether the origin of all text is snapped to the nearest physical
+    /// pixel after being transformed.
+    ///
+    /// This keeps static labels crisp at the cost of smooth subpixel motion.
+    /// Text can also opt into snapping its baseline individually.
+    pub snap_text: bool,
+
+    /// The maximum nesting depth of the primitives, if any.
+    ///
+    /// Top-level primitives have a depth of `0`, and the content of every
+    /// group, clip, transformation, or cache is one level deeper. Primitives
+    /// past this depth are skipped, which bounds the work and memory spent on
+    /// absurdly deep trees. By default, the depth is unlimited.
+    pub max_depth: Option<usize>,
+}
+
+/// A primitive waiting to be processed, with its transformation, the index of
+/// its layer, and its depth.
+type Node<'a> = (TranslateScale, &'a Primitive, usize, usize);
+
+/// The state shared by all the primitives of a frame during layer generation.
+#[derive(Debug)]
+struct Frame<'p> {
+    settings: Settings,
+    scale_factor: f32,
+    viewport_size: Size,
+    pool: &'p mut Pool,
+    parents: Vec<Option<usize>>,
+
+    /// The tints whose content is still being processed, as the length of
+    /// the stack before their content, the index of their layer, and their
+    /// linear color.
+    tints: Vec<(usize, usize, [f32; 4])>,
+
+    /// The layers with a tint pushed over them, together with the layer that
+    /// continues them after the tint, once it has been created.
+    continuations: Vec<(usize, Option<usize>)>,
+}
+
+impl Frame<'_> {
+    /// Returns whether primitives at the given depth exceed the maximum depth
+    /// of the [`Settings`].
+    fn is_too_deep(&self, depth: usize) -> bool {
+        matches!(self.settings.max_depth, Some(max_depth) if depth > max_depth)
+    }
+
+    /// Returns the last layer continuing the given one after its tints, and
+    /// whether it has a tint pushed over it that still needs a new layer to
+    /// continue it.
+    fn continuation(&self, mut layer: usize) -> (usize, bool) {
+        loop {
+            match self
+                .continuations
+                .iter()
+                .find(|(tinted, _)| *tinted == layer)
+            {
+                Some((_, Some(next))) => layer = *next,
+                Some((_, None)) => return (layer, true),
+                None => return (layer, false),
+            }
+        }
+    }
+
+    /// Applies the scale of the given transformation to the vertices of the
+    /// given [`Mesh`], if enabled in the [`Settings`].
+    fn transform_mesh<'a>(
+        &self,
+        mesh: Mesh<'a>,
+        transformation: TranslateScale,
+    ) -> Mesh<'a> {
+        if self.settings.transform_meshes {
+            mesh.transformed(Transformation::scale(
+                transformation.scale,
+                transformation.scale,
+            ))
+        } else {
+            mesh
+        }
+    }
+}
+
+/// A conservative estimate of the primitives that end up in a single layer.
+#[derive(Debug, Clone, Copy, Default)]
+struct Capacities {
+    quads: usize,
+    text: usize,
+    meshes: usize,
+    images: usize,
+}
+
+impl Capacities {
+    /// Counts the primitives that the given list adds to its own layer.
+    ///
+    /// The contents of clips are skipped, since they produce new layers.
+    fn estimate(primitives: &[Primitive]) -> Self {
+        let mut capacities = Self::default();
+        let mut stack: Vec<&Primitive> = primitives.iter().collect();
+
+        while let Some(primitive) = stack.pop() {
+            match primitive {
+                Primitive::Group { primitives } => stack.extend(primitives),
+                Primitive::Translate { content, .. }
+                | Primitive::Translate3D { content, .. }
+                | Primitive::Scale { content, .. }
+                | Primitive::Tint { content, .. }
+                | Primitive::ViewportRelative { content, .. } => {
+                    stack.push(content)
+                }
+                Primitive::Cached { cache, .. } => stack.push(cache),
+                Primitive::Quad { .. }
+                | Primitive::Caret { .. }
+                | Primitive::Marquee { .. } => capacities.quads += 1,
+                Primitive::Text { .. } => capacities.text += 1,
+                Primitive::SolidMesh { .. }
+                | Primitive::GradientMesh { .. }
+                | Primitive::Fill { .. }
+                | Primitive::Stroke { .. } => capacities.meshes += 1,
+                Primitive::Image { .. }
+                | Primitive::NinePatch { .. }
+                | Primitive::Svg { .. } => capacities.images += 1,
+                _ => {}
+            }
+        }
+
+        capacities
+    }
+}
+
 /// A group of primitives that should be clipped together.
 #[derive(Debug)]
 pub struct Layer<'a> {
@@ -24,14 +201,64 @@ pub struct Layer<'a> {
     /// The quads of the [`Layer`].
     pub quads: Vec<Quad>,
 
+    /// The gradients painted over the quads of the [`Layer`].
+    pub quad_gradients: Vec<quad::Gradient>,
+
+    /// The images filling the quads of the [`Layer`].
+    ///
+    /// They are drawn right after its quads.
+    pub quad_images: Vec<quad::Image>,
+
+    /// The batches of quads of the [`Layer`].
+    ///
+    /// They are drawn right after its quads.
+    pub quad_batches: Vec<quad::Batch>,
+
+    /// The shadows of the [`Layer`].
+    ///
+    /// They are drawn right before its quads.
+    pub shadows: Vec<Shadow>,
+
+    /// The arcs of the [`Layer`].
+    ///
+    /// They are drawn right after its quads.
+    pub arcs: Vec<Arc>,
+
     /// The triangle meshes of the [`Layer`].
     pub meshes: Vec<Mesh<'a>>,
 
+    /// The 3D triangle meshes of the [`Layer`].
+    ///
+    /// They are drawn right after its meshes.
+    pub meshes_3d: Vec<Mesh3D<'a>>,
+
     /// The text of the [`Layer`].
     pub text: Vec<Text<'a>>,
 
     /// The images of the [`Layer`].
     pub images: Vec<Image>,
+
+    /// The rounded clipping region of the [`Layer`], if any.
+    pub rounded_clip: Option<RoundedClip>,
+
+    /// Whether the edges of the clipping bounds of the [`Layer`] should be
+    /// anti-aliased.
+    pub antialias_clip: bool,
+
+    /// The color composited over everything drawn before the [`Layer`] within
+    /// its bounds, in __linear RGB__ and encoded in the [`AlphaMode`] of the
+    /// [`Settings`], if any.
+    ///
+    /// Generated layers with a tint have no other primitives, since they are
+    /// pushed right after the content of a [`Primitive::Tint`] and its
+    /// nested layers.
+    pub tint: Option<[f32; 4]>,
+
+    /// The keys of the cached primitives processed into the [`Layer`].
+    ///
+    /// A renderer can compare them between frames to skip preparing the
+    /// contents of unchanged caches.
+    pub cache_keys: Vec<u64>,
 }
 
 impl<'a> Layer<'a> {
@@ -40,224 +267,5405 @@ impl<'a> Layer<'a> {
         Self {
             bounds,
             quads: Vec::new(),
+            quad_gradients: Vec::new(),
+            quad_images: Vec::new(),
+            quad_batches: Vec::new(),
+            shadows: Vec::new(),
+            arcs: Vec::new(),
             meshes: Vec::new(),
+            meshes_3d: Vec::new(),
             text: Vec::new(),
             images: Vec::new(),
+            rounded_clip: None,
+            antialias_clip: false,
+            tint: None,
+            cache_keys: Vec::new(),
+        }
+    }
+
+    /// Creates a new [`LayerBuilder`] of a [`Layer`] with the given clipping
+    /// bounds.
+    pub fn builder(bounds: Rectangle) -> LayerBuilder<'a> {
+        LayerBuilder::new(bounds)
+    }
+
+    /// Creates a new [`Layer`] with the given clipping bounds and enough
+    /// capacity to hold the given amount of primitives without reallocating.
+    pub fn with_capacities(
+        bounds: Rectangle,
+        quads: usize,
+        text: usize,
+        meshes: usize,
+        images: usize,
+    ) -> Self {
+        Self {
+            quads: Vec::with_capacity(quads),
+            meshes: Vec::with_capacity(meshes),
+            text: Vec::with_capacity(text),
+            images: Vec::with_capacity(images),
+            ..Self::new(bounds)
         }
     }
 
     /// Creates a new [`Layer`] for the provided overlay text.
     ///
-    /// This can be useful for displaying debug information.
+    /// This can be useful for displaying debug information. The default
+    /// [`OverlayStyle`] is scaled by the scale factor of the [`Viewport`].
     pub fn overlay(lines: &'a [impl AsRef<str>], viewport: &Viewport) -> Self {
+        Self::overlay_with(
+            lines,
+            viewport,
+            OverlayStyle::default().scaled(viewport.scale_factor() as f32),
+        )
+    }
+
+    /// Creates a new [`Layer`] for the provided overlay text, using the given
+    /// [`OverlayStyle`].
+    pub fn overlay_with(
+        lines: &'a [impl AsRef<str>],
+        viewport: &Viewport,
+        style: OverlayStyle,
+    ) -> Self {
         let mut overlay =
             Layer::new(Rectangle::with_size(viewport.logical_size()));
 
+        // The shadow is offset by 1 pixel for the default size of 20
+        let shadow_offset = -style.size / 20.0;
+
+        if let Some(color) = style.panel.filter(|_| !lines.is_empty()) {
+            let padding = style.size / 2.0;
+
+            let longest_line = lines
+                .iter()
+                .map(|line| line.as_ref().chars().count())
+                .max()
+                .unwrap_or(0);
+
+            let width = longest_line as f32 * style.size * 0.6;
+            let height =
+                style.line_spacing * (lines.len() - 1) as f32 + style.size;
+
+            overlay.quads.push(Quad {
+                position: [style.origin.x - padding, style.origin.y - padding],
+                size: [width + padding * 2.0, height + padding * 2.0],
+                color,
+                border_color: [0.0; 4],
+                border_radius: [padding / 2.0; 4],
+                border_width: [0.0; 4],
+                border_only: 0,
+                corner_smoothing: 0.0,
+            });
+        }
+
         for (i, line) in lines.iter().enumerate() {
             let text = Text {
                 content: line.as_ref(),
                 bounds: Rectangle::new(
-                    Point::new(11.0, 11.0 + 25.0 * i as f32),
+                    Point::new(
+                        style.origin.x,
+                        style.origin.y + style.line_spacing * i as f32,
+                    ),
                     Size::INFINITY,
                 ),
-                color: [0.9, 0.9, 0.9, 1.0],
-                size: 20.0,
-                font: Font::Default,
+                color: style.color,
+                size: style.size,
+                letter_spacing: 0.0,
+                line_height: LineHeight::default(),
+                font: style.font,
                 horizontal_alignment: alignment::Horizontal::Left,
                 vertical_alignment: alignment::Vertical::Top,
+                truncate: Truncate::None,
+                max_lines: None,
+                background: None,
+                shadow: Some(TextShadow {
+                    color: style.shadow_color,
+                    offset: Vector::new(shadow_offset, shadow_offset),
+                    blur: 0.0,
+                }),
+                rounded_clip: None,
             };
 
             overlay.text.push(text);
-
-            overlay.text.push(Text {
-                bounds: text.bounds + Vector::new(-1.0, -1.0),
-                color: [0.0, 0.0, 0.0, 1.0],
-                ..text
-            });
         }
 
         overlay
     }
 
-    /// Distributes the given [`Primitive`] and generates a list of layers based
-    /// on its contents.
-    pub fn generate(
-        primitives: &'a [Primitive],
-        viewport: &Viewport,
-    ) -> Vec<Self> {
-        let first_layer =
-            Layer::new(Rectangle::with_size(viewport.logical_size()));
+    /// Creates a new [`Layer`] with a bar graph of the given frame times,
+    /// in milliseconds, using the given [`PerformanceGraphStyle`].
+    ///
+    /// Each sample is drawn as a bar growing from the bottom of the `bounds`,
+    /// from left to right, with a baseline at the target frame time. It pairs
+    /// well with [`Layer::overlay`].
+    pub fn performance_graph(
+        samples: &[f32],
+        bounds: Rectangle,
+        style: PerformanceGraphStyle,
+    ) -> Self {
+        let mut graph = Layer::new(bounds);
 
-        let mut layers = vec![first_layer];
+        let quad = |position: [f32; 2], size: [f32; 2], color| Quad {
+            position,
+            size,
+            color,
+            border_color: [0.0; 4],
+            border_radius: [0.0; 4],
+            border_width: [0.0; 4],
+            border_only: 0,
+            corner_smoothing: 0.0,
+        };
 
-        for primitive in primitives {
-            Self::process_primitive(
-                &mut layers,
-                Vector::new(0.0, 0.0),
-                primitive,
-                0,
-            );
+        if let Some(color) = style.background {
+            graph.quads.push(quad(
+                [bounds.x, bounds.y],
+                [bounds.width, bounds.height],
+                color,
+            ));
         }
 
-        layers
-    }
+        let height = |frame_time: f32| {
+            (frame_time / style.max_frame_time).clamp(0.0, 1.0) * bounds.height
+        };
 
-    fn process_primitive(
-        layers: &mut Vec<Self>,
-        translation: Vector,
-        primitive: &'a Primitive,
-        current_layer: usize,
-    ) {
-        match primitive {
-            Primitive::None => {}
-            Primitive::Group { primitives } => {
-                // TODO: Inspect a bit and regroup (?)
-                for primitive in primitives {
-                    Self::process_primitive(
-                        layers,
-                        translation,
-                        primitive,
-                        current_layer,
-                    )
-                }
-            }
-            Primitive::Text {
-                content,
-                bounds,
-                size,
-                color,
-                font,
-                horizontal_alignment,
-                vertical_alignment,
-            } => {
-                let layer = &mut layers[current_layer];
+        if !samples.is_empty() {
+            let count = samples.len() as f32;
+            let width = ((bounds.width - style.spacing * (count - 1.0))
+                / count)
+                .max(0.0);
 
-                layer.text.push(Text {
-                    content,
-                    bounds: *bounds + translation,
-                    size: *size,
-                    color: color.into_linear(),
-                    font: *font,
-                    horizontal_alignment: *horizontal_alignment,
-                    vertical_alignment: *vertical_alignment,
-                });
-            }
-            Primitive::Quad {
-                bounds,
-                background,
-                border_radius,
-                border_width,
-                border_color,
-            } => {
-                let layer = &mut layers[current_layer];
+            for (i, sample) in samples.iter().enumerate() {
+                let bar = height(*sample);
 
-                // TODO: Move some of these computations to the GPU (?)
-                layer.quads.push(Quad {
-                    position: [
-                        bounds.x + translation.x,
-                        bounds.y + translation.y,
+                graph.quads.push(quad(
+                    [
+                        bounds.x + (width + style.spacing) * i as f32,
+                        bounds.y + bounds.height - bar,
                     ],
-                    size: [bounds.width, bounds.height],
-                    color: match background {
-                        Background::Color(color) => color.into_linear(),
+                    [width, bar],
+                    if *sample > style.target_frame_time {
+                        style.over_budget_color
+                    } else {
+                        style.color
                     },
-                    border_radius: *border_radius,
-                    border_width: *border_width,
-                    border_color: border_color.into_linear(),
-                });
+                ));
             }
-            Primitive::SolidMesh { buffers, size } => {
-                let layer = &mut layers[current_layer];
+        }
 
-                let bounds = Rectangle::new(
-                    Point::new(translation.x, translation.y),
-                    *size,
-                );
+        graph.quads.push(quad(
+            [
+                bounds.x,
+                bounds.y + bounds.height - height(style.target_frame_time),
+            ],
+            [bounds.width, 1.0],
+            style.baseline_color,
+        ));
 
-                // Only draw visible content
-                if let Some(clip_bounds) = layer.bounds.intersection(&bounds) {
-                    layer.meshes.push(Mesh::Solid {
-                        origin: Point::new(translation.x, translation.y),
-                        buffers,
-                        clip_bounds,
-                    });
+        graph
+    }
+
+    /// Returns the opaque quads of the [`Layer`] sorted front-to-back.
+    ///
+    /// This is the reverse of the drawing order, which allows a renderer to
+    /// stop rasterizing early once the viewport is fully covered. Translucent
+    /// quads are left out and must be drawn back-to-front afterwards.
+    ///
+    /// Quads with a gradient background are always considered translucent.
+    pub fn front_to_back_opaque(&self) -> Vec<&Quad> {
+        let has_gradient_background = |i| {
+            self.quad_gradients.iter().any(|gradient| {
+                gradient.quad == i
+                    && gradient.target == quad::Target::Background
+            })
+        };
+
+        self.quads
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(i, quad)| {
+                quad.is_opaque() && !has_gradient_background(*i)
+            })
+            .map(|(_, quad)| quad)
+            .collect()
+    }
+
+    /// Returns the [`LayerStats`] of the given layers.
+    ///
+    /// Its [`Display`](std::fmt::Display) implementation prints a compact
+    /// summary, which can be shown with [`Layer::overlay`].
+    pub fn stats(layers: &[Self]) -> LayerStats {
+        LayerStats::new(layers)
+    }
+
+    /// Returns the top-most quad, image, or text of the given layers that
+    /// contains the given [`Point`], if any.
+    ///
+    /// Layers are tested from last to first, and only where their clipping
+    /// bounds contain the [`Point`]. The rounded corners of quads are taken
+    /// into account.
+    pub fn hit_test(layers: &[Self], point: Point) -> Option<Hit> {
+        hit::test(layers, point)
+    }
+
+    /// Returns the part of the given bounds, already transformed, that is
+    /// visible inside of the [`Layer`], if any.
+    ///
+    /// Primitives without any visible part can be culled.
+    pub fn visible_bounds(&self, transformed: Rectangle) -> Option<Rectangle> {
+        self.bounds.intersection(&transformed)
+    }
+
+    /// Returns the [`Quad`] that composites the tint of the [`Layer`] over
+    /// everything drawn before it, if any.
+    ///
+    /// A renderer can draw it after all the other primitives of the
+    /// [`Layer`].
+    pub fn tint_quad(&self) -> Option<Quad> {
+        self.tint.map(|color| Quad {
+            position: [self.bounds.x, self.bounds.y],
+            size: [self.bounds.width, self.bounds.height],
+            color,
+            border_color: [0.0; 4],
+            border_radius: [0.0; 4],
+            border_width: [0.0; 4],
+            border_only: 0,
+            corner_smoothing: 0.0,
+        })
+    }
+
+    /// Translates the [`Layer`] and all of its primitives by the given
+    /// offset, in logical pixels.
+    ///
+    /// This is a cheap alternative to wrapping the primitives of the
+    /// [`Layer`] in a [`Primitive::Translate`] and generating it again; for
+    /// instance, to scroll some content. The clipping bounds of the [`Layer`]
+    /// are translated as well.
+    pub fn translate(&mut self, offset: Vector) {
+        let translate_point = |[x, y]: [f32; 2]| [x + offset.x, y + offset.y];
+
+        self.bounds = self.bounds + offset;
+
+        for quad in &mut self.quads {
+            quad.position = translate_point(quad.position);
+        }
+
+        for gradient in &mut self.quad_gradients {
+            match &mut gradient.shape {
+                quad::Shape::Linear { start, end } => {
+                    *start = translate_point(*start);
+                    *end = translate_point(*end);
+                }
+                quad::Shape::Radial { center, .. }
+                | quad::Shape::Conic { center, .. } => {
+                    *center = translate_point(*center);
                 }
             }
-            Primitive::GradientMesh {
-                buffers,
-                size,
-                gradient,
-            } => {
-                let layer = &mut layers[current_layer];
+        }
 
-                let bounds = Rectangle::new(
-                    Point::new(translation.x, translation.y),
-                    *size,
-                );
+        for batch in &mut self.quad_batches {
+            for instance in &mut batch.instances {
+                instance.position = translate_point(instance.position);
+            }
+        }
 
-                // Only draw visible content
-                if let Some(clip_bounds) = layer.bounds.intersection(&bounds) {
-                    layer.meshes.push(Mesh::Gradient {
-                        origin: Point::new(translation.x, translation.y),
-                        buffers,
-                        clip_bounds,
-                        gradient,
-                    });
+        for shadow in &mut self.shadows {
+            shadow.bounds = shadow.bounds + offset;
+        }
+
+        for arc in &mut self.arcs {
+            arc.center = translate_point(arc.center);
+        }
+
+        for mesh in &mut self.meshes {
+            match mesh {
+                Mesh::Solid {
+                    origin,
+                    clip_bounds,
+                    ..
+                }
+                | Mesh::Gradient {
+                    origin,
+                    clip_bounds,
+                    ..
+                } => {
+                    *origin = *origin + offset;
+                    *clip_bounds = *clip_bounds + offset;
                 }
             }
-            Primitive::Clip { bounds, content } => {
-                let layer = &mut layers[current_layer];
-                let translated_bounds = *bounds + translation;
+        }
 
-                // Only draw visible content
-                if let Some(clip_bounds) =
-                    layer.bounds.intersection(&translated_bounds)
-                {
-                    let clip_layer = Layer::new(clip_bounds);
-                    layers.push(clip_layer);
+        for mesh in &mut self.meshes_3d {
+            mesh.transformation =
+                Transformation::translate_vector(offset) * mesh.transformation;
+            mesh.clip_bounds = mesh.clip_bounds + offset;
+        }
 
-                    Self::process_primitive(
-                        layers,
-                        translation,
-                        content,
-                        layers.len() - 1,
-                    );
+        for text in &mut self.text {
+            text.bounds = text.bounds + offset;
+
+            if let Some(rounded_clip) = &mut text.rounded_clip {
+                rounded_clip.bounds = rounded_clip.bounds + offset;
+            }
+        }
+
+        for image in &mut self.images {
+            match image {
+                Image::Raster { bounds, .. }
+                | Image::Vector { bounds, .. }
+                | Image::NinePatch { bounds, .. } => {
+                    *bounds = *bounds + offset;
                 }
             }
-            Primitive::Translate {
-                translation: new_translation,
-                content,
-            } => {
-                Self::process_primitive(
-                    layers,
-                    translation + *new_translation,
-                    content,
-                    current_layer,
-                );
+        }
+
+        if let Some(rounded_clip) = &mut self.rounded_clip {
+            rounded_clip.bounds = rounded_clip.bounds + offset;
+        }
+    }
+
+    /// Returns whether the [`Layer`] has nothing to draw.
+    pub fn is_empty(&self) -> bool {
+        self.draw_stages().is_none() && self.tint.is_none()
+    }
+
+    fn can_merge(&self, next: &Self) -> bool {
+        if self.bounds != next.bounds
+            || self.rounded_clip != next.rounded_clip
+            || self.antialias_clip != next.antialias_clip
+            // Each tint is composited once per layer
+            || self.tint.is_some()
+            || next.tint.is_some()
+        {
+            return false;
+        }
+
+        match (self.draw_stages(), next.draw_stages()) {
+            (Some((_, last)), Some((first, _))) => last <= first,
+            _ => true,
+        }
+    }
+
+    /// Returns the first and last non-empty stages of the [`Layer`], following
+    /// the order in which a renderer is expected to draw them.
+    fn draw_stages(&self) -> Option<(usize, usize)> {
+        let stages = [
+            self.shadows.is_empty(),
+            self.quads.is_empty() && self.quad_batches.is_empty(),
+            self.arcs.is_empty(),
+            self.meshes.is_empty(),
+            self.meshes_3d.is_empty(),
+            self.images.is_empty(),
+            self.text.is_empty(),
+        ];
+
+        let first = stages.iter().position(|is_empty| !is_empty)?;
+        let last = stages.iter().rposition(|is_empty| !is_empty)?;
+
+        Some((first, last))
+    }
+
+    fn merge(&mut self, other: Self) {
+        let offset = self.quads.len();
+
+        self.shadows.extend(other.shadows);
+        self.quads.extend(other.quads);
+        self.quad_gradients
+            .extend(other.quad_gradients.into_iter().map(|gradient| {
+                quad::Gradient {
+                    quad: gradient.quad + offset,
+                    ..gradient
+                }
+            }));
+        self.quad_batches.extend(other.quad_batches);
+        self.quad_images
+            .extend(other.quad_images.into_iter().map(|image| quad::Image {
+                quad: image.quad + offset,
+                ..image
+            }));
+        self.arcs.extend(other.arcs);
+        self.meshes.extend(other.meshes);
+        self.meshes_3d.extend(other.meshes_3d);
+        self.images.extend(other.images);
+        self.text.extend(other.text);
+        self.cache_keys.extend(other.cache_keys);
+    }
+
+    /// Merges the shadows of the [`Layer`] that share the same parameters and
+    /// are contained in one another into a single shadow.
+    ///
+    /// Shadows that only partially overlap are never merged, since their
+    /// overlap is darker than their union. Neither are shadows with a
+    /// different color, offset, or blur radius.
+    pub fn merge_shadows(&mut self) {
+        let mut merged: Vec<Shadow> = Vec::with_capacity(self.shadows.len());
+
+        for mut shadow in self.shadows.drain(..) {
+            while let Some((i, union)) =
+                merged.iter().enumerate().find_map(|(i, other)| {
+                    other.merge(&shadow).map(|union| (i, union))
+                })
+            {
+                let _ = merged.remove(i);
+                shadow = union;
             }
-            Primitive::Cached { cache } => {
-                Self::process_primitive(
-                    layers,
-                    translation,
-                    cache,
-                    current_layer,
-                );
+
+            merged.push(shadow);
+        }
+
+        self.shadows = merged;
+    }
+
+    /// Merges the consecutive quads of the [`Layer`] that share the same color
+    /// and are adjacent along a whole edge into a single quad.
+    ///
+    /// This reduces the overdraw of grids and tables, where neighboring cells
+    /// often share the same background. Only consecutive quads are merged to
+    /// keep the drawing order intact. Quads with borders, rounded corners,
+    /// gradients, or images are never merged.
+    pub fn merge_coplanar_quads(&mut self) {
+        let mut is_painted = vec![false; self.quads.len()];
+
+        for index in self
+            .quad_gradients
+            .iter()
+            .map(|gradient| gradient.quad)
+            .chain(self.quad_images.iter().map(|image| image.quad))
+        {
+            if let Some(is_painted) = is_painted.get_mut(index) {
+                *is_painted = true;
             }
-            Primitive::Image { handle, bounds } => {
-                let layer = &mut layers[current_layer];
+        }
 
-                layer.images.push(Image::Raster {
-                    handle: handle.clone(),
-                    bounds: *bounds + translation,
-                });
+        let mut merged: Vec<Quad> = Vec::with_capacity(self.quads.len());
+        let mut indices = Vec::with_capacity(self.quads.len());
+        let mut can_extend = false;
+
+        for (quad, is_painted) in self.quads.drain(..).zip(is_painted) {
+            if can_extend && !is_painted {
+                if let Some(last) = merged.last_mut() {
+                    if let Some(union) = last.merge(&quad) {
+                        *last = union;
+                        indices.push(merged.len() - 1);
+                        continue;
+                    }
+                }
             }
-            Primitive::Svg { handle, bounds } => {
-                let layer = &mut layers[current_layer];
 
-                layer.images.push(Image::Vector {
-                    handle: handle.clone(),
-                    bounds: *bounds + translation,
-                });
+            indices.push(merged.len());
+            merged.push(quad);
+            can_extend = !is_painted;
+        }
+
+        self.quads = merged;
+
+        for gradient in &mut self.quad_gradients {
+            gradient.quad =
+                indices.get(gradient.quad).copied().unwrap_or(gradient.quad);
+        }
+
+        for image in &mut self.quad_images {
+            image.quad = indices.get(image.quad).copied().unwrap_or(image.quad);
+        }
+    }
+
+    /// Distributes the given [`Primitive`] and generates a list of layers based
+    /// on its contents.
+    pub fn generate(
+        primitives: &'a [Primitive],
+        viewport: &Viewport,
+    ) -> Vec<Self> {
+        Self::generate_with(primitives, viewport, Settings::default())
+    }
+
+    /// Distributes the given [`Primitive`] and generates a list of layers based
+    /// on its contents, using the provided [`Settings`].
+    pub fn generate_with(
+        primitives: &'a [Primitive],
+        viewport: &Viewport,
+        settings: Settings,
+    ) -> Vec<Self> {
+        Self::generate_pooled(primitives, viewport, settings, &mut Pool::new())
+    }
+
+    /// Distributes the given [`Primitive`] and generates a list of layers based
+    /// on its contents, reusing the allocations of the given [`Pool`].
+    ///
+    /// The returned layers can be given back to the [`Pool`] with
+    /// [`Pool::recycle`] once they have been drawn.
+    pub fn generate_pooled(
+        primitives: &'a [Primitive],
+        viewport: &Viewport,
+        settings: Settings,
+        pool: &mut Pool,
+    ) -> Vec<Self> {
+        Self::generate_tree(primitives, viewport, settings, pool).into_layers()
+    }
+
+    /// Distributes the given [`Primitive`] and generates a [`LayerTree`]
+    /// with the layers and the clip hierarchy between them.
+    pub fn generate_tree(
+        primitives: &'a [Primitive],
+        viewport: &Viewport,
+        settings: Settings,
+        pool: &mut Pool,
+    ) -> LayerTree<'a> {
+        let mut first_layer =
+            pool.take(Rectangle::with_size(viewport.logical_size()));
+
+        first_layer.reserve(Capacities::estimate(primitives));
+
+        let mut layers = vec![first_layer];
+
+        let mut frame = Frame {
+            settings,
+            scale_factor: viewport.scale_factor() as f32,
+            viewport_size: viewport.logical_size(),
+            pool,
+            parents: vec![None],
+            tints: Vec::new(),
+            continuations: Vec::new(),
+        };
+
+        for primitive in primitives {
+            Self::process_primitive(
+                &mut layers,
+                &mut frame,
+                TranslateScale::IDENTITY,
+                primitive,
+                0,
+                0,
+            );
+        }
+
+        LayerTree::new(layers, frame.parents)
+    }
+
+    /// Distributes the given [`Primitive`] and generates a list of layers based
+    /// on its contents, processing the top-level primitives in parallel.
+    ///
+    /// The children of top-level groups are processed independently, each
+    /// producing its own list of layers. These lists are then merged in order,
+    /// which produces the same result as [`Layer::generate_with`].
+    #[cfg(feature = "rayon")]
+    pub fn generate_parallel(
+        primitives: &'a [Primitive],
+        viewport: &Viewport,
+        settings: Settings,
+    ) -> Vec<Self> {
+        use rayon::prelude::*;
+
+        const CHUNK_SIZE: usize = 256;
+
+        let bounds = Rectangle::with_size(viewport.logical_size());
+
+        // The children of top-level groups keep their depth
+        let roots: Vec<(&'a Primitive, usize)> = primitives
+            .iter()
+            .flat_map(|primitive| match primitive {
+                Primitive::Group { primitives } => {
+                    primitives.iter().map(|primitive| (primitive, 1)).collect()
+                }
+                primitive => vec![(primitive, 0)],
+            })
+            .collect();
+
+        let chunks: Vec<(Vec<Self>, (usize, bool))> = roots
+            .par_chunks(CHUNK_SIZE)
+            .map(|chunk| {
+                let mut pool = Pool::new();
+                let mut layers = vec![pool.take(bounds)];
+
+                let mut frame = Frame {
+                    settings,
+                    scale_factor: viewport.scale_factor() as f32,
+                    viewport_size: viewport.logical_size(),
+                    pool: &mut pool,
+                    parents: vec![None],
+                    tints: Vec::new(),
+                    continuations: Vec::new(),
+                };
+
+                for (primitive, depth) in chunk {
+                    Self::process_primitive(
+                        &mut layers,
+                        &mut frame,
+                        TranslateScale::IDENTITY,
+                        primitive,
+                        0,
+                        *depth,
+                    );
+                }
+
+                let root = frame.continuation(0);
+
+                (layers, root)
+            })
+            .collect();
+
+        let mut layers = vec![Layer::new(bounds)];
+        let mut root = 0;
+        let mut is_root_tinted = false;
+
+        // The first layer of every chunk is the root layer, while the rest
+        // are clip layers that can simply be appended after rebasing
+        for (chunk, (chunk_root, is_chunk_root_tinted)) in chunks {
+            // The root primitives after a top-level tint of a previous chunk
+            // are drawn after it
+            if is_root_tinted {
+                layers.push(Layer::new(bounds));
+                root = layers.len() - 1;
+            }
+
+            let offset = layers.len() - 1;
+            let mut chunk = chunk.into_iter();
+
+            if let Some(chunk_root) = chunk.next() {
+                layers[root].merge(chunk_root);
+            }
+
+            layers.extend(chunk);
+
+            if chunk_root > 0 {
+                root = offset + chunk_root;
+            }
+
+            is_root_tinted = is_chunk_root_tinted;
+        }
+
+        layers
+    }
+
+    fn reserve(&mut self, capacities: Capacities) {
+        self.quads.reserve(capacities.quads);
+        self.meshes.reserve(capacities.meshes);
+        self.text.reserve(capacities.text);
+        self.images.reserve(capacities.images);
+    }
+
+    /// Processes the given [`Primitive`] and all of its children.
+    ///
+    /// The tree is traversed depth-first with an explicit stack.
+    ///
+    /// Primitives nested deeper than the [`Settings::max_depth`] are skipped.
+    fn process_primitive(
+        layers: &mut Vec<Self>,
+        frame: &mut Frame<'_>,
+        transformation: TranslateScale,
+        primitive: &'a Primitive,
+        current_layer: usize,
+        depth: usize,
+    ) {
+        // The stack is reused across primitives and frames
+        let mut stack = frame.pool.take_stack();
+        stack.push((transformation, primitive, current_layer, depth));
+
+        let mut is_truncated = false;
+
+        loop {
+            Self::push_tints(layers, frame, stack.len());
+
+            let (transformation, primitive, current_layer, depth) =
+                match stack.pop() {
+                    Some(node) => node,
+                    None => break,
+                };
+
+            let current_layer =
+                Self::continue_layer(layers, frame, current_layer);
+
+            debug_assert!(
+                current_layer < layers.len(),
+                "primitive targets layer #{} but only {} layers exist",
+                current_layer,
+                layers.len()
+            );
+
+            if frame.is_too_deep(depth) {
+                is_truncated = true;
+                continue;
+            }
+
+            Self::process_node(
+                layers,
+                frame,
+                &mut stack,
+                transformation,
+                primitive,
+                current_layer,
+                depth,
+            );
+
+            // Any layer created by the primitive is a child of its layer
+            frame.parents.resize(layers.len(), Some(current_layer));
+        }
+
+        frame.pool.recycle_stack(stack);
+
+        if is_truncated {
+            log::warn!(
+                "Primitives nested deeper than {:?} levels were skipped",
+                frame.settings.max_depth
+            );
+        }
+    }
+
+    /// Pushes a tinted layer for every pending tint whose content has been
+    /// fully processed; i.e. when the stack is back to its length before the
+    /// content.
+    ///
+    /// Pushing the tinted layer after all the nested layers of its content
+    /// composites the tint exactly once over all of them. The primitives that
+    /// follow the content in the same layer are drawn in a new layer after
+    /// the tint; see [`Layer::continue_layer`].
+    fn push_tints(
+        layers: &mut Vec<Self>,
+        frame: &mut Frame<'_>,
+        stack_len: usize,
+    ) {
+        while let Some(&(len, layer, tint)) = frame.tints.last() {
+            if stack_len > len {
+                break;
+            }
+
+            let _ = frame.tints.pop();
+
+            let (layer, is_tinted) = frame.continuation(layer);
+            let parent = &layers[layer];
+
+            let mut tint_layer = frame.pool.take(parent.bounds);
+            tint_layer.rounded_clip = parent.rounded_clip;
+            tint_layer.antialias_clip = parent.antialias_clip;
+            tint_layer.tint = Some(tint);
+
+            let _ = Self::push_layer(layers, tint_layer);
+            frame.parents.resize(layers.len(), Some(layer));
+
+            if !is_tinted {
+                frame.continuations.push((layer, None));
+            }
+        }
+    }
+
+    /// Returns the index of the layer where the primitives of the given one
+    /// are drawn.
+    ///
+    /// Once a tint is pushed over a layer, the primitives that follow are
+    /// drawn in a new layer right after the tint, with the same clip, so they
+    /// are not tinted. The new layer is only created when needed.
+    fn continue_layer(
+        layers: &mut Vec<Self>,
+        frame: &mut Frame<'_>,
+        layer: usize,
+    ) -> usize {
+        let (layer, is_tinted) = frame.continuation(layer);
+
+        if !is_tinted {
+            return layer;
+        }
+
+        let tinted = &layers[layer];
+
+        let mut continuation = frame.pool.take(tinted.bounds);
+        continuation.rounded_clip = tinted.rounded_clip;
+        continuation.antialias_clip = tinted.antialias_clip;
+
+        let continuation = Self::push_layer(layers, continuation);
+        frame.parents.resize(layers.len(), Some(layer));
+
+        for (tinted, next) in &mut frame.continuations {
+            if *tinted == layer {
+                *next = Some(continuation);
+            }
+        }
+
+        continuation
+    }
+
+    /// Processes a single [`Primitive`], pushing its children to the given
+    /// stack in reverse order so they are popped in the order they are drawn.
+    fn process_node(
+        layers: &mut Vec<Self>,
+        frame: &mut Frame<'_>,
+        stack: &mut Vec<Node<'a>>,
+        transformation: TranslateScale,
+        primitive: &'a Primitive,
+        current_layer: usize,
+        depth: usize,
+    ) {
+        match primitive {
+            Primitive::None => {}
+            Primitive::Group { primitives } => {
+                // TODO: Inspect a bit and regroup (?)
+                stack.extend(primitives.iter().rev().map(|primitive| {
+                    (transformation, primitive, current_layer, depth + 1)
+                }));
+            }
+            Primitive::Text {
+                content,
+                bounds,
+                size,
+                letter_spacing,
+                line_height,
+                color,
+                font,
+                horizontal_alignment,
+                vertical_alignment,
+                truncate,
+                max_lines,
+                background,
+                shadow,
+                snap_baselines,
+            } => {
+                let layer = &mut layers[current_layer];
+                let mut bounds = transformation.transform_rectangle(*bounds);
+
+                let snap = |coordinate: f32| {
+                    (coordinate * frame.scale_factor).round()
+                        / frame.scale_factor
+                };
+
+                if frame.settings.snap_text {
+                    bounds.x = snap(bounds.x);
+                }
+
+                if frame.settings.snap_text || *snap_baselines {
+                    bounds.y = snap(bounds.y);
+                }
+
+                let shadow = shadow.map(|shadow| TextShadow {
+                    color: shadow.color.into_linear(),
+                    offset: transformation.transform_vector(shadow.offset),
+                    blur: transformation.transform_scalar(shadow.blur),
+                });
+
+                // Only draw visible content
+                if !is_text_visible(
+                    layer.bounds,
+                    bounds,
+                    *horizontal_alignment,
+                    *vertical_alignment,
+                    shadow.as_ref(),
+                ) {
+                    return;
+                }
+
+                layer.text.push(Text {
+                    content,
+                    bounds,
+                    size: transformation.transform_scalar(*size),
+                    letter_spacing: transformation
+                        .transform_scalar(*letter_spacing),
+                    line_height: match line_height {
+                        LineHeight::Relative(factor) => {
+                            LineHeight::Relative(*factor)
+                        }
+                        LineHeight::Absolute(height) => LineHeight::Absolute(
+                            transformation.transform_scalar(*height),
+                        ),
+                    },
+                    color: color.into_linear(),
+                    font: *font,
+                    horizontal_alignment: *horizontal_alignment,
+                    vertical_alignment: *vertical_alignment,
+                    truncate: if bounds.width.is_finite() {
+                        *truncate
+                    } else {
+                        Truncate::None
+                    },
+                    max_lines: *max_lines,
+                    background: background.map(Color::into_linear),
+                    shadow,
+                    rounded_clip: layer.rounded_clip,
+                });
+            }
+            Primitive::Quad {
+                bounds,
+                background,
+                border_radius,
+                border_width,
+                border_color,
+                border_alignment,
+                corner_smoothing,
+            } => {
+                let layer = &mut layers[current_layer];
+                let bounds = transformation.transform_rectangle(*bounds);
+
+                // Renderers draw borders inside of quads, so any other
+                // alignment grows the quad to move its border outwards
+                let [top, right, bottom, left] = border_width.0.map(|width| {
+                    transformation
+                        .transform_scalar(border_alignment.outset(width))
+                });
+
+                // A transparent fill only needs its border to be drawn
+                let border_only = !border_width.is_zero()
+                    && matches!(
+                        background,
+                        Paint::Background(Background::Color(color))
+                            if color.a == 0.0
+                    );
+
+                let linear = |direction: &Vector| {
+                    linear_gradient_line(
+                        bounds,
+                        transformation.transform_vector(*direction),
+                    )
+                    .map(|(start, end)| {
+                        quad::Shape::Linear {
+                            start: [start.x, start.y],
+                            end: [end.x, end.y],
+                        }
+                    })
+                };
+
+                let radial = |center: &Point, radius: &f32| {
+                    let center = bounds.position()
+                        + transformation
+                            .transform_vector(Vector::new(center.x, center.y));
+
+                    Some(quad::Shape::Radial {
+                        center: [center.x, center.y],
+                        radius: transformation.transform_scalar(*radius),
+                    })
+                };
+
+                let (shape, stops) = match background {
+                    Paint::Background(Background::Color(_))
+                    | Paint::Image { .. } => (None, Vec::new()),
+                    Paint::Background(Background::LinearGradient {
+                        direction,
+                        start,
+                        end,
+                    }) => (
+                        linear(direction),
+                        gradient_stops(&[(0.0, *start), (1.0, *end)]),
+                    ),
+                    Paint::Background(Background::RadialGradient {
+                        center,
+                        radius,
+                        start,
+                        end,
+                    }) => (
+                        radial(center, radius),
+                        gradient_stops(&[(0.0, *start), (1.0, *end)]),
+                    ),
+                    Paint::LinearGradient { direction, stops } => {
+                        (linear(direction), gradient_stops(stops))
+                    }
+                    Paint::RadialGradient {
+                        center,
+                        radius,
+                        stops,
+                    } => (radial(center, radius), gradient_stops(stops)),
+                    Paint::ConicGradient {
+                        center,
+                        start_angle,
+                        stops,
+                    } => {
+                        let center = transformation.transform_point(*center);
+
+                        (
+                            Some(quad::Shape::Conic {
+                                center: [center.x, center.y],
+                                start_angle: *start_angle,
+                            }),
+                            gradient_stops(stops),
+                        )
+                    }
+                };
+
+                let size =
+                    [bounds.width + left + right, bounds.height + top + bottom];
+
+                // Larger radii would make the arcs of the corners overlap
+                let max_radius = size[0].min(size[1]) / 2.0;
+
+                // TODO: Move some of these computations to the GPU (?)
+                layer.quads.push(Quad {
+                    position: [bounds.x - left, bounds.y - top],
+                    size,
+                    color: AlphaMode::Straight.convert(
+                        match background {
+                            Paint::Background(Background::Color(color)) => {
+                                color.into_linear()
+                            }
+                            // The image is drawn over the quad
+                            Paint::Image { .. } => [0.0; 4],
+                            // Renderers without support for gradients fall
+                            // back to their first stop
+                            _ => stops
+                                .first()
+                                .map_or([0.0; 4], |(_, color)| *color),
+                        },
+                        frame.settings.alpha_mode,
+                    ),
+                    border_radius: {
+                        // Each corner grows with its widest adjacent side
+                        let outsets = [
+                            top.max(left),
+                            top.max(right),
+                            bottom.max(right),
+                            bottom.max(left),
+                        ];
+
+                        std::array::from_fn(|i| {
+                            let radius = transformation
+                                .transform_scalar(border_radius[i]);
+
+                            // Square corners stay square
+                            if radius > 0.0 {
+                                (radius + outsets[i]).min(max_radius)
+                            } else {
+                                radius
+                            }
+                        })
+                    },
+                    border_width: border_width
+                        .0
+                        .map(|width| transformation.transform_scalar(width)),
+                    border_color: AlphaMode::Straight.convert(
+                        match border_color {
+                            BorderColor::Solid(color) => color.into_linear(),
+                            BorderColor::Gradient { start_color, .. } => {
+                                start_color.into_linear()
+                            }
+                        },
+                        frame.settings.alpha_mode,
+                    ),
+                    border_only: u32::from(border_only),
+                    corner_smoothing: *corner_smoothing,
+                });
+
+                if let Paint::Image {
+                    handle,
+                    fit,
+                    repeat,
+                    tile_size,
+                } = background
+                {
+                    layer.quad_images.push(quad::Image {
+                        quad: layer.quads.len() - 1,
+                        handle: handle.clone(),
+                        fit: *fit,
+                        repeat: *repeat,
+                        tile_size: tile_size.map(|size| {
+                            Size::new(
+                                transformation.transform_scalar(size.width),
+                                transformation.transform_scalar(size.height),
+                            )
+                        }),
+                    });
+                }
+
+                if let Some(shape) = shape.filter(|_| !stops.is_empty()) {
+                    layer.quad_gradients.push(quad::Gradient {
+                        quad: layer.quads.len() - 1,
+                        target: quad::Target::Background,
+                        shape,
+                        stops: convert_stops(stops, frame.settings.alpha_mode),
+                    });
+                }
+
+                if let BorderColor::Gradient {
+                    start,
+                    end,
+                    start_color,
+                    end_color,
+                } = border_color
+                {
+                    let start = transformation.transform_point(*start);
+                    let end = transformation.transform_point(*end);
+
+                    layer.quad_gradients.push(quad::Gradient {
+                        quad: layer.quads.len() - 1,
+                        target: quad::Target::Border,
+                        shape: quad::Shape::Linear {
+                            start: [start.x, start.y],
+                            end: [end.x, end.y],
+                        },
+                        stops: convert_stops(
+                            gradient_stops(&[
+                                (0.0, *start_color),
+                                (1.0, *end_color),
+                            ]),
+                            frame.settings.alpha_mode,
+                        ),
+                    });
+                }
+            }
+            Primitive::Caret {
+                position,
+                height,
+                color,
+                blink_phase,
+            } => {
+                let layer = &mut layers[current_layer];
+                let position = transformation.transform_point(*position);
+
+                let alpha = 0.5
+                    * (1.0 + (2.0 * std::f32::consts::PI * blink_phase).cos());
+
+                layer.quads.push(Quad {
+                    position: [position.x, position.y],
+                    size: [
+                        transformation.transform_scalar(1.0),
+                        transformation.transform_scalar(*height),
+                    ],
+                    color: AlphaMode::Straight.convert(
+                        Color {
+                            a: color.a * alpha,
+                            ..*color
+                        }
+                        .into_linear(),
+                        frame.settings.alpha_mode,
+                    ),
+                    border_radius: [0.0; 4],
+                    border_width: [0.0; 4],
+                    border_color: [0.0; 4],
+                    border_only: 0,
+                    corner_smoothing: 0.0,
+                });
+            }
+            Primitive::Arc {
+                center,
+                radius,
+                width,
+                start_angle,
+                progress,
+                color,
+            } => {
+                let layer = &mut layers[current_layer];
+                let center = transformation.transform_point(*center);
+
+                let sweep = progress.clamp(0.0, 1.0) * std::f32::consts::TAU;
+
+                layer.arcs.push(Arc {
+                    center: [center.x, center.y],
+                    radius: transformation.transform_scalar(*radius),
+                    width: transformation.transform_scalar(*width),
+                    start_angle: *start_angle,
+                    end_angle: start_angle + sweep,
+                    color: AlphaMode::Straight.convert(
+                        color.into_linear(),
+                        frame.settings.alpha_mode,
+                    ),
+                });
+            }
+            Primitive::Marquee {
+                bounds,
+                fill,
+                border,
+            } => {
+                let layer = &mut layers[current_layer];
+                let bounds = transformation.transform_rectangle(*bounds);
+
+                let snap = |value: f32| {
+                    (value * frame.scale_factor).round() / frame.scale_factor
+                };
+
+                let x = snap(bounds.x);
+                let y = snap(bounds.y);
+
+                layer.quads.push(Quad {
+                    position: [x, y],
+                    size: [
+                        snap(bounds.x + bounds.width) - x,
+                        snap(bounds.y + bounds.height) - y,
+                    ],
+                    color: AlphaMode::Straight
+                        .convert(fill.into_linear(), frame.settings.alpha_mode),
+                    border_radius: [0.0; 4],
+                    border_width: [1.0 / frame.scale_factor; 4],
+                    border_color: AlphaMode::Straight.convert(
+                        border.into_linear(),
+                        frame.settings.alpha_mode,
+                    ),
+                    border_only: u32::from(fill.a == 0.0),
+                    corner_smoothing: 0.0,
+                });
+            }
+            Primitive::QuadBatch { quads } => {
+                let layer = &mut layers[current_layer];
+
+                layer.quad_batches.push(quad::Batch {
+                    instances: quads
+                        .iter()
+                        .map(|quad| {
+                            let bounds =
+                                transformation.transform_rectangle(quad.bounds);
+
+                            quad::Instance {
+                                position: [bounds.x, bounds.y],
+                                size: [bounds.width, bounds.height],
+                                color: AlphaMode::Straight.convert(
+                                    quad.color.into_linear(),
+                                    frame.settings.alpha_mode,
+                                ),
+                            }
+                        })
+                        .collect(),
+                });
+            }
+            Primitive::Shadow {
+                bounds,
+                color,
+                border_radius,
+                offset,
+                blur_radius,
+                spread_radius,
+            } => {
+                Self::push_shadow(
+                    &mut layers[current_layer],
+                    frame,
+                    transformation,
+                    Shadow {
+                        bounds: *bounds,
+                        color: color.into_linear(),
+                        border_radius: *border_radius,
+                        offset: *offset,
+                        blur_radius: *blur_radius,
+                        spread_radius: *spread_radius,
+                        inset: false,
+                        glow: false,
+                    },
+                );
+            }
+            Primitive::InnerShadow {
+                bounds,
+                color,
+                blur_radius,
+                offset,
+                border_radius,
+            } => {
+                Self::push_shadow(
+                    &mut layers[current_layer],
+                    frame,
+                    transformation,
+                    Shadow {
+                        bounds: *bounds,
+                        color: color.into_linear(),
+                        border_radius: *border_radius,
+                        offset: *offset,
+                        blur_radius: *blur_radius,
+                        spread_radius: 0.0,
+                        inset: true,
+                        glow: false,
+                    },
+                );
+            }
+            Primitive::Glow {
+                bounds,
+                color,
+                radius,
+                inset,
+                border_radius,
+            } => {
+                Self::push_shadow(
+                    &mut layers[current_layer],
+                    frame,
+                    transformation,
+                    Shadow {
+                        bounds: *bounds,
+                        color: color.into_linear(),
+                        border_radius: *border_radius,
+                        offset: Vector::new(0.0, 0.0),
+                        blur_radius: *radius,
+                        spread_radius: 0.0,
+                        inset: *inset,
+                        glow: true,
+                    },
+                );
+            }
+            // Skip degenerate meshes, since some backends fail on empty draws
+            Primitive::SolidMesh { buffers, .. } if buffers.is_empty() => {}
+            Primitive::GradientMesh { buffers, .. } if buffers.is_empty() => {}
+            Primitive::Mesh3D { buffers, .. } if buffers.is_empty() => {}
+            Primitive::SolidMesh { buffers, size } => {
+                let layer = &mut layers[current_layer];
+
+                let bounds = transformation
+                    .transform_rectangle(Rectangle::with_size(*size));
+
+                // Only draw visible content
+                if let Some(clip_bounds) = layer.visible_bounds(bounds) {
+                    let buffers =
+                        if buffers.alpha_mode == frame.settings.alpha_mode {
+                            Cow::Borrowed(buffers)
+                        } else {
+                            Cow::Owned(triangle::Mesh2D {
+                                vertices: buffers
+                                    .vertices
+                                    .iter()
+                                    .map(|vertex| triangle::ColoredVertex2D {
+                                        color: buffers.alpha_mode.convert(
+                                            vertex.color,
+                                            frame.settings.alpha_mode,
+                                        ),
+                                        ..*vertex
+                                    })
+                                    .collect(),
+                                indices: buffers.indices.clone(),
+                                alpha_mode: frame.settings.alpha_mode,
+                            })
+                        };
+
+                    let mesh = Mesh::Solid {
+                        origin: bounds.position(),
+                        buffers,
+                        clip_bounds,
+                    };
+
+                    layer
+                        .meshes
+                        .push(frame.transform_mesh(mesh, transformation));
+                }
+            }
+            Primitive::GradientMesh {
+                buffers,
+                size,
+                gradient,
+            } => {
+                let layer = &mut layers[current_layer];
+
+                let bounds = transformation
+                    .transform_rectangle(Rectangle::with_size(*size));
+
+                // Only draw visible content
+                if let Some(clip_bounds) = layer.visible_bounds(bounds) {
+                    let mesh = Mesh::Gradient {
+                        origin: bounds.position(),
+                        buffers: Cow::Borrowed(buffers),
+                        clip_bounds,
+                        gradient: Cow::Borrowed(gradient),
+                    };
+
+                    layer
+                        .meshes
+                        .push(frame.transform_mesh(mesh, transformation));
+                }
+            }
+            Primitive::Fill {
+                path,
+                fill_rule,
+                color,
+            } => {
+                let (points, indices) = path.tessellate(*fill_rule);
+
+                Self::push_path_mesh(
+                    &mut layers[current_layer],
+                    frame,
+                    transformation,
+                    points,
+                    indices,
+                    *color,
+                );
+            }
+            Primitive::Stroke {
+                path,
+                width,
+                color,
+                line_cap,
+                line_join,
+            } => {
+                // Scaling the tessellated stroke also scales its width
+                let (points, indices) =
+                    path.stroke(*width, *line_cap, *line_join);
+
+                Self::push_path_mesh(
+                    &mut layers[current_layer],
+                    frame,
+                    transformation,
+                    points,
+                    indices,
+                    *color,
+                );
+            }
+            Primitive::Mesh3D {
+                buffers,
+                transformation: projection,
+            } => {
+                let layer = &mut layers[current_layer];
+                let projection =
+                    Transformation::from(transformation) * *projection;
+
+                let (min, max) = buffers.vertices.iter().fold(
+                    (
+                        Point::new(f32::INFINITY, f32::INFINITY),
+                        Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+                    ),
+                    |(min, max), vertex| {
+                        let point = projection.project_point(vertex.position);
+
+                        (
+                            Point::new(min.x.min(point.x), min.y.min(point.y)),
+                            Point::new(max.x.max(point.x), max.y.max(point.y)),
+                        )
+                    },
+                );
+
+                let bounds = Rectangle::new(
+                    min,
+                    Size::new(max.x - min.x, max.y - min.y),
+                );
+
+                // Only draw visible content
+                if let Some(clip_bounds) = layer.visible_bounds(bounds) {
+                    let buffers =
+                        if buffers.alpha_mode == frame.settings.alpha_mode {
+                            Cow::Borrowed(buffers)
+                        } else {
+                            Cow::Owned(triangle::Mesh3D {
+                                vertices: buffers
+                                    .vertices
+                                    .iter()
+                                    .map(|vertex| triangle::ColoredVertex3D {
+                                        color: buffers.alpha_mode.convert(
+                                            vertex.color,
+                                            frame.settings.alpha_mode,
+                                        ),
+                                        ..*vertex
+                                    })
+                                    .collect(),
+                                indices: buffers.indices.clone(),
+                                alpha_mode: frame.settings.alpha_mode,
+                            })
+                        };
+
+                    layer.meshes_3d.push(Mesh3D {
+                        buffers,
+                        transformation: projection,
+                        clip_bounds,
+                    });
+                }
+            }
+            Primitive::Clip {
+                bounds,
+                antialias,
+                mode,
+                content,
+            } => {
+                // The accumulated `TranslateScale` is always axis-aligned,
+                // so the transformed bounds are exact and the clip layer can
+                // use their intersection with the current layer directly.
+                //
+                // The clip bounds are already in target space, which means
+                // the content keeps being processed with the same
+                // transformation.
+                let transformed_bounds =
+                    transformation.transform_rectangle(*bounds);
+
+                // A replacing clip escapes its parent, but not the viewport
+                // bounds of the root layer
+                let parent = match mode {
+                    ClipMode::Intersect => &layers[current_layer],
+                    ClipMode::Replace => &layers[0],
+                };
+
+                // Only draw visible content
+                let mut clip_bounds =
+                    match parent.visible_bounds(transformed_bounds) {
+                        Some(clip_bounds) => clip_bounds,
+                        None => return,
+                    };
+
+                let mut transformation = transformation;
+                let mut content: &Primitive = content;
+                let mut depth = depth + 1;
+
+                // Nested clips that only shrink this one share its layer, so
+                // a deep chain of scrollables produces a single scissor
+                while !frame.is_too_deep(depth) {
+                    match content {
+                        Primitive::Translate {
+                            translation,
+                            content: nested,
+                        } => {
+                            transformation =
+                                transformation.translated(*translation);
+                            content = nested;
+                        }
+                        Primitive::Scale {
+                            scale,
+                            content: nested,
+                        } => {
+                            transformation = transformation.scaled(*scale);
+                            content = nested;
+                        }
+                        Primitive::Clip {
+                            bounds,
+                            antialias: nested_antialias,
+                            mode: ClipMode::Intersect,
+                            content: nested,
+                        } if nested_antialias == antialias => {
+                            match clip_bounds.intersection(
+                                &transformation.transform_rectangle(*bounds),
+                            ) {
+                                Some(bounds) => clip_bounds = bounds,
+                                None => return,
+                            }
+
+                            content = nested;
+                        }
+                        _ => break,
+                    }
+
+                    depth += 1;
+                }
+
+                let mut clip_layer = frame.pool.take(clip_bounds);
+                clip_layer.rounded_clip = parent.rounded_clip;
+                clip_layer.antialias_clip = *antialias;
+                let clip_layer = Self::push_layer(layers, clip_layer);
+
+                stack.push((transformation, content, clip_layer, depth));
+            }
+            Primitive::RoundedClip {
+                bounds,
+                border_radius,
+                content,
+            } => {
+                let layer = &mut layers[current_layer];
+                let transformed_bounds =
+                    transformation.transform_rectangle(*bounds);
+
+                // Only draw visible content
+                if let Some(clip_bounds) =
+                    layer.visible_bounds(transformed_bounds)
+                {
+                    let mut clip_layer = frame.pool.take(clip_bounds);
+                    clip_layer.rounded_clip = Some(RoundedClip {
+                        bounds: transformed_bounds,
+                        border_radius: border_radius.map(|radius| {
+                            transformation.transform_scalar(radius)
+                        }),
+                    });
+                    let clip_layer = Self::push_layer(layers, clip_layer);
+
+                    stack.push((
+                        transformation,
+                        content,
+                        clip_layer,
+                        depth + 1,
+                    ));
+                }
+            }
+            Primitive::Translate {
+                translation,
+                content,
+            } => {
+                stack.push((
+                    transformation.translated(*translation),
+                    content,
+                    current_layer,
+                    depth + 1,
+                ));
+            }
+            // Layers are flat, so the depth of the translation is ignored
+            Primitive::Translate3D {
+                translation: [x, y, _],
+                content,
+            } => {
+                stack.push((
+                    transformation.translated(Vector::new(*x, *y)),
+                    content,
+                    current_layer,
+                    depth + 1,
+                ));
+            }
+            Primitive::ViewportRelative {
+                fractional_bounds,
+                content,
+            } => {
+                let size = frame.viewport_size;
+
+                let bounds = Rectangle {
+                    x: fractional_bounds.x * size.width,
+                    y: fractional_bounds.y * size.height,
+                    width: fractional_bounds.width * size.width,
+                    height: fractional_bounds.height * size.height,
+                };
+
+                let parent = &layers[current_layer];
+
+                // Only draw visible content
+                let clip_bounds = match parent
+                    .visible_bounds(transformation.transform_rectangle(bounds))
+                {
+                    Some(clip_bounds) => clip_bounds,
+                    None => return,
+                };
+
+                let mut clip_layer = frame.pool.take(clip_bounds);
+                clip_layer.rounded_clip = parent.rounded_clip;
+                let clip_layer = Self::push_layer(layers, clip_layer);
+
+                stack.push((
+                    transformation.translated(Vector::new(bounds.x, bounds.y)),
+                    content,
+                    clip_layer,
+                    depth + 1,
+                ));
+            }
+            Primitive::Tint { color, content } => {
+                if is_invisible(color.a) {
+                    stack.push((
+                        transformation,
+                        content,
+                        current_layer,
+                        depth + 1,
+                    ));
+
+                    return;
+                }
+
+                // The tint is pushed once its content is done, so a nested
+                // tint is composited before the tint of its parent
+                frame.tints.push((
+                    stack.len(),
+                    current_layer,
+                    AlphaMode::Straight.convert(
+                        color.into_linear(),
+                        frame.settings.alpha_mode,
+                    ),
+                ));
+
+                stack.push((transformation, content, current_layer, depth + 1));
+            }
+            Primitive::Scale { scale, content } => {
+                stack.push((
+                    transformation.scaled(*scale),
+                    content,
+                    current_layer,
+                    depth + 1,
+                ));
+            }
+            Primitive::Cached { key, cache } => {
+                layers[current_layer].cache_keys.push(*key);
+
+                stack.push((transformation, cache, current_layer, depth + 1));
+            }
+            Primitive::Image {
+                handle,
+                bounds,
+                source,
+                tint,
+                opacity,
+                rotation,
+                filter,
+                fit,
+            } => {
+                let opacity = opacity.clamp(0.0, 1.0);
+
+                if is_invisible(opacity) {
+                    return;
+                }
+
+                let layer = &mut layers[current_layer];
+
+                // The source region is in source pixels, so it is not
+                // transformed
+                layer.images.push(Image::Raster {
+                    handle: handle.clone(),
+                    bounds: transformation.transform_rectangle(*bounds),
+                    source: *source,
+                    tint: tint.into_linear(),
+                    opacity,
+                    rotation: *rotation,
+                    filter: *filter,
+                    fit: *fit,
+                });
+            }
+            Primitive::NinePatch {
+                handle,
+                bounds,
+                insets,
+            } => {
+                let layer = &mut layers[current_layer];
+
+                // The insets are in source pixels, so they are not transformed
+                layer.images.push(Image::NinePatch {
+                    handle: handle.clone(),
+                    bounds: transformation.transform_rectangle(*bounds),
+                    insets: *insets,
+                });
+            }
+            Primitive::Svg {
+                handle,
+                bounds,
+                opacity,
+                color,
+                rotation,
+            } => {
+                let opacity = opacity.clamp(0.0, 1.0);
+
+                if is_invisible(opacity) {
+                    return;
+                }
+
+                let layer = &mut layers[current_layer];
+
+                layer.images.push(Image::Vector {
+                    handle: handle.clone(),
+                    bounds: transformation.transform_rectangle(*bounds),
+                    opacity,
+                    color: color.map(Color::into_linear),
+                    rotation: *rotation,
+                });
+            }
+        }
+    }
+
+    fn push_path_mesh(
+        layer: &mut Self,
+        frame: &Frame<'_>,
+        transformation: TranslateScale,
+        points: Vec<Point>,
+        indices: Vec<u32>,
+        color: Color,
+    ) {
+        if indices.is_empty() {
+            return;
+        }
+
+        let points: Vec<Point> = points
+            .into_iter()
+            .map(|point| transformation.transform_point(point))
+            .collect();
+
+        let (min, max) =
+            points
+                .iter()
+                .fold((points[0], points[0]), |(min, max), point| {
+                    (
+                        Point::new(min.x.min(point.x), min.y.min(point.y)),
+                        Point::new(max.x.max(point.x), max.y.max(point.y)),
+                    )
+                });
+
+        let bounds =
+            Rectangle::new(min, Size::new(max.x - min.x, max.y - min.y));
+
+        // Only draw visible content
+        if let Some(clip_bounds) = layer.visible_bounds(bounds) {
+            let color = AlphaMode::Straight
+                .convert(color.into_linear(), frame.settings.alpha_mode);
+
+            layer.meshes.push(Mesh::Solid {
+                origin: min,
+                buffers: Cow::Owned(triangle::Mesh2D {
+                    vertices: points
+                        .into_iter()
+                        .map(|point| triangle::ColoredVertex2D {
+                            position: [point.x - min.x, point.y - min.y],
+                            color,
+                        })
+                        .collect(),
+                    indices,
+                    alpha_mode: frame.settings.alpha_mode,
+                }),
+                clip_bounds,
+            });
+        }
+    }
+
+    /// Pushes the given [`Layer`] and returns its index, which can be used as
+    /// the current layer of its content.
+    fn push_layer(layers: &mut Vec<Self>, layer: Self) -> usize {
+        layers.push(layer);
+        layers.len() - 1
+    }
+
+    fn push_shadow(
+        layer: &mut Self,
+        frame: &Frame<'_>,
+        transformation: TranslateScale,
+        shadow: Shadow,
+    ) {
+        let shadow = Shadow {
+            bounds: transformation.transform_rectangle(shadow.bounds),
+            color: AlphaMode::Straight
+                .convert(shadow.color, frame.settings.alpha_mode),
+            border_radius: shadow
+                .border_radius
+                .map(|radius| transformation.transform_scalar(radius)),
+            offset: transformation.transform_vector(shadow.offset),
+            blur_radius: transformation.transform_scalar(shadow.blur_radius),
+            spread_radius: transformation
+                .transform_scalar(shadow.spread_radius),
+            inset: shadow.inset,
+            glow: shadow.glow,
+        };
+
+        // Only draw visible content
+        if layer.visible_bounds(shadow.visible_bounds()).is_some() {
+            layer.shadows.push(shadow);
+        }
+    }
+}
+
+/// Returns whether text with the given aligned bounds, or its shadow, may be
+/// visible within the given layer bounds.
+///
+/// Text is only culled along the axes where its bounds are finite, since
+/// infinite bounds do not tell where its glyphs end.
+fn is_text_visible(
+    layer: Rectangle,
+    bounds: Rectangle,
+    horizontal_alignment: alignment::Horizontal,
+    vertical_alignment: alignment::Vertical,
+    shadow: Option<&TextShadow>,
+) -> bool {
+    let overlaps = |start: f32, length: f32, layer_start, layer_length| {
+        !length.is_finite()
+            || (start < layer_start + layer_length
+                && layer_start < start + length)
+    };
+
+    let x = match horizontal_alignment {
+        alignment::Horizontal::Left => bounds.x,
+        alignment::Horizontal::Center => bounds.x - bounds.width / 2.0,
+        alignment::Horizontal::Right => bounds.x - bounds.width,
+    };
+
+    let y = match vertical_alignment {
+        alignment::Vertical::Top => bounds.y,
+        alignment::Vertical::Center => bounds.y - bounds.height / 2.0,
+        alignment::Vertical::Bottom => bounds.y - bounds.height,
+    };
+
+    let is_visible = |offset: Vector, blur: f32| {
+        overlaps(
+            x + offset.x - blur,
+            bounds.width + blur * 2.0,
+            layer.x,
+            layer.width,
+        ) && overlaps(
+            y + offset.y - blur,
+            bounds.height + blur * 2.0,
+            layer.y,
+            layer.height,
+        )
+    };
+
+    is_visible(Vector::new(0.0, 0.0), 0.0)
+        || matches!(
+            shadow,
+            Some(shadow) if is_visible(shadow.offset, shadow.blur)
+        )
+}
+
+/// Returns whether the given opacity rounds to zero in an 8-bit target.
+fn is_invisible(opacity: f32) -> bool {
+    (opacity * 255.0).round() == 0.0
+}
+
+/// Returns the start and end points of a linear gradient spanning the given
+/// bounds along the given direction, like CSS does.
+///
+/// The gradient line passes through the center of the bounds and is long
+/// enough for its perpendiculars at both ends to touch the opposite corners.
+fn linear_gradient_line(
+    bounds: Rectangle,
+    direction: Vector,
+) -> Option<(Point, Point)> {
+    let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+
+    if length == 0.0 || !length.is_finite() {
+        return None;
+    }
+
+    let direction = direction * (1.0 / length);
+    let half_extent = (bounds.width * direction.x.abs()
+        + bounds.height * direction.y.abs())
+        / 2.0;
+
+    let center = bounds.center();
+    let offset = direction * half_extent;
+
+    Some((center - offset, center + offset))
+}
+
+/// Returns the given color stops sorted by offset, with their offsets clamped
+/// to `[0, 1]` and their colors in __linear RGB__.
+///
+/// The sort is stable, so stops sharing an offset keep their order and
+/// produce a hard transition.
+fn gradient_stops(stops: &[(f32, Color)]) -> Vec<(f32, [f32; 4])> {
+    let mut stops: Vec<_> = stops
+        .iter()
+        .map(|(offset, color)| (offset.clamp(0.0, 1.0), color.into_linear()))
+        .collect();
+
+    stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    stops
+}
+
+/// Converts the colors of the given linear color stops from straight alpha to
+/// the given [`AlphaMode`].
+fn convert_stops(
+    stops: Vec<(f32, [f32; 4])>,
+    alpha_mode: AlphaMode,
+) -> Vec<(f32, [f32; 4])> {
+    stops
+        .into_iter()
+        .map(|(offset, color)| {
+            (offset, AlphaMode::Straight.convert(color, alpha_mode))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::image::Filter;
+    use crate::QuadInstance;
+    use crate::{BorderAlignment, BorderWidth, Font, ImageFit, ImageRepeat};
+    use iced_native::{image, svg};
+
+    fn viewport() -> Viewport {
+        Viewport::with_physical_size(Size::new(800, 600), 1.0)
+    }
+
+    fn tree(primitives: &[Primitive]) -> LayerTree<'_> {
+        Layer::generate_tree(
+            primitives,
+            &viewport(),
+            Settings::default(),
+            &mut Pool::new(),
+        )
+    }
+
+    fn text(bounds: Rectangle) -> Primitive {
+        Primitive::Text {
+            content: String::from("Hello"),
+            bounds,
+            color: Color::BLACK,
+            size: 20.0,
+            letter_spacing: 0.0,
+            line_height: LineHeight::default(),
+            font: Font::Default,
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Top,
+            truncate: Truncate::None,
+            max_lines: None,
+            background: None,
+            shadow: None,
+            snap_baselines: false,
+        }
+    }
+
+    #[test]
+    fn text_in_rounded_clip_carries_corner_radii() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 50.0));
+        let primitives = [Primitive::RoundedClip {
+            bounds,
+            border_radius: [1.0, 2.0, 3.0, 4.0],
+            content: Box::new(text(bounds)),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(
+            layers[1].text[0].rounded_clip,
+            Some(RoundedClip {
+                bounds,
+                border_radius: [1.0, 2.0, 3.0, 4.0],
+            })
+        );
+        assert_eq!(layers[1].bounds, bounds);
+    }
+
+    fn image(tint: Color, opacity: f32) -> Primitive {
+        Primitive::Image {
+            handle: image::Handle::from_pixels(1, 1, vec![255; 4]),
+            bounds: Rectangle::new(Point::ORIGIN, Size::new(10.0, 10.0)),
+            source: None,
+            tint,
+            opacity,
+            rotation: 0.0,
+            filter: Filter::default(),
+            fit: ImageFit::Fill,
+        }
+    }
+
+    #[test]
+    fn image_tint_is_linearized() {
+        let tint = Color::from_rgb(0.5, 0.2, 1.0);
+        let primitives = [image(tint, 1.0)];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].images[0] {
+            Image::Raster { tint: linear, .. } => {
+                assert_eq!(*linear, tint.into_linear());
+            }
+            Image::Vector { .. } | Image::NinePatch { .. } => {
+                panic!("expected a raster image")
+            }
+        }
+    }
+
+    #[test]
+    fn white_image_tint_is_identity() {
+        let primitives = [image(Color::WHITE, 1.0)];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].images[0] {
+            Image::Raster { tint, .. } => assert_eq!(*tint, [1.0; 4]),
+            Image::Vector { .. } | Image::NinePatch { .. } => {
+                panic!("expected a raster image")
+            }
+        }
+    }
+
+    #[test]
+    fn caret_scales_and_blinks() {
+        let caret = |blink_phase| Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Caret {
+                position: Point::new(5.0, 5.0),
+                height: 16.0,
+                color: Color::BLACK,
+                blink_phase,
+            }),
+        };
+
+        let primitives = [caret(0.0), caret(0.5)];
+        let layers = Layer::generate(&primitives, &viewport());
+        let quads = &layers[0].quads;
+
+        assert_eq!(quads[0].position, [10.0, 10.0]);
+        assert_eq!(quads[0].size, [2.0, 32.0]);
+        assert_eq!(quads[0].color[3], 1.0);
+        assert!(quads[1].color[3].abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn image_opacity_is_clamped() {
+        let primitives = [image(Color::WHITE, 2.0), image(Color::WHITE, 0.5)];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        let opacities: Vec<f32> = layers[0]
+            .images
+            .iter()
+            .map(|image| match image {
+                Image::Raster { opacity, .. }
+                | Image::Vector { opacity, .. } => *opacity,
+                Image::NinePatch { .. } => 1.0,
+            })
+            .collect();
+
+        assert_eq!(opacities, vec![1.0, 0.5]);
+    }
+
+    #[test]
+    fn invisible_images_are_skipped() {
+        let primitives = [
+            image(Color::WHITE, -1.0),
+            image(Color::WHITE, 0.001),
+            image(Color::WHITE, 0.01),
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers[0].images.len(), 1);
+    }
+
+    #[test]
+    fn straight_mesh_colors_are_premultiplied_like_quads() {
+        let color = Color::from_rgba(1.0, 1.0, 1.0, 0.5);
+        let bounds = Rectangle::with_size(Size::new(10.0, 10.0));
+
+        let primitives = [
+            Primitive::Quad {
+                bounds,
+                background: Background::Color(color).into(),
+                border_radius: [0.0; 4],
+                border_width: 0.0.into(),
+                border_color: Color::TRANSPARENT.into(),
+                border_alignment: BorderAlignment::Inside,
+                corner_smoothing: 0.0,
+            },
+            Primitive::SolidMesh {
+                buffers: triangle::Mesh2D {
+                    vertices: vec![
+                        triangle::ColoredVertex2D {
+                            position: [0.0, 0.0],
+                            color: color.into_linear(),
+                        };
+                        3
+                    ],
+                    indices: vec![0, 1, 2],
+                    alpha_mode: AlphaMode::Straight,
+                },
+                size: bounds.size(),
+            },
+        ];
+
+        let layers = Layer::generate_with(
+            &primitives,
+            &viewport(),
+            Settings {
+                alpha_mode: AlphaMode::Premultiplied,
+                ..Settings::default()
+            },
+        );
+
+        let quad_color = layers[0].quads[0].color;
+
+        match &layers[0].meshes[0] {
+            Mesh::Solid { buffers, .. } => {
+                assert_eq!(buffers.alpha_mode, AlphaMode::Premultiplied);
+                assert_eq!(buffers.vertices[0].color, quad_color);
+                assert_eq!(quad_color, [0.5, 0.5, 0.5, 0.5]);
+            }
+            Mesh::Gradient { .. } => panic!("expected a solid mesh"),
+        }
+    }
+
+    fn svg(color: Option<Color>) -> Primitive {
+        Primitive::Svg {
+            handle: svg::Handle::from_memory(Vec::new()),
+            bounds: Rectangle::new(Point::new(1.0, 2.0), Size::new(3.0, 4.0)),
+            opacity: 1.0,
+            color,
+            rotation: 0.0,
+        }
+    }
+
+    #[test]
+    fn svg_color_is_linearized() {
+        let color = Color::from_rgb(0.2, 0.4, 0.6);
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(10.0, 10.0),
+            content: Box::new(svg(Some(color))),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].images[0] {
+            Image::Vector {
+                color: linear,
+                bounds,
+                ..
+            } => {
+                assert_eq!(*linear, Some(color.into_linear()));
+                assert_eq!(bounds.position(), Point::new(11.0, 12.0));
+            }
+            Image::Raster { .. } | Image::NinePatch { .. } => {
+                panic!("expected a vector image")
+            }
+        }
+    }
+
+    #[test]
+    fn svg_keeps_its_colors_by_default() {
+        let primitives = [svg(None)];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].images[0] {
+            Image::Vector { color, .. } => assert_eq!(*color, None),
+            Image::Raster { .. } | Image::NinePatch { .. } => {
+                panic!("expected a vector image")
+            }
+        }
+    }
+
+    #[test]
+    fn image_rotation_is_carried_through() {
+        let rotation = std::f32::consts::FRAC_PI_2;
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Image {
+                handle: image::Handle::from_pixels(1, 1, vec![255; 4]),
+                bounds: Rectangle::new(Point::ORIGIN, Size::new(10.0, 20.0)),
+                source: None,
+                tint: Color::WHITE,
+                opacity: 1.0,
+                rotation,
+                filter: Filter::default(),
+                fit: ImageFit::Fill,
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].images[0] {
+            Image::Raster {
+                rotation: layer_rotation,
+                bounds,
+                ..
+            } => {
+                assert_eq!(*layer_rotation, rotation);
+                assert_eq!(bounds.size(), Size::new(20.0, 40.0));
+            }
+            Image::Vector { .. } | Image::NinePatch { .. } => {
+                panic!("expected a raster image")
+            }
+        }
+    }
+
+    #[test]
+    fn performance_graph_bars_follow_samples() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 20.0), Size::new(39.0, 100.0));
+
+        let style = PerformanceGraphStyle {
+            background: None,
+            target_frame_time: 10.0,
+            max_frame_time: 20.0,
+            spacing: 1.0,
+            ..PerformanceGraphStyle::default()
+        };
+
+        let samples = [5.0, 10.0, 15.0, 40.0];
+        let graph = Layer::performance_graph(&samples, bounds, style);
+
+        let (baseline, bars) = graph.quads.split_last().unwrap();
+
+        assert_eq!(bars.len(), samples.len());
+        assert_eq!(
+            bars.iter().map(|bar| bar.size[1]).collect::<Vec<_>>(),
+            [25.0, 50.0, 75.0, 100.0]
+        );
+        assert_eq!(
+            bars.iter().map(|bar| bar.position[0]).collect::<Vec<_>>(),
+            [10.0, 20.0, 30.0, 40.0]
+        );
+        assert!(bars
+            .iter()
+            .all(|bar| bar.position[1] + bar.size[1] == 120.0));
+        assert_eq!(bars[1].color, style.color);
+        assert_eq!(bars[2].color, style.over_budget_color);
+
+        assert_eq!(baseline.position, [10.0, 70.0]);
+        assert_eq!(baseline.size, [39.0, 1.0]);
+    }
+
+    #[test]
+    fn image_filter_is_kept() {
+        assert_eq!(Filter::default(), Filter::Linear);
+
+        let mut nearest = image(Color::WHITE, 1.0);
+
+        if let Primitive::Image { filter, .. } = &mut nearest {
+            *filter = Filter::Nearest;
+        }
+
+        let primitives = [image(Color::WHITE, 1.0), nearest];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        let filters: Vec<_> = layers[0]
+            .images
+            .iter()
+            .map(|image| match image {
+                Image::Raster { filter, .. } => *filter,
+                Image::Vector { .. } | Image::NinePatch { .. } => {
+                    panic!("expected a raster image")
+                }
+            })
+            .collect();
+
+        assert_eq!(filters, [Filter::Linear, Filter::Nearest]);
+    }
+
+    #[test]
+    fn image_source_is_not_transformed() {
+        let source =
+            Rectangle::new(Point::new(16.0, 0.0), Size::new(16.0, 16.0));
+
+        let sprite = |source| Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Image {
+                handle: image::Handle::from_pixels(1, 1, vec![255; 4]),
+                bounds: Rectangle::new(Point::ORIGIN, Size::new(10.0, 20.0)),
+                source,
+                tint: Color::WHITE,
+                opacity: 1.0,
+                rotation: 0.0,
+                filter: Filter::default(),
+                fit: ImageFit::Fill,
+            }),
+        };
+
+        let primitives = [sprite(Some(source)), sprite(None)];
+        let layers = Layer::generate(&primitives, &viewport());
+
+        let sources: Vec<_> = layers[0]
+            .images
+            .iter()
+            .map(|image| match image {
+                Image::Raster { bounds, source, .. } => {
+                    assert_eq!(bounds.size(), Size::new(20.0, 40.0));
+
+                    *source
+                }
+                Image::Vector { .. } | Image::NinePatch { .. } => {
+                    panic!("expected a raster image")
+                }
+            })
+            .collect();
+
+        assert_eq!(sources, [Some(source), None]);
+    }
+
+    #[test]
+    fn marquee_border_snaps_to_device_grid() {
+        let primitives = [Primitive::Marquee {
+            bounds: Rectangle {
+                x: 10.3,
+                y: 10.1,
+                width: 20.0,
+                height: 20.0,
+            },
+            fill: Color::from_rgba(0.0, 0.0, 1.0, 0.2),
+            border: Color::from_rgb(0.0, 0.0, 1.0),
+        }];
+
+        let viewport = Viewport::with_physical_size(Size::new(800, 600), 2.0);
+        let layers = Layer::generate(&primitives, &viewport);
+        let quad = &layers[0].quads[0];
+
+        assert_eq!(quad.position, [10.5, 10.0]);
+        assert_eq!(quad.size, [20.0, 20.0]);
+        assert_eq!(quad.border_width, [0.5; 4]);
+        assert_eq!(
+            quad.color,
+            Color::from_rgba(0.0, 0.0, 1.0, 0.2).into_linear()
+        );
+    }
+
+    fn shadow(bounds: Rectangle, inset: bool) -> Primitive {
+        let color = Color::from_rgba(0.0, 0.0, 0.0, 0.5);
+        let offset = Vector::new(2.0, 2.0);
+        let border_radius = [4.0; 4];
+        let blur_radius = 10.0;
+
+        if inset {
+            Primitive::InnerShadow {
+                bounds,
+                color,
+                blur_radius,
+                offset,
+                border_radius,
+            }
+        } else {
+            Primitive::Shadow {
+                bounds,
+                color,
+                border_radius,
+                offset,
+                blur_radius,
+                spread_radius: 0.0,
+            }
+        }
+    }
+
+    #[test]
+    fn inner_shadow_is_inset_and_scaled() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(shadow(bounds, true)),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let shadow = &layers[0].shadows[0];
+
+        assert!(shadow.inset);
+        assert_eq!(shadow.blur_radius, 20.0);
+        assert_eq!(shadow.offset, Vector::new(4.0, 4.0));
+        assert_eq!(shadow.border_radius, [8.0; 4]);
+        assert_eq!(shadow.visible_bounds(), bounds * 2.0);
+    }
+
+    #[test]
+    fn shadow_shapes_are_spread_outwards_unless_inset() {
+        let shadow = |inset| Shadow {
+            bounds: Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(50.0, 50.0),
+            ),
+            color: [0.0, 0.0, 0.0, 1.0],
+            border_radius: [8.0, 2.0, 0.0, 4.0],
+            offset: Vector::new(5.0, 0.0),
+            blur_radius: 10.0,
+            spread_radius: 4.0,
+            inset,
+            glow: false,
+        };
+
+        assert_eq!(
+            shadow(false).shape(),
+            (
+                Rectangle::new(Point::new(11.0, 6.0), Size::new(58.0, 58.0)),
+                [12.0, 6.0, 4.0, 8.0]
+            )
+        );
+        assert_eq!(
+            shadow(true).shape(),
+            (
+                Rectangle::new(Point::new(19.0, 14.0), Size::new(42.0, 42.0)),
+                [4.0, 0.0, 0.0, 0.0]
+            )
+        );
+    }
+
+    #[test]
+    fn inner_shadows_are_not_expanded_beyond_bounds() {
+        // Right outside of the viewport, but within reach of the blur
+        let bounds =
+            Rectangle::new(Point::new(805.0, 0.0), Size::new(50.0, 50.0));
+
+        let primitives = [shadow(bounds, true), shadow(bounds, false)];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers[0].shadows.len(), 1);
+        assert!(!layers[0].shadows[0].inset);
+    }
+
+    #[test]
+    fn glows_are_transformed() {
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(Primitive::Glow {
+                    bounds: Rectangle::new(
+                        Point::new(10.0, 10.0),
+                        Size::new(50.0, 20.0),
+                    ),
+                    color: Color::from_rgb(0.0, 0.5, 1.0),
+                    radius: 4.0,
+                    inset: false,
+                    border_radius: [3.0; 4],
+                }),
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let glow = &layers[0].shadows[0];
+
+        assert!(glow.glow);
+        assert_eq!(
+            glow.bounds,
+            Rectangle::new(Point::new(25.0, 25.0), Size::new(100.0, 40.0))
+        );
+        assert_eq!(glow.blur_radius, 8.0);
+        assert_eq!(glow.border_radius, [6.0; 4]);
+        assert_eq!(glow.offset, Vector::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn inset_glows_stay_within_their_bounds() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let glow = |inset| Primitive::Glow {
+            bounds,
+            color: Color::WHITE,
+            radius: 5.0,
+            inset,
+            border_radius: [0.0; 4],
+        };
+
+        let primitives = [glow(true), glow(false)];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let [inset, outset] = [&layers[0].shadows[0], &layers[0].shadows[1]];
+
+        assert!(inset.inset && !outset.inset);
+        assert_eq!(inset.visible_bounds(), bounds);
+        assert_eq!(
+            outset.visible_bounds(),
+            Rectangle::new(Point::new(5.0, 5.0), Size::new(60.0, 60.0))
+        );
+        assert_eq!(
+            primitives[0].bounds(Transformation::identity()),
+            Some(bounds)
+        );
+        assert_eq!(
+            primitives[1].bounds(Transformation::identity()),
+            Some(outset.visible_bounds())
+        );
+    }
+
+    #[test]
+    fn pooled_layers_equal_fresh_layers() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let primitives = [
+            text(bounds),
+            Primitive::Clip {
+                bounds,
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(Primitive::Group {
+                    primitives: vec![text(bounds), shadow(bounds, false)],
+                }),
+            },
+        ];
+
+        let fresh = Layer::generate(&primitives, &viewport());
+
+        let mut pool = Pool::new();
+
+        for _ in 0..2 {
+            let pooled = Layer::generate_pooled(
+                &primitives,
+                &viewport(),
+                Settings::default(),
+                &mut pool,
+            );
+
+            assert_eq!(format!("{:?}", pooled), format!("{:?}", fresh));
+
+            pool.recycle(pooled);
+        }
+    }
+
+    #[test]
+    fn layer_trees_report_the_parent_of_each_clip() {
+        let clip = |x, content| Primitive::Clip {
+            bounds: Rectangle::new(Point::new(x, x), Size::new(100.0, 100.0)),
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(content),
+        };
+
+        let bounds =
+            Rectangle::new(Point::new(20.0, 20.0), Size::new(10.0, 10.0));
+
+        let primitives = [
+            clip(
+                10.0,
+                Primitive::Group {
+                    primitives: vec![text(bounds), clip(15.0, text(bounds))],
+                },
+            ),
+            clip(0.0, text(bounds)),
+        ];
+
+        let tree = Layer::generate_tree(
+            &primitives,
+            &viewport(),
+            Settings::default(),
+            &mut Pool::new(),
+        );
+
+        assert_eq!(tree.layers().len(), 4);
+        assert_eq!(tree.parents(), [None, Some(0), Some(1), Some(0)]);
+        assert_eq!(tree.parent(2), Some(1));
+        assert_eq!(tree.children(0).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(tree.children(2).count(), 0);
+    }
+
+    #[test]
+    fn clipped_content_is_added_to_its_clip_layer() {
+        let text_at = |x| {
+            text(Rectangle::new(Point::new(x, 20.0), Size::new(10.0, 10.0)))
+        };
+
+        let clip = |antialias, content| Primitive::Clip {
+            bounds: Rectangle::new(Point::ORIGIN, Size::new(100.0, 100.0)),
+            antialias,
+            mode: ClipMode::Intersect,
+            content: Box::new(content),
+        };
+
+        let primitives = [
+            text_at(0.0),
+            clip(
+                false,
+                Primitive::Group {
+                    primitives: vec![
+                        text_at(10.0),
+                        clip(true, text_at(20.0)),
+                        text_at(30.0),
+                    ],
+                },
+            ),
+            text_at(40.0),
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        let origins = |layer: &Layer<'_>| -> Vec<f32> {
+            layer.text.iter().map(|text| text.bounds.x).collect()
+        };
+
+        assert_eq!(layers.len(), 3);
+        assert_eq!(origins(&layers[0]), [0.0, 40.0]);
+        assert_eq!(origins(&layers[1]), [10.0, 30.0]);
+        assert_eq!(origins(&layers[2]), [20.0]);
+    }
+
+    #[test]
+    fn translations_in_3d_match_flat_translations() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let flat = [Primitive::Translate {
+            translation: Vector::new(5.0, -5.0),
+            content: Box::new(text(bounds)),
+        }];
+
+        let deep = [Primitive::Translate3D {
+            translation: [5.0, -5.0, 10.0],
+            content: Box::new(text(bounds)),
+        }];
+
+        assert_eq!(
+            Layer::to_snapshot_string(&Layer::generate(&deep, &viewport())),
+            Layer::to_snapshot_string(&Layer::generate(&flat, &viewport()))
+        );
+    }
+
+    #[test]
+    fn translated_layers_move_their_clip_bounds() {
+        let clip =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 100.0));
+        let quad = Rectangle::new(Point::new(20.0, 30.0), Size::new(5.0, 5.0));
+
+        let primitives = [clipped_quad(clip, quad)];
+
+        let mut layers = Layer::generate(&primitives, &viewport());
+        let offset = Vector::new(0.0, -25.0);
+
+        layers[1].translate(offset);
+
+        assert_eq!(layers[1].bounds, clip + offset);
+        assert_eq!(layers[1].quads[0].position, [20.0, 5.0]);
+        assert_eq!(layers[1].quads[0].size, [5.0, 5.0]);
+    }
+
+    #[test]
+    fn finite_text_outside_of_the_layer_is_culled() {
+        let primitives = [
+            // Off-screen
+            text(Rectangle::new(
+                Point::new(0.0, 700.0),
+                Size::new(100.0, 20.0),
+            )),
+            text(Rectangle::new(
+                Point::new(-200.0, 10.0),
+                Size::new(100.0, 20.0),
+            )),
+            // Partially visible
+            text(Rectangle::new(
+                Point::new(0.0, 590.0),
+                Size::new(100.0, 20.0),
+            )),
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers[0].text.len(), 1);
+        assert_eq!(layers[0].text[0].bounds.y, 590.0);
+    }
+
+    #[test]
+    fn infinite_text_is_never_culled() {
+        let primitives = [
+            text(Rectangle::new(
+                Point::new(900.0, 10.0),
+                Size::new(f32::INFINITY, 20.0),
+            )),
+            text(Rectangle::new(Point::new(10.0, 900.0), Size::INFINITY)),
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers[0].text.len(), 2);
+    }
+
+    #[test]
+    fn recycled_layers_keep_their_capacity() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+        let primitives: Vec<_> = (0..10).map(|_| text(bounds)).collect();
+
+        let mut pool = Pool::new();
+
+        let layers = Layer::generate_pooled(
+            &primitives,
+            &viewport(),
+            Settings::default(),
+            &mut pool,
+        );
+        let capacity = layers[0].text.capacity();
+
+        pool.recycle(layers);
+
+        let layers = Layer::generate_pooled(
+            &[],
+            &viewport(),
+            Settings::default(),
+            &mut pool,
+        );
+
+        assert!(layers[0].text.is_empty());
+        assert_eq!(layers[0].text.capacity(), capacity);
+    }
+
+    #[test]
+    fn border_widths_are_scaled_per_side() {
+        let quad = |border_width: BorderWidth| Primitive::Quad {
+            bounds: Rectangle::new(Point::ORIGIN, Size::new(10.0, 10.0)),
+            background: Color::WHITE.into(),
+            border_radius: [0.0; 4],
+            border_width,
+            border_color: Color::BLACK.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Group {
+                primitives: vec![
+                    quad(1.5.into()),
+                    quad([0.0, 0.0, 1.0, 0.0].into()),
+                    quad([1.0, 2.0, 3.0, 4.0].into()),
+                ],
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let quads = &layers[0].quads;
+
+        assert_eq!(quads[0].border_width, [3.0; 4]);
+        assert_eq!(quads[1].border_width, [0.0, 0.0, 2.0, 0.0]);
+        assert_eq!(quads[2].border_width, [2.0, 4.0, 6.0, 8.0]);
+        assert_eq!(quads[0].with_uniform_border(1.0).border_width, [1.0; 4]);
+    }
+
+    #[test]
+    fn outside_borders_grow_each_side() {
+        let primitives = [Primitive::Quad {
+            bounds: Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(10.0, 10.0),
+            ),
+            background: Color::WHITE.into(),
+            border_radius: [0.0; 4],
+            border_width: [1.0, 2.0, 3.0, 4.0].into(),
+            border_color: Color::BLACK.into(),
+            border_alignment: BorderAlignment::Outside,
+            corner_smoothing: 0.0,
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers[0].quads[0].position, [6.0, 9.0]);
+        assert_eq!(layers[0].quads[0].size, [16.0, 14.0]);
+    }
+
+    #[test]
+    fn oversized_border_radii_are_clamped() {
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Group {
+                primitives: vec![
+                    rounded_quad(
+                        Rectangle::new(Point::ORIGIN, Size::new(20.0, 10.0)),
+                        50.0,
+                    ),
+                    Primitive::Quad {
+                        bounds: Rectangle::new(
+                            Point::ORIGIN,
+                            Size::new(10.0, 30.0),
+                        ),
+                        background: Color::WHITE.into(),
+                        border_radius: [0.0, 2.0, 8.0, 100.0],
+                        border_width: 0.0.into(),
+                        border_color: Color::TRANSPARENT.into(),
+                        border_alignment: BorderAlignment::Inside,
+                        corner_smoothing: 0.0,
+                    },
+                ],
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let quads = &layers[0].quads;
+
+        assert_eq!(quads[0].border_radius, [10.0; 4]);
+        assert_eq!(quads[1].border_radius, [0.0, 4.0, 10.0, 10.0]);
+    }
+
+    #[test]
+    fn quad_batches_are_kept_together() {
+        let quads: Vec<_> = (0..100)
+            .map(|i| QuadInstance {
+                bounds: Rectangle::new(
+                    Point::new(i as f32 * 10.0, 0.0),
+                    Size::new(10.0, 10.0),
+                ),
+                color: Color::WHITE,
+            })
+            .collect();
+
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Scale {
+                scale: 0.5,
+                content: Box::new(Primitive::QuadBatch { quads }),
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let batches = &layers[0].quad_batches;
+
+        assert!(layers[0].quads.is_empty());
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].instances.len(), 100);
+        assert_eq!(
+            batches[0].instances[99],
+            quad::Instance {
+                position: [500.0, 5.0],
+                size: [5.0, 5.0],
+                color: [1.0; 4],
+            }
+        );
+    }
+
+    #[test]
+    fn transparent_fills_with_borders_are_border_only() {
+        let quad = |background: Color, border_width: f32| Primitive::Quad {
+            bounds: Rectangle::new(Point::ORIGIN, Size::new(10.0, 10.0)),
+            background: background.into(),
+            border_radius: [0.0; 4],
+            border_width: border_width.into(),
+            border_color: Color::BLACK.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [
+            quad(Color::TRANSPARENT, 1.0),
+            quad(Color::TRANSPARENT, 0.0),
+            quad(Color::WHITE, 1.0),
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let quads = &layers[0].quads;
+
+        assert!(quads[0].is_border_only());
+        assert!(!quads[1].is_border_only());
+        assert!(!quads[2].is_border_only());
+        assert!(quads[2].is_opaque());
+    }
+
+    #[test]
+    fn border_alignment_moves_the_outer_edge() {
+        assert_eq!(BorderAlignment::default(), BorderAlignment::Inside);
+
+        let quad = |border_alignment| {
+            let primitives = [Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(Primitive::Quad {
+                    bounds: Rectangle::new(
+                        Point::new(10.0, 10.0),
+                        Size::new(20.0, 20.0),
+                    ),
+                    background: Color::WHITE.into(),
+                    border_radius: [0.0, 4.0, 0.0, 4.0],
+                    border_width: 2.0.into(),
+                    border_color: Color::BLACK.into(),
+                    border_alignment,
+                    corner_smoothing: 0.0,
+                }),
+            }];
+
+            let layers = Layer::generate(&primitives, &viewport());
+            let quad = layers[0].quads[0];
+
+            (quad.position, quad.size, quad.border_radius)
+        };
+
+        assert_eq!(
+            quad(BorderAlignment::Inside),
+            ([20.0, 20.0], [40.0, 40.0], [0.0, 8.0, 0.0, 8.0])
+        );
+        assert_eq!(
+            quad(BorderAlignment::Center),
+            ([18.0, 18.0], [44.0, 44.0], [0.0, 10.0, 0.0, 10.0])
+        );
+        assert_eq!(
+            quad(BorderAlignment::Outside),
+            ([16.0, 16.0], [48.0, 48.0], [0.0, 12.0, 0.0, 12.0])
+        );
+    }
+
+    #[test]
+    fn opaque_quads_are_sorted_front_to_back() {
+        let quad = |x: f32, color: Color| Primitive::Quad {
+            bounds: Rectangle::new(Point::new(x, 0.0), Size::new(10.0, 10.0)),
+            background: Background::Color(color).into(),
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [
+            quad(0.0, Color::BLACK),
+            quad(1.0, Color::from_rgba(0.0, 0.0, 0.0, 0.5)),
+            quad(2.0, Color::WHITE),
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        let positions: Vec<_> = layers[0]
+            .front_to_back_opaque()
+            .iter()
+            .map(|quad| quad.position[0])
+            .collect();
+
+        assert_eq!(positions, vec![2.0, 0.0]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_layers_equal_sequential_layers() {
+        let primitives: Vec<_> = (0..10_000)
+            .map(|i| {
+                let bounds = Rectangle::new(
+                    Point::new((i % 100) as f32 * 8.0, (i / 100) as f32 * 6.0),
+                    Size::new(8.0, 6.0),
+                );
+
+                // Some tints end the chunks of the parallel generation
+                if i % 256 == 255 || i % 1_000 == 500 {
+                    Primitive::Tint {
+                        color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                        content: Box::new(text(bounds)),
+                    }
+                } else if i % 7 == 0 {
+                    Primitive::Clip {
+                        bounds,
+                        antialias: false,
+                        mode: ClipMode::Intersect,
+                        content: Box::new(text(bounds)),
+                    }
+                } else {
+                    shadow(bounds, i % 2 == 0)
+                }
+            })
+            .collect();
+
+        let primitives = [Primitive::Group { primitives }];
+
+        let sequential = Layer::generate(&primitives, &viewport());
+        let parallel = Layer::generate_parallel(
+            &primitives,
+            &viewport(),
+            Settings::default(),
+        );
+
+        assert_eq!(format!("{:?}", parallel), format!("{:?}", sequential));
+    }
+
+    #[test]
+    fn arc_progress_maps_to_end_angle() {
+        let primitives = [Primitive::Arc {
+            center: Point::new(50.0, 50.0),
+            radius: 20.0,
+            width: 4.0,
+            start_angle: 0.0,
+            progress: 0.25,
+            color: Color::BLACK,
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let arc = &layers[0].arcs[0];
+
+        assert_eq!(arc.start_angle, 0.0);
+        assert!((arc.end_angle - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn arcs_are_tessellated_into_their_ring_sector() {
+        let arc = |progress| Primitive::Arc {
+            center: Point::new(50.0, 50.0),
+            radius: 20.0,
+            width: 4.0,
+            start_angle: 0.0,
+            progress,
+            color: Color::BLACK,
+        };
+
+        let primitives = [arc(0.25), arc(1.0), arc(0.0)];
+        let layers = Layer::generate(&primitives, &viewport());
+        let bounds = layers[0].bounds;
+
+        let area = |arc: &Arc| match arc.mesh(AlphaMode::Straight, bounds) {
+            Some(Mesh::Solid {
+                origin, buffers, ..
+            }) => {
+                assert_eq!(origin, Point::new(50.0, 50.0));
+
+                buffers
+                    .indices
+                    .chunks(3)
+                    .map(|triangle| {
+                        let [a, b, c] = [0, 1, 2].map(|i| {
+                            buffers.vertices[triangle[i] as usize].position
+                        });
+
+                        ((b[0] - a[0]) * (c[1] - a[1])
+                            - (b[1] - a[1]) * (c[0] - a[0]))
+                            .abs()
+                            / 2.0
+                    })
+                    .sum::<f32>()
+            }
+            _ => panic!("expected a solid mesh"),
+        };
+
+        // A ring sector covers its sweep times its radius and width
+        let quarter = std::f32::consts::FRAC_PI_2 * 20.0 * 4.0;
+
+        assert!((area(&layers[0].arcs[0]) - quarter).abs() < quarter * 0.01);
+        assert!(
+            (area(&layers[0].arcs[1]) - quarter * 4.0).abs() < quarter * 0.04
+        );
+        assert!(layers[0].arcs[2]
+            .mesh(AlphaMode::Straight, bounds)
+            .is_none());
+    }
+
+    #[test]
+    fn capacities_are_reserved_for_the_first_layer() {
+        let quad = |x: f32| Primitive::Quad {
+            bounds: Rectangle::new(Point::new(x, 0.0), Size::new(1.0, 1.0)),
+            background: Background::Color(Color::BLACK).into(),
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [
+            Primitive::Group {
+                primitives: (0..100).map(|i| quad(i as f32)).collect(),
+            },
+            Primitive::Translate {
+                translation: Vector::new(1.0, 1.0),
+                content: Box::new(text(Rectangle::with_size(Size::INFINITY))),
+            },
+            Primitive::Clip {
+                bounds: Rectangle::with_size(Size::new(10.0, 10.0)),
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(quad(0.0)),
+            },
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0].quads.len(), 100);
+        assert!(layers[0].quads.capacity() >= 100);
+        assert_eq!(layers[0].text.len(), 1);
+        assert!(layers[0].text.capacity() >= 1);
+        assert_eq!(layers[1].quads.len(), 1);
+
+        for (i, quad) in layers[0].quads.iter().enumerate() {
+            assert_eq!(quad.position, [i as f32, 0.0]);
+        }
+
+        let layer = Layer::with_capacities(
+            Rectangle::with_size(Size::new(1.0, 1.0)),
+            4,
+            3,
+            2,
+            1,
+        );
+
+        assert!(layer.quads.capacity() >= 4);
+        assert!(layer.text.capacity() >= 3);
+        assert!(layer.meshes.capacity() >= 2);
+        assert!(layer.images.capacity() >= 1);
+    }
+
+    #[test]
+    fn identical_clip_layers_are_coalesced() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let clip = |content| Primitive::Clip {
+            bounds,
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(content),
+        };
+
+        let primitives = [clip(text(bounds)), clip(text(bounds))];
+
+        let layers = tree(&primitives).coalesce().into_layers();
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[1].bounds, bounds);
+        assert_eq!(layers[1].text.len(), 2);
+    }
+
+    #[test]
+    fn visible_bounds_are_clipped_by_the_layer() {
+        let layer =
+            Layer::new(Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0)));
+
+        assert_eq!(
+            layer.visible_bounds(Rectangle::new(
+                Point::new(900.0, 100.0),
+                Size::new(50.0, 50.0)
+            )),
+            None
+        );
+        assert_eq!(
+            layer.visible_bounds(Rectangle::new(
+                Point::new(-10.0, 580.0),
+                Size::new(50.0, 50.0)
+            )),
+            Some(Rectangle::new(
+                Point::new(0.0, 580.0),
+                Size::new(40.0, 20.0)
+            ))
+        );
+    }
+
+    #[test]
+    fn viewport_relative_bounds_are_resolved() {
+        let viewport = Viewport::with_physical_size(Size::new(1000, 500), 2.0);
+
+        let primitives = [Primitive::ViewportRelative {
+            fractional_bounds: Rectangle {
+                x: 0.5,
+                y: 0.5,
+                width: 0.5,
+                height: 0.5,
+            },
+            content: Box::new(text(Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(50.0, 20.0),
+            ))),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport);
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(
+            layers[1].bounds,
+            Rectangle::new(Point::new(250.0, 125.0), Size::new(250.0, 125.0))
+        );
+        assert_eq!(
+            layers[1].text[0].bounds.position(),
+            Point::new(260.0, 135.0)
+        );
+    }
+
+    #[test]
+    fn tints_create_tinted_layers() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+        let color = Color::from_rgba(0.5, 0.5, 0.5, 0.5);
+
+        let primitives = [
+            Primitive::Tint {
+                color,
+                content: Box::new(Primitive::Group {
+                    primitives: vec![
+                        text(bounds),
+                        Primitive::Clip {
+                            bounds,
+                            antialias: false,
+                            mode: ClipMode::Intersect,
+                            content: Box::new(text(bounds)),
+                        },
+                    ],
+                }),
+            },
+            Primitive::Tint {
+                color: Color::TRANSPARENT,
+                content: Box::new(text(bounds)),
+            },
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        // The tint is pushed after its nested clip, so it covers it, while the
+        // primitives that follow it are drawn after it
+        assert_eq!(layers.len(), 4);
+        assert_eq!(layers[0].tint, None);
+        assert_eq!(layers[0].text.len(), 1);
+        assert_eq!(layers[1].tint, None);
+        assert_eq!(layers[1].text.len(), 1);
+        assert_eq!(layers[2].tint, Some(color.into_linear()));
+        assert_eq!(layers[2].bounds, layers[0].bounds);
+        assert!(layers[2].text.is_empty());
+        assert_eq!(layers[3].tint, None);
+        assert_eq!(layers[3].bounds, layers[0].bounds);
+        assert_eq!(layers[3].text.len(), 1);
+    }
+
+    #[test]
+    fn primitives_after_a_tint_are_not_tinted() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+        let color = Color::from_rgba(0.0, 0.0, 0.0, 0.5);
+
+        let label = |label: &str| {
+            let mut primitive = text(bounds);
+
+            if let Primitive::Text { content, .. } = &mut primitive {
+                *content = String::from(label);
+            }
+
+            primitive
+        };
+
+        let clip = Primitive::Clip {
+            bounds,
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(Primitive::Group {
+                primitives: vec![
+                    Primitive::Tint {
+                        color,
+                        content: Box::new(label("app")),
+                    },
+                    label("modal"),
+                ],
+            }),
+        };
+
+        let primitives = [clip, label("footer")];
+
+        let tree = Layer::generate_tree(
+            &primitives,
+            &viewport(),
+            Settings {
+                alpha_mode: AlphaMode::Premultiplied,
+                ..Settings::default()
+            },
+            &mut Pool::new(),
+        );
+
+        let layers: Vec<_> = tree
+            .layers()
+            .iter()
+            .map(|layer| {
+                let text: Vec<_> =
+                    layer.text.iter().map(|text| text.content).collect();
+
+                (text, layer.tint)
+            })
+            .collect();
+
+        assert_eq!(
+            layers,
+            [
+                (vec!["footer"], None),
+                (vec!["app"], None),
+                (vec![], Some(color.into_linear_premultiplied())),
+                (vec!["modal"], None),
+            ]
+        );
+        assert_eq!(tree.parents(), [None, Some(0), Some(1), Some(1)]);
+        assert_eq!(tree.layers()[3].bounds, tree.layers()[1].bounds);
+    }
+
+    #[test]
+    fn nested_tints_are_composited_once() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+        let outer = Color::from_rgba(0.0, 0.0, 0.0, 0.5);
+        let inner = Color::from_rgba(1.0, 0.0, 0.0, 0.25);
+
+        let primitives = [Primitive::Tint {
+            color: outer,
+            content: Box::new(Primitive::Tint {
+                color: inner,
+                content: Box::new(Primitive::Clip {
+                    bounds,
+                    antialias: false,
+                    mode: ClipMode::Intersect,
+                    content: Box::new(text(bounds)),
+                }),
+            }),
+        }];
+
+        let tree = Layer::generate_tree(
+            &primitives,
+            &viewport(),
+            Settings::default(),
+            &mut Pool::new(),
+        );
+
+        let tints: Vec<_> =
+            tree.layers().iter().map(|layer| layer.tint).collect();
+
+        // The inner tint is composited first, and each one only once
+        assert_eq!(
+            tints,
+            [
+                None,
+                None,
+                Some(inner.into_linear()),
+                Some(outer.into_linear())
+            ]
+        );
+        assert_eq!(tree.parents(), [None, Some(0), Some(0), Some(0)]);
+    }
+
+    #[test]
+    fn empty_layers_are_pruned() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let clip = |content| Primitive::Clip {
+            bounds,
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(content),
+        };
+
+        let culled = Primitive::Cached {
+            key: 42,
+            cache: std::sync::Arc::new(Primitive::Group { primitives: vec![] }),
+        };
+
+        let primitives = [clip(text(bounds)), clip(culled)];
+
+        let tree = tree(&primitives);
+
+        assert_eq!(tree.layers().len(), 3);
+        assert!(tree.layers()[2].is_empty());
+
+        let tree = tree.prune();
+        let layers = tree.layers();
+
+        assert_eq!(layers.len(), 2);
+        assert!(layers[0].is_empty());
+        assert_eq!(layers[1].text.len(), 1);
+        assert_eq!(tree.parents(), [None, Some(0)]);
+
+        // The cached primitive was drawn in the first layer
+        assert_eq!(layers[0].cache_keys, [42]);
+        assert!(layers[1].cache_keys.is_empty());
+    }
+
+    #[test]
+    fn empty_layers_with_children_are_not_pruned() {
+        let clip = |x: f32, content| Primitive::Clip {
+            bounds: Rectangle::new(Point::new(x, x), Size::new(50.0, 50.0)),
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(content),
+        };
+
+        let bounds =
+            Rectangle::new(Point::new(20.0, 20.0), Size::new(10.0, 10.0));
+
+        // Groups keep nested clips from sharing a single layer
+        let nested = |content| Primitive::Group {
+            primitives: vec![Primitive::None, clip(15.0, content)],
+        };
+
+        let primitives = [
+            clip(10.0, nested(text(bounds))),
+            clip(
+                10.0,
+                Primitive::Cached {
+                    key: 7,
+                    cache: std::sync::Arc::new(nested(Primitive::None)),
+                },
+            ),
+            clip(15.0, text(bounds)),
+        ];
+
+        let tree = tree(&primitives);
+
+        assert_eq!(
+            tree.parents(),
+            [None, Some(0), Some(1), Some(0), Some(3), Some(0)]
+        );
+
+        let tree = tree.prune();
+        let layers = tree.layers();
+
+        // The empty outer clip keeps its child, while the empty nested clip
+        // and its parent are removed
+        assert_eq!(tree.parents(), [None, Some(0), Some(1), Some(0)]);
+        assert_eq!(layers[2].text.len(), 1);
+        assert_eq!(layers[3].text.len(), 1);
+        assert_eq!(layers[0].cache_keys, [7]);
+    }
+
+    #[test]
+    fn coalescing_keeps_the_clip_hierarchy() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let clip = |content| Primitive::Clip {
+            bounds,
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(content),
+        };
+
+        let inner =
+            Rectangle::new(Point::new(20.0, 20.0), Size::new(10.0, 10.0));
+
+        let inner_clip = || Primitive::Clip {
+            bounds: inner,
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(text(inner)),
+        };
+
+        let primitives = [
+            clip(text(bounds)),
+            clip(Primitive::Group {
+                primitives: vec![text(bounds), inner_clip()],
+            }),
+            inner_clip(),
+        ];
+
+        let tree = tree(&primitives);
+
+        assert_eq!(tree.parents(), [None, Some(0), Some(0), Some(2), Some(0)]);
+
+        let tree = tree.coalesce();
+        let layers = tree.layers();
+
+        // Sibling clips are merged, while the last clip is not merged into
+        // the identical, but unrelated, nested clip before it
+        assert_eq!(tree.parents(), [None, Some(0), Some(1), Some(0)]);
+        assert_eq!(layers[1].text.len(), 2);
+        assert_eq!(layers[2].bounds, inner);
+        assert_eq!(layers[3].bounds, inner);
+    }
+
+    #[test]
+    fn layers_with_different_bounds_are_not_coalesced() {
+        let outer =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+        let inner =
+            Rectangle::new(Point::new(20.0, 20.0), Size::new(10.0, 10.0));
+
+        let primitives = [
+            Primitive::Clip {
+                bounds: outer,
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(text(outer)),
+            },
+            Primitive::Clip {
+                bounds: inner,
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(text(inner)),
+            },
+        ];
+
+        let layers = tree(&primitives).coalesce().into_layers();
+
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[1].bounds, outer);
+        assert_eq!(layers[2].bounds, inner);
+    }
+
+    #[test]
+    fn coalescing_preserves_draw_order() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let primitives = [
+            Primitive::Clip {
+                bounds,
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(text(bounds)),
+            },
+            Primitive::Clip {
+                bounds,
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(shadow(bounds, false)),
+            },
+        ];
+
+        let layers = tree(&primitives).coalesce().into_layers();
+
+        assert_eq!(layers.len(), 3);
+    }
+
+    #[test]
+    fn contained_shadows_are_merged() {
+        let shadow = |x: f32, blur_radius: f32| Shadow {
+            bounds: Rectangle::new(Point::new(x, 10.0), Size::new(20.0, 10.0)),
+            color: [0.0, 0.0, 0.0, 0.5],
+            border_radius: [0.0; 4],
+            offset: Vector::new(0.0, 2.0),
+            blur_radius,
+            spread_radius: 0.0,
+            inset: false,
+            glow: false,
+        };
+
+        let mut contained = shadow(5.0, 4.0);
+        contained.bounds.width = 10.0;
+
+        let mut layer = Layer::new(Rectangle::with_size(Size::INFINITY));
+        layer.shadows = vec![shadow(0.0, 4.0), shadow(0.0, 4.0), contained];
+        layer.merge_shadows();
+
+        assert_eq!(layer.shadows, [shadow(0.0, 4.0)]);
+
+        layer.shadows = vec![shadow(0.0, 4.0), shadow(15.0, 4.0)];
+        layer.merge_shadows();
+
+        assert_eq!(layer.shadows.len(), 2);
+
+        layer.shadows = vec![shadow(0.0, 4.0), shadow(0.0, 8.0)];
+        layer.merge_shadows();
+
+        assert_eq!(layer.shadows.len(), 2);
+    }
+
+    #[test]
+    fn adjacent_quads_are_merged() {
+        let quad = |x: f32, color: Color| Primitive::Quad {
+            bounds: Rectangle::new(Point::new(x, 10.0), Size::new(20.0, 10.0)),
+            background: color.into(),
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let row = [
+            quad(0.0, Color::WHITE),
+            quad(20.0, Color::WHITE),
+            quad(40.0, Color::WHITE),
+        ];
+
+        let mut layers = Layer::generate(&row, &viewport());
+        layers[0].merge_coplanar_quads();
+
+        assert_eq!(layers[0].quads.len(), 1);
+        assert_eq!(layers[0].quads[0].position, [0.0, 10.0]);
+        assert_eq!(layers[0].quads[0].size, [60.0, 10.0]);
+
+        let mixed = [
+            quad(0.0, Color::WHITE),
+            quad(20.0, Color::BLACK),
+            quad(40.0, Color::WHITE),
+        ];
+
+        let mut layers = Layer::generate(&mixed, &viewport());
+        layers[0].merge_coplanar_quads();
+
+        assert_eq!(layers[0].quads.len(), 3);
+    }
+
+    #[test]
+    fn rounded_or_painted_quads_are_not_merged() {
+        let bounds =
+            |x| Rectangle::new(Point::new(x, 10.0), Size::new(20.0, 10.0));
+
+        let gradient = Primitive::Quad {
+            bounds: bounds(40.0),
+            background: Paint::LinearGradient {
+                direction: Vector::new(1.0, 0.0),
+                stops: vec![(0.0, Color::BLACK), (1.0, Color::WHITE)],
+            },
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [
+            rounded_quad(bounds(0.0), 4.0),
+            rounded_quad(bounds(20.0), 4.0),
+            rounded_quad(bounds(60.0), 0.0),
+            rounded_quad(bounds(80.0), 0.0),
+            gradient,
+        ];
+
+        let mut layers = Layer::generate(&primitives, &viewport());
+        let gradient_quad = layers[0].quad_gradients[0].quad;
+
+        layers[0].merge_coplanar_quads();
+
+        let layer = &layers[0];
+
+        assert_eq!(gradient_quad, 4);
+        assert_eq!(layer.quads.len(), 4);
+        assert_eq!(layer.quads[2].size, [40.0, 10.0]);
+        assert_eq!(layer.quad_gradients[0].quad, 3);
+        assert_eq!(layer.quads[3].position, [40.0, 10.0]);
+    }
+
+    #[test]
+    fn empty_meshes_are_skipped() {
+        let mesh = |vertices: usize, indices: Vec<u32>| Primitive::SolidMesh {
+            buffers: triangle::Mesh2D {
+                vertices: vec![
+                    triangle::ColoredVertex2D {
+                        position: [0.0, 0.0],
+                        color: [0.0, 0.0, 0.0, 1.0],
+                    };
+                    vertices
+                ],
+                indices,
+                alpha_mode: AlphaMode::Straight,
+            },
+            size: Size::new(10.0, 10.0),
+        };
+
+        let primitives =
+            [mesh(0, vec![]), mesh(3, vec![]), mesh(3, vec![0, 1, 2])];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers[0].meshes.len(), 1);
+        assert_eq!(layers[0].meshes[0].indices(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn mesh_vertices_are_scaled_when_enabled() {
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(Primitive::SolidMesh {
+                    buffers: triangle::Mesh2D {
+                        vertices: [[0.0, 0.0], [10.0, 0.0], [5.0, 10.0]]
+                            .map(|position| triangle::ColoredVertex2D {
+                                position,
+                                color: [0.0, 0.0, 0.0, 1.0],
+                            })
+                            .to_vec(),
+                        indices: vec![0, 1, 2],
+                        alpha_mode: AlphaMode::Straight,
+                    },
+                    size: Size::new(10.0, 10.0),
+                }),
+            }),
+        }];
+
+        let positions = |transform_meshes| {
+            let layers = Layer::generate_with(
+                &primitives,
+                &viewport(),
+                Settings {
+                    transform_meshes,
+                    ..Settings::default()
+                },
+            );
+
+            match &layers[0].meshes[..] {
+                [Mesh::Solid {
+                    origin, buffers, ..
+                }] => {
+                    assert_eq!(*origin, Point::new(5.0, 5.0));
+                    assert_eq!(
+                        matches!(buffers, Cow::Borrowed(_)),
+                        !transform_meshes
+                    );
+
+                    buffers
+                        .vertices
+                        .iter()
+                        .map(|vertex| vertex.position)
+                        .collect::<Vec<_>>()
+                }
+                meshes => panic!("unexpected meshes: {:?}", meshes),
+            }
+        };
+
+        assert_eq!(
+            positions(false),
+            vec![[0.0, 0.0], [10.0, 0.0], [5.0, 10.0]]
+        );
+        assert_eq!(
+            positions(true),
+            vec![[0.0, 0.0], [20.0, 0.0], [10.0, 20.0]]
+        );
+    }
+
+    #[test]
+    fn gradient_borders_are_transformed() {
+        let quad = |border_color| Primitive::Quad {
+            bounds: Rectangle::new(Point::new(0.0, 0.0), Size::new(10.0, 10.0)),
+            background: Background::Color(Color::WHITE).into(),
+            border_radius: [0.0; 4],
+            border_width: 1.0.into(),
+            border_color,
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(Primitive::Group {
+                    primitives: vec![
+                        quad(BorderColor::Solid(Color::BLACK)),
+                        quad(BorderColor::Gradient {
+                            start: Point::new(0.0, 0.0),
+                            end: Point::new(10.0, 0.0),
+                            start_color: Color::WHITE,
+                            end_color: Color::BLACK,
+                        }),
+                    ],
+                }),
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let layer = &layers[0];
+
+        assert_eq!(layer.quads[0].border_color, [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(layer.quads[1].border_color, [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(
+            layer.quad_gradients,
+            vec![quad::Gradient {
+                quad: 1,
+                target: quad::Target::Border,
+                shape: quad::Shape::Linear {
+                    start: [5.0, 5.0],
+                    end: [25.0, 5.0],
+                },
+                stops: vec![
+                    (0.0, [1.0, 1.0, 1.0, 1.0]),
+                    (1.0, [0.0, 0.0, 0.0, 1.0]),
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn radial_gradients_are_transformed() {
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(Primitive::Quad {
+                    bounds: Rectangle::new(
+                        Point::new(10.0, 10.0),
+                        Size::new(20.0, 20.0),
+                    ),
+                    background: Background::RadialGradient {
+                        center: Point::new(10.0, 5.0),
+                        radius: 8.0,
+                        start: Color::WHITE,
+                        end: Color::TRANSPARENT,
+                    }
+                    .into(),
+                    border_radius: [0.0; 4],
+                    border_width: 0.0.into(),
+                    border_color: Color::TRANSPARENT.into(),
+                    border_alignment: BorderAlignment::Inside,
+                    corner_smoothing: 0.0,
+                }),
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let layer = &layers[0];
+
+        assert_eq!(layer.quads[0].position, [25.0, 25.0]);
+        assert_eq!(layer.quads[0].color, [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(layer.quad_gradients.len(), 1);
+        assert_eq!(layer.quad_gradients[0].target, quad::Target::Background);
+        assert_eq!(
+            layer.quad_gradients[0].shape,
+            quad::Shape::Radial {
+                center: [45.0, 35.0],
+                radius: 16.0,
+            }
+        );
+        assert!(layer.front_to_back_opaque().is_empty());
+    }
+
+    #[test]
+    fn linear_gradients_span_transformed_bounds() {
+        let gradient = |angle_degrees| {
+            let primitives = [Primitive::Translate {
+                translation: Vector::new(5.0, 5.0),
+                content: Box::new(Primitive::Scale {
+                    scale: 2.0,
+                    content: Box::new(Primitive::Quad {
+                        bounds: Rectangle::new(
+                            Point::new(10.0, 10.0),
+                            Size::new(20.0, 10.0),
+                        ),
+                        background: Background::linear_gradient_deg(
+                            angle_degrees,
+                            Color::WHITE,
+                            Color::BLACK,
+                        )
+                        .into(),
+                        border_radius: [0.0; 4],
+                        border_width: 0.0.into(),
+                        border_color: Color::TRANSPARENT.into(),
+                        border_alignment: BorderAlignment::Inside,
+                        corner_smoothing: 0.0,
+                    }),
+                }),
+            }];
+
+            let layers = Layer::generate(&primitives, &viewport());
+
+            assert_eq!(layers[0].quads[0].color, [1.0, 1.0, 1.0, 1.0]);
+            assert_eq!(layers[0].quad_gradients.len(), 1);
+
+            layers[0].quad_gradients[0].shape.clone()
+        };
+
+        assert_eq!(
+            gradient(90.0),
+            quad::Shape::Linear {
+                start: [25.0, 35.0],
+                end: [65.0, 35.0],
+            }
+        );
+        assert_eq!(
+            gradient(0.0),
+            quad::Shape::Linear {
+                start: [45.0, 45.0],
+                end: [45.0, 25.0],
+            }
+        );
+    }
+
+    #[test]
+    fn conic_gradients_are_transformed_and_linearized() {
+        let conic = |stops| {
+            let primitives = [Primitive::Translate {
+                translation: Vector::new(5.0, 5.0),
+                content: Box::new(Primitive::Scale {
+                    scale: 2.0,
+                    content: Box::new(Primitive::Quad {
+                        bounds: Rectangle::new(
+                            Point::new(10.0, 10.0),
+                            Size::new(20.0, 20.0),
+                        ),
+                        background: Paint::ConicGradient {
+                            center: Point::new(20.0, 20.0),
+                            start_angle: 1.0,
+                            stops,
+                        },
+                        border_radius: [0.0; 4],
+                        border_width: 0.0.into(),
+                        border_color: Color::TRANSPARENT.into(),
+                        border_alignment: BorderAlignment::Inside,
+                        corner_smoothing: 0.0,
+                    }),
+                }),
+            }];
+
+            let mut layers = Layer::generate(&primitives, &viewport());
+            let layer = layers.remove(0);
+
+            (layer.quads[0].color, layer.quad_gradients)
+        };
+
+        let orange = Color::from_rgb(1.0, 0.5, 0.0);
+        let (color, gradients) =
+            conic(vec![(0.0, orange), (1.0, Color::BLACK)]);
+
+        assert_eq!(color, orange.into_linear());
+        assert_eq!(
+            gradients[0].shape,
+            quad::Shape::Conic {
+                center: [45.0, 45.0],
+                start_angle: 1.0,
+            }
+        );
+        assert_eq!(
+            gradients[0].stops,
+            vec![(0.0, orange.into_linear()), (1.0, [0.0, 0.0, 0.0, 1.0])]
+        );
+
+        let (color, gradients) = conic(Vec::new());
+
+        assert_eq!(color, [0.0; 4]);
+        assert!(gradients.is_empty());
+    }
+
+    #[test]
+    fn multi_stop_gradients_keep_their_stops_in_order() {
+        let gradient = |stops| {
+            let primitives = [Primitive::Quad {
+                bounds: Rectangle::new(
+                    Point::new(0.0, 0.0),
+                    Size::new(30.0, 10.0),
+                ),
+                background: Paint::LinearGradient {
+                    direction: Vector::new(1.0, 0.0),
+                    stops,
+                },
+                border_radius: [0.0; 4],
+                border_width: 0.0.into(),
+                border_color: Color::TRANSPARENT.into(),
+                border_alignment: BorderAlignment::Inside,
+                corner_smoothing: 0.0,
+            }];
+
+            let mut layers = Layer::generate(&primitives, &viewport());
+            let layer = layers.remove(0);
+
+            (layer.quads[0].color, layer.quad_gradients)
+        };
+
+        let orange = Color::from_rgb(1.0, 0.5, 0.0);
+        let teal = Color::from_rgb(0.0, 0.5, 0.5);
+
+        let (color, gradients) =
+            gradient(vec![(0.0, Color::WHITE), (0.5, orange), (1.0, teal)]);
+
+        let expected = vec![
+            (0.0, [1.0, 1.0, 1.0, 1.0]),
+            (0.5, orange.into_linear()),
+            (1.0, teal.into_linear()),
+        ];
+
+        assert_eq!(color, [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(
+            gradients[0].shape,
+            quad::Shape::Linear {
+                start: [0.0, 5.0],
+                end: [30.0, 5.0],
+            }
+        );
+        assert_eq!(gradients[0].stops, expected);
+
+        let (color, gradients) =
+            gradient(vec![(1.0, teal), (0.0, Color::WHITE), (0.5, orange)]);
+
+        assert_eq!(color, [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(gradients[0].stops, expected);
+
+        let (_, gradients) =
+            gradient(vec![(-1.0, Color::WHITE), (2.0, Color::BLACK)]);
+
+        assert_eq!(
+            gradients[0].stops,
+            vec![(0.0, [1.0, 1.0, 1.0, 1.0]), (1.0, [0.0, 0.0, 0.0, 1.0])]
+        );
+    }
+
+    #[test]
+    fn text_baselines_are_snapped_to_device_pixels() {
+        let viewport = Viewport::with_physical_size(Size::new(800, 600), 2.0);
+
+        let text = |snap_baselines| Primitive::Translate {
+            translation: Vector::new(0.0, 0.3),
+            content: Box::new(Primitive::Text {
+                content: String::from("Hello"),
+                bounds: Rectangle::new(
+                    Point::new(10.0, 10.0),
+                    Size::new(100.0, 20.0),
+                ),
+                color: Color::BLACK,
+                size: 20.0,
+                letter_spacing: 0.0,
+                line_height: LineHeight::default(),
+                font: Font::Default,
+                horizontal_alignment: alignment::Horizontal::Left,
+                vertical_alignment: alignment::Vertical::Top,
+                truncate: Truncate::None,
+                max_lines: None,
+                background: None,
+                shadow: None,
+                snap_baselines,
+            }),
+        };
+
+        let primitives = [text(true), text(false)];
+        let layers = Layer::generate(&primitives, &viewport);
+
+        assert_eq!(layers[0].text[0].bounds.y, 10.5);
+        assert_eq!(layers[0].text[1].bounds.y, 10.3);
+    }
+
+    #[test]
+    fn text_is_snapped_to_physical_pixels_when_enabled() {
+        let primitives = [text(Rectangle::new(
+            Point::new(10.3, 10.7),
+            Size::new(100.0, 20.0),
+        ))];
+
+        let origin = |snap_text, scale_factor| {
+            let layers = Layer::generate_with(
+                &primitives,
+                &Viewport::with_physical_size(
+                    Size::new(800, 600),
+                    scale_factor,
+                ),
+                Settings {
+                    snap_text,
+                    ..Settings::default()
+                },
+            );
+
+            let bounds = layers[0].text[0].bounds;
+
+            (bounds.x, bounds.y)
+        };
+
+        assert_eq!(origin(false, 1.0), (10.3, 10.7));
+        assert_eq!(origin(true, 1.0), (10.0, 11.0));
+        assert_eq!(origin(true, 2.0), (10.5, 10.5));
+    }
+
+    #[test]
+    fn text_spacing_is_transformed() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 20.0));
+
+        let styled = |letter_spacing, line_height| Primitive::Text {
+            content: String::from("Hello"),
+            bounds,
+            color: Color::BLACK,
+            size: 20.0,
+            letter_spacing,
+            line_height,
+            font: Font::Default,
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Top,
+            truncate: Truncate::None,
+            max_lines: None,
+            background: None,
+            shadow: None,
+            snap_baselines: false,
+        };
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Group {
+                primitives: vec![
+                    text(bounds),
+                    styled(1.5, LineHeight::Relative(1.2)),
+                    styled(0.0, LineHeight::Absolute(24.0)),
+                ],
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let text = &layers[0].text;
+
+        assert_eq!(text[0].letter_spacing, 0.0);
+        assert_eq!(text[0].line_height, LineHeight::Relative(1.0));
+        assert_eq!(text[1].letter_spacing, 3.0);
+        assert_eq!(text[1].line_height, LineHeight::Relative(1.2));
+        assert_eq!(text[2].line_height, LineHeight::Absolute(48.0));
+    }
+
+    #[test]
+    fn image_filled_quads_keep_handle_and_radius() {
+        let handle = image::Handle::from_path("texture.png");
+
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 10.0),
+            content: Box::new(Primitive::Quad {
+                bounds: Rectangle::new(
+                    Point::new(10.0, 10.0),
+                    Size::new(40.0, 20.0),
+                ),
+                background: Paint::Image {
+                    handle: handle.clone(),
+                    fit: ImageFit::Cover,
+                    repeat: ImageRepeat::NoRepeat,
+                    tile_size: None,
+                },
+                border_radius: [4.0; 4],
+                border_width: 0.0.into(),
+                border_color: Color::TRANSPARENT.into(),
+                border_alignment: BorderAlignment::Inside,
+                corner_smoothing: 0.0,
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let layer = &layers[0];
+        let quad = &layer.quads[layer.quad_images[0].quad];
+
+        assert_eq!(layer.quad_images.len(), 1);
+        assert_eq!(layer.quad_images[0].handle.id(), handle.id());
+        assert_eq!(layer.quad_images[0].fit, ImageFit::Cover);
+        assert_eq!(quad.position, [15.0, 20.0]);
+        assert_eq!(quad.border_radius, [4.0; 4]);
+        assert_eq!(quad.color, [0.0; 4]);
+    }
+
+    #[test]
+    fn image_filled_quads_are_clipped_inside_their_border() {
+        let image = |fit| Primitive::Quad {
+            bounds: Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(40.0, 20.0),
+            ),
+            background: Paint::Image {
+                handle: image::Handle::from_path("texture.png"),
+                fit,
+                repeat: ImageRepeat::NoRepeat,
+                tile_size: None,
+            },
+            border_radius: [6.0, 6.0, 0.0, 0.0],
+            border_width: 2.0.into(),
+            border_color: Color::BLACK.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [image(ImageFit::Contain), image(ImageFit::Cover)];
+        let layers = Layer::generate(&primitives, &viewport());
+        let layer = &layers[0];
+
+        let slices = |image: &quad::Image| {
+            image
+                .slices(&layer.quads[image.quad], Size::new(9.0, 9.0))
+                .collect::<Vec<_>>()
+        };
+
+        let inner =
+            Rectangle::new(Point::new(12.0, 12.0), Size::new(36.0, 16.0));
+
+        assert_eq!(
+            layer.quad_images[0].clip(&layer.quads[0]),
+            RoundedClip {
+                bounds: inner,
+                border_radius: [4.0, 4.0, 0.0, 0.0],
+            }
+        );
+
+        assert_eq!(
+            slices(&layer.quad_images[0]),
+            [Slice {
+                source: Rectangle::new(Point::ORIGIN, Size::new(9.0, 9.0)),
+                bounds: Rectangle::new(
+                    Point::new(22.0, 12.0),
+                    Size::new(16.0, 16.0),
+                ),
+            }]
+        );
+
+        assert_eq!(
+            slices(&layer.quad_images[1]),
+            [Slice {
+                source: Rectangle::new(
+                    Point::new(0.0, 2.5),
+                    Size::new(9.0, 4.0),
+                ),
+                bounds: inner,
+            }]
+        );
+    }
+
+    #[test]
+    fn image_repetition_is_kept_and_scaled() {
+        let image = |repeat, tile_size| Primitive::Quad {
+            bounds: Rectangle::new(Point::ORIGIN, Size::new(40.0, 20.0)),
+            background: Paint::Image {
+                handle: image::Handle::from_path("texture.png"),
+                fit: ImageFit::Fill,
+                repeat,
+                tile_size,
+            },
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Group {
+                primitives: vec![
+                    image(ImageRepeat::default(), None),
+                    image(ImageRepeat::RepeatX, Some(Size::new(8.0, 4.0))),
+                ],
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let images = &layers[0].quad_images;
+
+        assert_eq!(images[0].repeat, ImageRepeat::NoRepeat);
+        assert_eq!(images[0].tile_size, None);
+        assert_eq!(images[1].repeat, ImageRepeat::RepeatX);
+        assert_eq!(images[1].tile_size, Some(Size::new(16.0, 8.0)));
+    }
+
+    #[test]
+    fn repeated_images_are_tiled_across_their_quad() {
+        let image = |repeat, tile_size| Primitive::Quad {
+            bounds: Rectangle::new(Point::ORIGIN, Size::new(40.0, 20.0)),
+            background: Paint::Image {
+                handle: image::Handle::from_path("texture.png"),
+                fit: ImageFit::Fill,
+                repeat,
+                tile_size,
+            },
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        };
+
+        let primitives = [
+            image(ImageRepeat::RepeatX, Some(Size::new(16.0, 8.0))),
+            image(ImageRepeat::Repeat, None),
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let layer = &layers[0];
+
+        let slices = |image: &quad::Image| {
+            image
+                .slices(&layer.quads[image.quad], Size::new(16.0, 16.0))
+                .map(|slice| {
+                    let Slice { source, bounds } = slice;
+
+                    (
+                        [source.x, source.y, source.width, source.height],
+                        [bounds.x, bounds.y, bounds.width, bounds.height],
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            slices(&layer.quad_images[0]),
+            [
+                ([0.0, 0.0, 16.0, 16.0], [0.0, 0.0, 16.0, 8.0]),
+                ([0.0, 0.0, 16.0, 16.0], [16.0, 0.0, 16.0, 8.0]),
+                ([0.0, 0.0, 8.0, 16.0], [32.0, 0.0, 8.0, 8.0]),
+            ]
+        );
+
+        assert_eq!(
+            slices(&layer.quad_images[1]),
+            [
+                ([0.0, 0.0, 16.0, 16.0], [0.0, 0.0, 16.0, 16.0]),
+                ([0.0, 0.0, 16.0, 16.0], [16.0, 0.0, 16.0, 16.0]),
+                ([0.0, 0.0, 8.0, 16.0], [32.0, 0.0, 8.0, 16.0]),
+                ([0.0, 0.0, 16.0, 4.0], [0.0, 16.0, 16.0, 4.0]),
+                ([0.0, 0.0, 16.0, 4.0], [16.0, 16.0, 16.0, 4.0]),
+                ([0.0, 0.0, 8.0, 4.0], [32.0, 16.0, 8.0, 4.0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn stats_aggregate_all_layers() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        let primitives = [
+            text(bounds),
+            image(Color::WHITE, 1.0),
+            Primitive::Clip {
+                bounds,
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(Primitive::Group {
+                    primitives: vec![text(bounds), text(bounds)],
+                }),
+            },
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let stats = Layer::stats(&layers);
+
+        assert_eq!(
+            stats.layers,
+            vec![
+                Counts {
+                    text: 1,
+                    images: 1,
+                    ..Counts::default()
+                },
+                Counts {
+                    text: 2,
+                    ..Counts::default()
+                },
+            ]
+        );
+        assert_eq!(
+            stats.total,
+            Counts {
+                quads: 0,
+                text: 3,
+                images: 1,
+                meshes: 0,
+            }
+        );
+        assert_eq!(
+            stats.to_string(),
+            "2 layers | 0 quads | 3 text | 1 images | 0 meshes"
+        );
+    }
+
+    #[test]
+    fn default_overlay_is_unchanged() {
+        let lines = ["FPS: 60", "Frame: 16ms"];
+        let overlay = Layer::overlay(&lines, &viewport());
+
+        let bounds: Vec<_> =
+            overlay.text.iter().map(|text| text.bounds).collect();
+
+        assert_eq!(
+            bounds,
+            vec![
+                Rectangle::new(Point::new(11.0, 11.0), Size::INFINITY),
+                Rectangle::new(Point::new(11.0, 36.0), Size::INFINITY),
+            ]
+        );
+        assert_eq!(overlay.text[0].size, 20.0);
+        assert_eq!(overlay.text[0].color, [0.9, 0.9, 0.9, 1.0]);
+        assert_eq!(
+            overlay.text[0].shadow,
+            Some(TextShadow {
+                color: [0.0, 0.0, 0.0, 1.0],
+                offset: Vector::new(-1.0, -1.0),
+                blur: 0.0,
+            })
+        );
+    }
+
+    #[test]
+    fn overlay_is_scaled_by_the_scale_factor() {
+        let lines = ["FPS: 60", "Frame: 16ms"];
+        let viewport = Viewport::with_physical_size(Size::new(800, 600), 2.0);
+
+        let overlay = Layer::overlay(&lines, &viewport);
+
+        assert_eq!(overlay.text[0].size, 40.0);
+        assert_eq!(
+            overlay.text[0].bounds,
+            Rectangle::new(Point::new(22.0, 22.0), Size::INFINITY)
+        );
+        assert_eq!(overlay.text[1].bounds.y - overlay.text[0].bounds.y, 50.0);
+    }
+
+    #[test]
+    fn overlay_style_is_customizable() {
+        let lines = ["FPS: 60", "Frame: 16ms"];
+
+        let overlay = Layer::overlay_with(
+            &lines,
+            &viewport(),
+            OverlayStyle {
+                color: [0.0, 0.0, 0.0, 1.0],
+                shadow_color: [1.0, 1.0, 1.0, 1.0],
+                size: 40.0,
+                origin: Point::new(20.0, 30.0),
+                line_spacing: 50.0,
+                font: Font::Default,
+                panel: None,
+            },
+        );
+
+        assert_eq!(overlay.text.len(), 2);
+        assert_eq!(overlay.text[1].size, 40.0);
+        assert_eq!(overlay.text[1].color, [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(
+            overlay.text[1].bounds,
+            Rectangle::new(Point::new(20.0, 80.0), Size::INFINITY)
+        );
+        assert_eq!(
+            overlay.text[1]
+                .shadow
+                .map(|shadow| (shadow.color, shadow.offset)),
+            Some(([1.0, 1.0, 1.0, 1.0], Vector::new(-2.0, -2.0)))
+        );
+    }
+
+    #[test]
+    fn overlay_panel_encloses_all_lines() {
+        let lines = ["FPS: 60", "Frame: 16ms", "Layers: 4"];
+
+        assert!(Layer::overlay(&lines, &viewport()).quads.is_empty());
+
+        let overlay = Layer::overlay_with(
+            &lines,
+            &viewport(),
+            OverlayStyle {
+                panel: Some([0.0, 0.0, 0.0, 0.5]),
+                ..OverlayStyle::default()
+            },
+        );
+
+        assert_eq!(overlay.quads.len(), 1);
+
+        let panel = &overlay.quads[0];
+
+        for text in &overlay.text {
+            let width = text.content.chars().count() as f32 * text.size * 0.6;
+
+            assert!(panel.position[0] <= text.bounds.x);
+            assert!(panel.position[1] <= text.bounds.y);
+            assert!(text.bounds.x + width <= panel.position[0] + panel.size[0]);
+            assert!(
+                text.bounds.y + text.size <= panel.position[1] + panel.size[1]
+            );
+        }
+    }
+
+    fn clipped_quad(clip: Rectangle, quad: Rectangle) -> Primitive {
+        Primitive::Clip {
+            bounds: clip,
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(Primitive::Quad {
+                bounds: quad,
+                background: Background::Color(Color::BLACK).into(),
+                border_radius: [0.0; 4],
+                border_width: 0.0.into(),
+                border_color: Color::TRANSPARENT.into(),
+                border_alignment: BorderAlignment::Inside,
+                corner_smoothing: 0.0,
+            }),
+        }
+    }
+
+    #[test]
+    fn clip_inside_translate_is_translated() {
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(100.0, 50.0),
+            content: Box::new(clipped_quad(
+                Rectangle::new(Point::new(10.0, 10.0), Size::new(40.0, 30.0)),
+                Rectangle::new(Point::new(20.0, 20.0), Size::new(10.0, 10.0)),
+            )),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers.len(), 2);
+        assert_eq!(
+            layers[1].bounds,
+            Rectangle::new(Point::new(110.0, 60.0), Size::new(40.0, 30.0))
+        );
+
+        let quad = &layers[1].quads[0];
+
+        assert_eq!(quad.position, [120.0, 70.0]);
+        assert!(layers[1].bounds.contains(Point::new(
+            quad.position[0] + quad.size[0],
+            quad.position[1] + quad.size[1],
+        )));
+    }
+
+    #[test]
+    fn clip_inside_scale_is_scaled_and_intersected() {
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(clipped_quad(
+                Rectangle::new(Point::new(350.0, 10.0), Size::new(100.0, 20.0)),
+                Rectangle::new(Point::new(360.0, 15.0), Size::new(5.0, 5.0)),
+            )),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers.len(), 2);
+
+        // The scaled clip is cut by the 800x600 viewport
+        assert_eq!(
+            layers[1].bounds,
+            Rectangle::new(Point::new(700.0, 20.0), Size::new(100.0, 40.0))
+        );
+
+        let quad = &layers[1].quads[0];
+
+        assert_eq!(quad.position, [720.0, 30.0]);
+        assert_eq!(quad.size, [10.0, 10.0]);
+    }
+
+    #[test]
+    fn clip_antialiasing_is_carried_to_its_layer() {
+        let clip = |x, antialias| {
+            let bounds =
+                Rectangle::new(Point::new(x, 0.0), Size::new(10.0, 10.0));
+
+            Primitive::Clip {
+                bounds,
+                antialias,
+                mode: ClipMode::Intersect,
+                content: Box::new(Primitive::Quad {
+                    bounds,
+                    background: Background::Color(Color::BLACK).into(),
+                    border_radius: [0.0; 4],
+                    border_width: 0.0.into(),
+                    border_color: Color::TRANSPARENT.into(),
+                    border_alignment: BorderAlignment::Inside,
+                    corner_smoothing: 0.0,
+                }),
+            }
+        };
+
+        let primitives = [clip(0.0, true), clip(20.0, false)];
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers.len(), 3);
+        assert!(!layers[0].antialias_clip);
+        assert!(layers[1].antialias_clip);
+        assert!(!layers[2].antialias_clip);
+    }
+
+    #[test]
+    fn nested_clips_share_a_single_layer() {
+        let clip = |bounds, content| Primitive::Clip {
+            bounds,
+            antialias: false,
+            mode: ClipMode::Intersect,
+            content: Box::new(content),
+        };
+
+        let primitives = [clip(
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 100.0)),
+            Primitive::Translate {
+                translation: Vector::new(20.0, 0.0),
+                content: Box::new(clip(
+                    Rectangle::new(
+                        Point::new(0.0, 0.0),
+                        Size::new(50.0, 200.0),
+                    ),
+                    Primitive::Scale {
+                        scale: 2.0,
+                        content: Box::new(clip(
+                            Rectangle::new(
+                                Point::new(0.0, 20.0),
+                                Size::new(100.0, 100.0),
+                            ),
+                            text(Rectangle::new(
+                                Point::new(0.0, 20.0),
+                                Size::new(100.0, 20.0),
+                            )),
+                        )),
+                    },
+                )),
+            },
+        )];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        // [10, 110] x [10, 110], [20, 70] x [0, 200], [20, 220] x [40, 240]
+        assert_eq!(layers.len(), 2);
+        assert_eq!(
+            layers[1].bounds,
+            Rectangle::new(Point::new(20.0, 40.0), Size::new(50.0, 70.0))
+        );
+        assert_eq!(layers[1].text[0].bounds.position(), Point::new(20.0, 40.0));
+    }
+
+    #[test]
+    fn replacing_clips_escape_their_parent_but_not_the_viewport() {
+        let nested = |mode| {
+            let primitives = [Primitive::Clip {
+                bounds: Rectangle::new(
+                    Point::new(10.0, 10.0),
+                    Size::new(20.0, 20.0),
+                ),
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(Primitive::Clip {
+                    bounds: Rectangle::new(
+                        Point::new(0.0, 0.0),
+                        Size::new(1000.0, 100.0),
+                    ),
+                    antialias: false,
+                    mode,
+                    content: Box::new(text(Rectangle::new(
+                        Point::new(0.0, 0.0),
+                        Size::new(100.0, 20.0),
+                    ))),
+                }),
+            }];
+
+            Layer::generate(&primitives, &viewport())
+                .iter()
+                .map(|layer| layer.bounds)
+                .collect::<Vec<_>>()
+        };
+
+        let parent =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0));
+
+        // Intersecting clips are merged into a single layer
+        assert_eq!(
+            nested(ClipMode::Intersect),
+            [
+                Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0)),
+                parent
+            ]
+        );
+        assert_eq!(
+            nested(ClipMode::Replace)[2],
+            Rectangle::new(Point::ORIGIN, Size::new(800.0, 100.0))
+        );
+    }
+
+    #[test]
+    fn cache_keys_are_preserved_and_distinguish_caches() {
+        let contents = std::sync::Arc::new(text(Rectangle::new(
+            Point::new(10.0, 10.0),
+            Size::new(100.0, 20.0),
+        )));
+
+        let cached = |key| Primitive::Cached {
+            key,
+            cache: contents.clone(),
+        };
+
+        let primitives = [cached(1), cached(2)];
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers[0].cache_keys, [1, 2]);
+        assert_eq!(layers[0].text.len(), 2);
+
+        // A consumer that only prepares the contents of unseen caches
+        let mut prepared = std::collections::HashSet::new();
+        let mut preparations = 0;
+
+        for _ in 0..2 {
+            let layers = Layer::generate(&primitives, &viewport());
+
+            for key in &layers[0].cache_keys {
+                if prepared.insert(*key) {
+                    preparations += 1;
+                }
+            }
+        }
+
+        assert_eq!(preparations, 2);
+    }
+
+    fn cube(transformation: Transformation) -> Primitive {
+        let vertices = (0..8)
+            .map(|i| triangle::ColoredVertex3D {
+                position: [
+                    (i & 1) as f32,
+                    ((i >> 1) & 1) as f32,
+                    ((i >> 2) & 1) as f32,
+                ],
+                color: [1.0, 0.0, 0.0, 1.0],
+            })
+            .collect();
+
+        Primitive::Mesh3D {
+            buffers: triangle::Mesh3D {
+                vertices,
+                indices: vec![
+                    0, 1, 2, 2, 1, 3, // Front
+                    4, 5, 6, 6, 5, 7, // Back
+                ],
+                alpha_mode: AlphaMode::Straight,
+            },
+            transformation,
+        }
+    }
+
+    #[test]
+    fn mesh_3d_clip_bounds_are_projected() {
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(10.0, 20.0),
+            content: Box::new(cube(
+                Transformation::translate(200.0, 150.0)
+                    * Transformation::scale(100.0, 50.0),
+            )),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let mesh = &layers[0].meshes_3d[0];
+
+        assert_eq!(
+            mesh.clip_bounds,
+            Rectangle::new(Point::new(210.0, 170.0), Size::new(100.0, 50.0))
+        );
+        assert_eq!(
+            mesh.transformation.project_point([1.0, 1.0, 1.0]),
+            Point::new(310.0, 220.0)
+        );
+    }
+
+    #[test]
+    fn mesh_3d_off_screen_is_culled() {
+        let primitives = [cube(
+            Transformation::translate(-500.0, 100.0)
+                * Transformation::scale(100.0, 100.0),
+        )];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert!(layers[0].meshes_3d.is_empty());
+    }
+
+    #[test]
+    fn flattened_meshes_3d_are_drawn_back_to_front() {
+        let primitives = [cube(
+            Transformation::translate(200.0, 150.0)
+                * Transformation::scale(100.0, 50.0),
+        )];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let mesh = &layers[0].meshes_3d[0];
+
+        match mesh.flattened() {
+            Mesh::Solid {
+                origin,
+                buffers,
+                clip_bounds,
+            } => {
+                assert_eq!(origin, Point::ORIGIN);
+                assert_eq!(clip_bounds, mesh.clip_bounds);
+                assert_eq!(buffers.vertices[7].position, [300.0, 200.0]);
+
+                // The back face has a greater depth than the front one
+                assert_eq!(
+                    buffers.indices,
+                    [4, 5, 6, 6, 5, 7, 0, 1, 2, 2, 1, 3]
+                );
+            }
+            Mesh::Gradient { .. } => panic!("expected a solid mesh"),
+        }
+    }
+
+    #[test]
+    fn vertex_colors_are_linearized_and_preserved() {
+        let colors = [
+            Color::from_rgb(1.0, 0.0, 0.0),
+            Color::from_rgb(0.0, 0.5, 0.0),
+            Color::from_rgb(0.0, 0.0, 0.25),
+            Color::from_rgba(0.5, 0.5, 0.5, 0.5),
+        ];
+
+        let corners = [
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 10.0),
+            Point::new(10.0, 10.0),
+        ];
+
+        let primitives = [Primitive::SolidMesh {
+            buffers: triangle::Mesh2D {
+                vertices: corners
+                    .into_iter()
+                    .zip(colors)
+                    .map(|(corner, color)| {
+                        triangle::ColoredVertex2D::new(corner, color)
+                    })
+                    .collect(),
+                indices: vec![0, 1, 2, 2, 1, 3],
+                alpha_mode: AlphaMode::Straight,
+            },
+            size: Size::new(10.0, 10.0),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].meshes[0] {
+            Mesh::Solid { buffers, .. } => {
+                assert_eq!(buffers.vertices.len(), 4);
+
+                for (vertex, color) in buffers.vertices.iter().zip(colors) {
+                    assert_eq!(vertex.color, color.into_linear());
+                }
+
+                assert_ne!(buffers.vertices[1].color, [0.0, 0.5, 0.0, 1.0]);
+            }
+            Mesh::Gradient { .. } => panic!("expected a solid mesh"),
+        }
+    }
+
+    #[test]
+    fn shadow_spread_enlarges_bounds_and_culling_margin() {
+        let bounds =
+            Rectangle::new(Point::new(-20.0, 10.0), Size::new(10.0, 10.0));
+
+        let spread = |spread_radius| Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Shadow {
+                bounds,
+                color: Color::BLACK,
+                border_radius: [0.0; 4],
+                offset: Vector::new(0.0, 0.0),
+                blur_radius: 5.0,
+                spread_radius,
+            }),
+        };
+
+        // The blur alone does not reach the viewport
+        let primitives = [spread(0.0)];
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert!(layers[0].shadows.is_empty());
+
+        // The spread makes it visible
+        let primitives = [spread(10.0)];
+        let layers = Layer::generate(&primitives, &viewport());
+        let shadow = &layers[0].shadows[0];
+
+        assert_eq!(shadow.blur_radius, 10.0);
+        assert_eq!(shadow.spread_radius, 20.0);
+        assert_eq!(
+            shadow.spread_bounds(),
+            Rectangle::new(Point::new(-60.0, 0.0), Size::new(60.0, 60.0))
+        );
+        assert_eq!(
+            shadow.visible_bounds(),
+            Rectangle::new(Point::new(-70.0, -10.0), Size::new(80.0, 80.0))
+        );
+    }
+
+    fn rounded_quad(bounds: Rectangle, radius: f32) -> Primitive {
+        Primitive::Quad {
+            bounds,
+            background: Background::Color(Color::BLACK).into(),
+            border_radius: [radius; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        }
+    }
+
+    #[test]
+    fn corner_smoothing_is_kept_unscaled() {
+        let quad = |corner_smoothing| Primitive::Quad {
+            bounds: Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(20.0, 20.0),
+            ),
+            background: Color::BLACK.into(),
+            border_radius: [5.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing,
+        };
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Group {
+                primitives: vec![quad(0.6), quad(0.0)],
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let quads = &layers[0].quads;
+
+        assert_eq!(quads[0].corner_smoothing, 0.6);
+        assert_eq!(quads[0].border_radius, [10.0; 4]);
+        assert_eq!(quads[1].corner_smoothing, 0.0);
+    }
+
+    #[test]
+    fn deeply_nested_primitives_do_not_overflow() {
+        let mut primitive = rounded_quad(
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(20.0, 20.0)),
+            0.0,
+        );
+
+        for i in 0..100_000 {
+            let translation = if i % 2 == 0 {
+                Vector::new(1.0, 2.0)
+            } else {
+                Vector::new(-1.0, -2.0)
+            };
+
+            primitive = Primitive::Translate {
+                translation,
+                content: Box::new(primitive),
+            };
+        }
+
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 10.0),
+            content: Box::new(primitive),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers[0].quads.len(), 1);
+        assert_eq!(layers[0].quads[0].position, [15.0, 20.0]);
+
+        let bounds = primitives[0].bounds(Transformation::identity());
+
+        assert_eq!(
+            bounds,
+            Some(Rectangle::new(
+                Point::new(15.0, 20.0),
+                Size::new(20.0, 20.0)
+            ))
+        );
+
+        let _ = primitives[0].content_hash();
+    }
+
+    #[test]
+    fn primitives_past_the_max_depth_are_skipped() {
+        let quad = |x| {
+            rounded_quad(
+                Rectangle::new(Point::new(x, 10.0), Size::new(10.0, 10.0)),
+                0.0,
+            )
+        };
+
+        let nested = |depth, primitive| {
+            (0..depth).fold(primitive, |content, _| Primitive::Translate {
+                translation: Vector::new(1.0, 0.0),
+                content: Box::new(content),
+            })
+        };
+
+        let primitives = [
+            nested(2, quad(10.0)),
+            nested(3, quad(20.0)),
+            Primitive::Clip {
+                bounds: Rectangle::new(Point::ORIGIN, Size::new(100.0, 100.0)),
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(nested(2, quad(30.0))),
+            },
+        ];
+
+        let generate = |max_depth| {
+            Layer::generate_with(
+                &primitives,
+                &viewport(),
+                Settings {
+                    max_depth,
+                    ..Settings::default()
+                },
+            )
+        };
+
+        let layers = generate(None);
+
+        assert_eq!(layers[0].quads.len(), 2);
+        assert_eq!(layers[1].quads.len(), 1);
+
+        let layers = generate(Some(2));
+
+        assert_eq!(layers[0].quads.len(), 1);
+        assert_eq!(layers[0].quads[0].position, [12.0, 10.0]);
+        assert!(layers[1].quads.is_empty());
+
+        let layers = generate(Some(3));
+
+        assert_eq!(layers[0].quads.len(), 2);
+        assert_eq!(layers[1].quads.len(), 1);
+        assert_eq!(layers[1].quads[0].position, [32.0, 10.0]);
+    }
+
+    #[test]
+    fn hit_test_skips_rounded_corners() {
+        let primitives = [rounded_quad(
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 50.0)),
+            20.0,
+        )];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(
+            Layer::hit_test(&layers, Point::new(60.0, 30.0)),
+            Some(Hit::Quad { layer: 0, index: 0 })
+        );
+        assert_eq!(
+            Layer::hit_test(&layers, Point::new(100.0, 50.0)),
+            Some(Hit::Quad { layer: 0, index: 0 })
+        );
+        assert_eq!(Layer::hit_test(&layers, Point::new(12.0, 12.0)), None);
+        assert_eq!(Layer::hit_test(&layers, Point::new(109.0, 59.0)), None);
+    }
+
+    #[test]
+    fn hit_test_respects_clip_bounds() {
+        let quad =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(100.0, 100.0));
+
+        let primitives = [
+            rounded_quad(quad, 0.0),
+            Primitive::Clip {
+                bounds: Rectangle::new(
+                    Point::new(0.0, 0.0),
+                    Size::new(50.0, 50.0),
+                ),
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(rounded_quad(quad, 0.0)),
+            },
+        ];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(
+            Layer::hit_test(&layers, Point::new(20.0, 20.0)),
+            Some(Hit::Quad { layer: 1, index: 0 })
+        );
+        assert_eq!(
+            Layer::hit_test(&layers, Point::new(80.0, 80.0)),
+            Some(Hit::Quad { layer: 0, index: 0 })
+        );
+        assert_eq!(Layer::hit_test(&layers, Point::new(200.0, 200.0)), None);
+    }
+
+    #[test]
+    fn text_max_lines_survive() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 20.0));
+
+        let mut limited = text(bounds);
+
+        if let Primitive::Text { max_lines, .. } = &mut limited {
+            assert_eq!(*max_lines, None);
+
+            *max_lines = Some(3);
+        }
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Group {
+                primitives: vec![text(bounds), limited],
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let text = &layers[0].text;
+
+        assert_eq!(text[0].max_lines, None);
+        assert_eq!(text[1].max_lines, Some(3));
+    }
+
+    #[test]
+    fn text_truncation_survives_and_needs_finite_width() {
+        let truncated = |bounds| {
+            let mut primitive = text(bounds);
+
+            if let Primitive::Text { truncate, .. } = &mut primitive {
+                *truncate = Truncate::Ellipsis;
+            }
+
+            primitive
+        };
+
+        let primitives = [Primitive::Scale {
+            scale: 2.0,
+            content: Box::new(Primitive::Group {
+                primitives: vec![
+                    truncated(Rectangle::new(
+                        Point::new(10.0, 10.0),
+                        Size::new(50.0, 20.0),
+                    )),
+                    truncated(Rectangle::new(
+                        Point::new(10.0, 40.0),
+                        Size::new(f32::INFINITY, 20.0),
+                    )),
+                ],
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+        let text = &layers[0].text;
+
+        assert_eq!(text[0].truncate, Truncate::Ellipsis);
+        assert_eq!(text[0].bounds.width, 100.0);
+        assert_eq!(text[1].truncate, Truncate::None);
+    }
+
+    #[test]
+    fn text_backgrounds_are_linearized() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 20.0));
+        let highlight = Color::from_rgb(1.0, 0.5, 0.0);
+
+        let mut highlighted = text(bounds);
+
+        if let Primitive::Text { background, .. } = &mut highlighted {
+            *background = Some(highlight);
+        }
+
+        let primitives = [highlighted, text(bounds)];
+        let layers = Layer::generate(&primitives, &viewport());
+        let layer = &layers[0];
+
+        assert_eq!(layer.text[0].background, Some(highlight.into_linear()));
+        assert_eq!(layer.text[1].background, None);
+        assert!(layer.quads.is_empty());
+    }
+
+    #[test]
+    fn text_shadows_are_transformed() {
+        let mut shadowed = text(Rectangle::new(
+            Point::new(10.0, 10.0),
+            Size::new(50.0, 20.0),
+        ));
+
+        if let Primitive::Text { shadow, .. } = &mut shadowed {
+            *shadow = Some(crate::TextShadow {
+                color: Color::BLACK,
+                offset: Vector::new(1.0, 2.0),
+                blur: 3.0,
+            });
+        }
+
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(shadowed),
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert_eq!(
+            layers[0].text[0].shadow,
+            Some(TextShadow {
+                color: [0.0, 0.0, 0.0, 1.0],
+                offset: Vector::new(2.0, 4.0),
+                blur: 6.0,
+            })
+        );
+    }
+
+    #[test]
+    fn nine_patch_bounds_are_transformed_but_not_insets() {
+        let handle = image::Handle::from_pixels(4, 4, vec![255; 64]);
+        let insets = [1.0, 2.0, 1.0, 2.0];
+
+        let primitives = [Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(Primitive::NinePatch {
+                    handle: handle.clone(),
+                    bounds: Rectangle::new(
+                        Point::new(10.0, 10.0),
+                        Size::new(40.0, 20.0),
+                    ),
+                    insets,
+                }),
+            }),
+        }];
+
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].images[..] {
+            [Image::NinePatch {
+                handle: processed,
+                bounds,
+                insets: processed_insets,
+            }] => {
+                assert_eq!(processed.id(), handle.id());
+                assert_eq!(
+                    *bounds,
+                    Rectangle::new(
+                        Point::new(25.0, 25.0),
+                        Size::new(80.0, 40.0)
+                    )
+                );
+                assert_eq!(*processed_insets, insets);
+            }
+            images => panic!("expected a nine-patch image, got {:?}", images),
+        }
+    }
+
+    #[test]
+    fn half_transparent_colors_follow_alpha_mode() {
+        let color = Color::from_rgba(1.0, 0.5, 0.0, 0.5);
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(10.0, 10.0));
+
+        let primitives = [
+            Primitive::Quad {
+                bounds,
+                background: Background::RadialGradient {
+                    center: Point::new(5.0, 5.0),
+                    radius: 5.0,
+                    start: color,
+                    end: Color::TRANSPARENT,
+                }
+                .into(),
+                border_radius: [0.0; 4],
+                border_width: 0.0.into(),
+                border_color: Color::TRANSPARENT.into(),
+                border_alignment: BorderAlignment::Inside,
+                corner_smoothing: 0.0,
+            },
+            Primitive::Shadow {
+                bounds,
+                color,
+                border_radius: [0.0; 4],
+                offset: Vector::new(0.0, 0.0),
+                blur_radius: 2.0,
+                spread_radius: 0.0,
+            },
+        ];
+
+        let straight = Layer::generate(&primitives, &viewport());
+
+        let premultiplied = Layer::generate_with(
+            &primitives,
+            &viewport(),
+            Settings {
+                alpha_mode: AlphaMode::Premultiplied,
+                ..Settings::default()
+            },
+        );
+
+        assert_eq!(straight[0].quads[0].color, color.into_linear());
+        assert_eq!(straight[0].shadows[0].color, color.into_linear());
+        assert_eq!(
+            straight[0].quad_gradients[0].stops[0].1,
+            color.into_linear()
+        );
+
+        let expected = color.into_linear_premultiplied();
+
+        assert_eq!(premultiplied[0].quads[0].color, expected);
+        assert_eq!(premultiplied[0].shadows[0].color, expected);
+        assert_eq!(premultiplied[0].quad_gradients[0].stops[0].1, expected);
+        assert_ne!(expected, color.into_linear());
+    }
+
+    fn filled_triangle(translation: Vector) -> Primitive {
+        Primitive::Translate {
+            translation,
+            content: Box::new(Primitive::Scale {
+                scale: 2.0,
+                content: Box::new(Primitive::Fill {
+                    path: crate::path::Path::polygon([
+                        Point::new(0.0, 0.0),
+                        Point::new(10.0, 0.0),
+                        Point::new(5.0, 10.0),
+                    ]),
+                    fill_rule: crate::path::FillRule::NonZero,
+                    color: Color::BLACK,
+                }),
+            }),
+        }
+    }
+
+    #[test]
+    fn filled_path_is_tessellated_and_transformed() {
+        let primitives = [filled_triangle(Vector::new(10.0, 20.0))];
+        let layers = Layer::generate(&primitives, &viewport());
+
+        match &layers[0].meshes[..] {
+            [Mesh::Solid {
+                origin,
+                buffers,
+                clip_bounds,
+            }] => {
+                let vertices: Vec<Point> = buffers
+                    .vertices
+                    .iter()
+                    .map(|vertex| {
+                        Point::new(
+                            origin.x + vertex.position[0],
+                            origin.y + vertex.position[1],
+                        )
+                    })
+                    .collect();
+
+                assert_eq!(
+                    vertices,
+                    [
+                        Point::new(10.0, 20.0),
+                        Point::new(30.0, 20.0),
+                        Point::new(20.0, 40.0)
+                    ]
+                );
+                assert_eq!(buffers.indices.len(), 3);
+                assert_eq!(
+                    *clip_bounds,
+                    Rectangle::new(
+                        Point::new(10.0, 20.0),
+                        Size::new(20.0, 20.0)
+                    )
+                );
+            }
+            meshes => panic!("expected a single solid mesh, got {:?}", meshes),
+        }
+
+        let primitives = [filled_triangle(Vector::new(-100.0, 20.0))];
+        let layers = Layer::generate(&primitives, &viewport());
+
+        assert!(layers[0].meshes.is_empty());
+    }
+
+    fn stroked_line(scale: f32) -> Primitive {
+        Primitive::Scale {
+            scale,
+            content: Box::new(Primitive::Stroke {
+                path: crate::path::Path::new()
+                    .move_to(Point::new(10.0, 10.0))
+                    .line_to(Point::new(30.0, 10.0)),
+                width: 4.0,
+                color: Color::BLACK,
+                line_cap: crate::path::LineCap::Butt,
+                line_join: crate::path::LineJoin::Miter,
+            }),
+        }
+    }
+
+    #[test]
+    fn stroked_line_is_a_quad_with_scaled_width() {
+        for (scale, expected) in [
+            (
+                1.0,
+                Rectangle::new(Point::new(10.0, 8.0), Size::new(20.0, 4.0)),
+            ),
+            (
+                2.0,
+                Rectangle::new(Point::new(20.0, 16.0), Size::new(40.0, 8.0)),
+            ),
+        ] {
+            let primitives = [stroked_line(scale)];
+            let layers = Layer::generate(&primitives, &viewport());
+
+            match &layers[0].meshes[..] {
+                [Mesh::Solid {
+                    buffers,
+                    clip_bounds,
+                    ..
+                }] => {
+                    assert_eq!(buffers.vertices.len(), 4);
+                    assert_eq!(buffers.indices.len(), 6);
+                    assert_eq!(*clip_bounds, expected);
+                }
+                meshes => {
+                    panic!("expected a single solid mesh, got {:?}", meshes)
+                }
             }
         }
     }
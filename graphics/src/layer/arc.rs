@@ -0,0 +1,105 @@
+use crate::layer::Mesh;
+use crate::path::{LineCap, LineJoin, Path};
+use crate::triangle::{self, AlphaMode};
+use crate::{Point, Rectangle};
+
+use std::borrow::Cow;
+
+/// The maximum distance between an [`Arc`] and the segments approximating
+/// it, in logical pixels.
+const TOLERANCE: f32 = 0.05;
+
+/// A stroked circular arc.
+///
+/// Renderers draw it as a [`Mesh`], tessellated with [`Arc::mesh`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arc {
+    /// The center of the [`Arc`].
+    pub center: [f32; 2],
+
+    /// The radius of the [`Arc`].
+    pub radius: f32,
+
+    /// The stroke width of the [`Arc`].
+    pub width: f32,
+
+    /// The angle where the [`Arc`] starts, in radians.
+    pub start_angle: f32,
+
+    /// The angle where the [`Arc`] ends, in radians.
+    pub end_angle: f32,
+
+    /// The color of the [`Arc`], in __linear RGB__.
+    pub color: [f32; 4],
+}
+
+impl Arc {
+    /// Tessellates the stroke of the [`Arc`] into a solid [`Mesh`] with the
+    /// given clipping bounds, unless it is empty.
+    ///
+    /// Its color is labeled with the given [`AlphaMode`], which must be the
+    /// one of the [`Settings`] used to generate the [`Arc`].
+    ///
+    /// [`Settings`]: crate::layer::Settings
+    pub fn mesh(
+        &self,
+        alpha_mode: AlphaMode,
+        clip_bounds: Rectangle,
+    ) -> Option<Mesh<'static>> {
+        let sweep = self.end_angle - self.start_angle;
+
+        let is_visible = self.radius > 0.0
+            && self.radius.is_finite()
+            && sweep.is_finite()
+            && sweep != 0.0;
+
+        if !is_visible {
+            return None;
+        }
+
+        // The angle covered by each segment keeps its midpoint within the
+        // tolerance of the arc
+        let step = 2.0 * (1.0 - (TOLERANCE / self.radius).min(1.0)).acos();
+        let is_closed = sweep.abs() >= std::f32::consts::TAU;
+        let sweep = sweep.clamp(-std::f32::consts::TAU, std::f32::consts::TAU);
+        let segments = (sweep.abs() / step).ceil().max(1.0) as usize;
+
+        let mut points = (0..=segments).map(|i| {
+            let angle = self.start_angle + sweep * i as f32 / segments as f32;
+
+            Point::new(self.radius * angle.cos(), self.radius * angle.sin())
+        });
+
+        let path = if is_closed {
+            // The last point of a full circle is its first one
+            Path::polygon(points.take(segments))
+        } else {
+            let first = points.next().unwrap_or(Point::ORIGIN);
+
+            points.fold(Path::new().move_to(first), Path::line_to)
+        };
+
+        let (points, indices) =
+            path.stroke(self.width, LineCap::Butt, LineJoin::Miter);
+
+        if indices.is_empty() {
+            return None;
+        }
+
+        Some(Mesh::Solid {
+            origin: Point::new(self.center[0], self.center[1]),
+            buffers: Cow::Owned(triangle::Mesh2D {
+                vertices: points
+                    .into_iter()
+                    .map(|point| triangle::ColoredVertex2D {
+                        position: [point.x, point.y],
+                        color: self.color,
+                    })
+                    .collect(),
+                indices,
+                alpha_mode,
+            }),
+            clip_bounds,
+        })
+    }
+}
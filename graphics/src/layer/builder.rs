@@ -0,0 +1,139 @@
+use crate::layer::{Image, Layer, Mesh, Quad, Text};
+use crate::Rectangle;
+
+/// A builder of a [`Layer`].
+///
+/// It is a shorthand for creating an empty [`Layer`] and pushing primitives
+/// into its lists, which is handy in tests and tools that synthesize layers.
+#[derive(Debug)]
+pub struct LayerBuilder<'a> {
+    layer: Layer<'a>,
+}
+
+impl<'a> LayerBuilder<'a> {
+    /// Creates a new [`LayerBuilder`] of an empty [`Layer`] with the given
+    /// clipping bounds.
+    pub fn new(bounds: Rectangle) -> Self {
+        Self {
+            layer: Layer::new(bounds),
+        }
+    }
+
+    /// Adds a [`Quad`] to the [`Layer`].
+    pub fn quad(mut self, quad: Quad) -> Self {
+        self.layer.quads.push(quad);
+        self
+    }
+
+    /// Adds some [`Text`] to the [`Layer`].
+    pub fn text(mut self, text: Text<'a>) -> Self {
+        self.layer.text.push(text);
+        self
+    }
+
+    /// Adds an [`Image`] to the [`Layer`].
+    pub fn image(mut self, image: Image) -> Self {
+        self.layer.images.push(image);
+        self
+    }
+
+    /// Adds a [`Mesh`] to the [`Layer`].
+    pub fn mesh(mut self, mesh: Mesh<'a>) -> Self {
+        self.layer.meshes.push(mesh);
+        self
+    }
+
+    /// Builds the [`Layer`].
+    pub fn build(self) -> Layer<'a> {
+        self.layer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::Filter;
+    use crate::triangle;
+    use crate::{alignment, Font, ImageFit, LineHeight, Point, Size, Truncate};
+
+    use iced_native::image;
+    use std::borrow::Cow;
+
+    #[test]
+    fn builder_pushes_one_of_each_primitive() {
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(100.0, 100.0));
+        let small = Rectangle::new(Point::new(10.0, 10.0), Size::new(5.0, 5.0));
+
+        let quad = Quad {
+            position: [10.0, 10.0],
+            size: [5.0, 5.0],
+            color: [1.0, 0.0, 0.0, 1.0],
+            border_color: [0.0; 4],
+            border_radius: [0.0; 4],
+            border_width: [0.0; 4],
+            border_only: 0,
+            corner_smoothing: 0.0,
+        };
+
+        let text = Text {
+            content: "Hello",
+            bounds: small,
+            color: [0.0, 0.0, 0.0, 1.0],
+            size: 16.0,
+            letter_spacing: 0.0,
+            line_height: LineHeight::default(),
+            font: Font::Default,
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Top,
+            truncate: Truncate::None,
+            max_lines: None,
+            background: None,
+            shadow: None,
+            rounded_clip: None,
+        };
+
+        let image = Image::Raster {
+            handle: image::Handle::from_pixels(1, 1, vec![255; 4]),
+            bounds: small,
+            source: None,
+            tint: [1.0; 4],
+            opacity: 1.0,
+            rotation: 0.0,
+            filter: Filter::Linear,
+            fit: ImageFit::Fill,
+        };
+
+        let mesh = Mesh::Solid {
+            origin: small.position(),
+            buffers: Cow::Owned(triangle::Mesh2D {
+                vertices: vec![
+                    triangle::ColoredVertex2D {
+                        position: [0.0, 0.0],
+                        color: [0.0, 0.0, 0.0, 1.0],
+                    };
+                    3
+                ],
+                indices: vec![0, 1, 2],
+                alpha_mode: triangle::AlphaMode::Straight,
+            }),
+            clip_bounds: small,
+        };
+
+        let layer = Layer::builder(bounds)
+            .quad(quad)
+            .text(text)
+            .image(image)
+            .mesh(mesh)
+            .build();
+
+        assert_eq!(layer.bounds, bounds);
+        assert_eq!(layer.quads.len(), 1);
+        assert_eq!(layer.quads[0].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(layer.text.len(), 1);
+        assert_eq!(layer.text[0].content, "Hello");
+        assert_eq!(layer.images.len(), 1);
+        assert_eq!(layer.images[0].bounds(), small);
+        assert_eq!(layer.meshes.len(), 1);
+        assert_eq!(layer.meshes[0].indices(), &[0, 1, 2]);
+    }
+}
@@ -0,0 +1,11 @@
+use crate::Rectangle;
+
+/// A clipping region with rounded corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundedClip {
+    /// The bounds of the [`RoundedClip`].
+    pub bounds: Rectangle,
+
+    /// The border radius of the [`RoundedClip`].
+    pub border_radius: [f32; 4],
+}
@@ -0,0 +1,323 @@
+use crate::layer::{quad, Image, Layer, Mesh, RoundedClip};
+use crate::{Font, Gradient, LineHeight, Rectangle};
+
+use iced_native::Hasher;
+use std::hash::{Hash, Hasher as _};
+use std::mem;
+
+/// Computes a hash of all the contents of the given layers.
+///
+/// Floats are hashed by their bit pattern and images by the id of their
+/// handle. Comparing the hashes of consecutive frames can be used to skip
+/// presenting a frame identical to the previous one.
+pub fn hash_layers(layers: &[Layer<'_>]) -> u64 {
+    let mut state = Hasher::default();
+
+    layers.len().hash(&mut state);
+
+    for layer in layers {
+        rectangle(&mut state, &layer.bounds);
+        rounded_clip(&mut state, layer.rounded_clip.as_ref());
+        layer.antialias_clip.hash(&mut state);
+        layer.tint.is_some().hash(&mut state);
+
+        if let Some(tint) = &layer.tint {
+            floats(&mut state, tint);
+        }
+
+        layer.quads.len().hash(&mut state);
+        state.write(bytemuck::cast_slice(&layer.quads));
+
+        layer.quad_gradients.len().hash(&mut state);
+
+        for gradient in &layer.quad_gradients {
+            gradient.quad.hash(&mut state);
+            mem::discriminant(&gradient.target).hash(&mut state);
+            mem::discriminant(&gradient.shape).hash(&mut state);
+
+            match &gradient.shape {
+                quad::Shape::Linear { start, end } => {
+                    floats(&mut state, start);
+                    floats(&mut state, end);
+                }
+                quad::Shape::Radial { center, radius } => {
+                    floats(&mut state, center);
+                    floats(&mut state, &[*radius]);
+                }
+                quad::Shape::Conic {
+                    center,
+                    start_angle,
+                } => {
+                    floats(&mut state, center);
+                    floats(&mut state, &[*start_angle]);
+                }
+            }
+
+            gradient.stops.len().hash(&mut state);
+
+            for (offset, color) in &gradient.stops {
+                floats(&mut state, &[*offset]);
+                floats(&mut state, color);
+            }
+        }
+
+        layer.quad_images.len().hash(&mut state);
+
+        for image in &layer.quad_images {
+            image.quad.hash(&mut state);
+            image.handle.id().hash(&mut state);
+            mem::discriminant(&image.fit).hash(&mut state);
+            image.repeat.hash(&mut state);
+
+            if let Some(size) = image.tile_size {
+                floats(&mut state, &[size.width, size.height]);
+            }
+        }
+
+        layer.quad_batches.len().hash(&mut state);
+
+        for batch in &layer.quad_batches {
+            batch.instances.len().hash(&mut state);
+            state.write(bytemuck::cast_slice(&batch.instances));
+        }
+
+        layer.shadows.len().hash(&mut state);
+
+        for shadow in &layer.shadows {
+            rectangle(&mut state, &shadow.bounds);
+            floats(&mut state, &shadow.color);
+            floats(&mut state, &shadow.border_radius);
+            floats(
+                &mut state,
+                &[
+                    shadow.offset.x,
+                    shadow.offset.y,
+                    shadow.blur_radius,
+                    shadow.spread_radius,
+                ],
+            );
+            shadow.inset.hash(&mut state);
+            shadow.glow.hash(&mut state);
+        }
+
+        layer.arcs.len().hash(&mut state);
+
+        for arc in &layer.arcs {
+            floats(&mut state, &arc.center);
+            floats(
+                &mut state,
+                &[arc.radius, arc.width, arc.start_angle, arc.end_angle],
+            );
+            floats(&mut state, &arc.color);
+        }
+
+        layer.meshes.len().hash(&mut state);
+
+        for mesh in &layer.meshes {
+            let origin = mesh.origin();
+
+            floats(&mut state, &[origin.x, origin.y]);
+            rectangle(&mut state, &mesh.clip_bounds());
+
+            match mesh {
+                Mesh::Solid { buffers, .. } => {
+                    mem::discriminant(&buffers.alpha_mode).hash(&mut state);
+                    buffers.vertices.len().hash(&mut state);
+                    state.write(bytemuck::cast_slice(&buffers.vertices));
+                    buffers.indices.hash(&mut state);
+                }
+                Mesh::Gradient {
+                    buffers, gradient, ..
+                } => {
+                    buffers.vertices.len().hash(&mut state);
+                    state.write(bytemuck::cast_slice(&buffers.vertices));
+                    buffers.indices.hash(&mut state);
+
+                    match gradient.as_ref() {
+                        Gradient::Linear(linear) => {
+                            floats(
+                                &mut state,
+                                &[
+                                    linear.start.x,
+                                    linear.start.y,
+                                    linear.end.x,
+                                    linear.end.y,
+                                ],
+                            );
+
+                            linear.color_stops.len().hash(&mut state);
+
+                            for stop in &linear.color_stops {
+                                let [r, g, b, a] = stop.color.into_linear();
+
+                                floats(&mut state, &[stop.offset, r, g, b, a]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        layer.meshes_3d.len().hash(&mut state);
+
+        for mesh in &layer.meshes_3d {
+            floats(&mut state, mesh.transformation.as_ref());
+            rectangle(&mut state, &mesh.clip_bounds);
+            mem::discriminant(&mesh.buffers.alpha_mode).hash(&mut state);
+            mesh.buffers.vertices.len().hash(&mut state);
+            state.write(bytemuck::cast_slice(&mesh.buffers.vertices));
+            mesh.buffers.indices.hash(&mut state);
+        }
+
+        layer.text.len().hash(&mut state);
+
+        for text in &layer.text {
+            text.content.hash(&mut state);
+            rectangle(&mut state, &text.bounds);
+            floats(&mut state, &text.color);
+            floats(&mut state, &[text.size, text.letter_spacing]);
+
+            match text.line_height {
+                LineHeight::Relative(factor) => {
+                    false.hash(&mut state);
+                    floats(&mut state, &[factor]);
+                }
+                LineHeight::Absolute(height) => {
+                    true.hash(&mut state);
+                    floats(&mut state, &[height]);
+                }
+            }
+
+            match text.font {
+                Font::Default => 0.hash(&mut state),
+                Font::External { name, .. } => name.hash(&mut state),
+            }
+
+            text.horizontal_alignment.hash(&mut state);
+            text.vertical_alignment.hash(&mut state);
+            text.truncate.hash(&mut state);
+            text.max_lines.hash(&mut state);
+            text.background.is_some().hash(&mut state);
+            floats(&mut state, &text.background.unwrap_or_default());
+            text.shadow.is_some().hash(&mut state);
+
+            if let Some(shadow) = text.shadow {
+                floats(&mut state, &shadow.color);
+                floats(
+                    &mut state,
+                    &[shadow.offset.x, shadow.offset.y, shadow.blur],
+                );
+            }
+            rounded_clip(&mut state, text.rounded_clip.as_ref());
+        }
+
+        layer.images.len().hash(&mut state);
+
+        for image in &layer.images {
+            mem::discriminant(image).hash(&mut state);
+
+            match image {
+                Image::Raster {
+                    handle,
+                    bounds,
+                    source,
+                    tint,
+                    opacity,
+                    rotation,
+                    filter,
+                    fit,
+                } => {
+                    handle.id().hash(&mut state);
+                    rectangle(&mut state, bounds);
+                    source.is_some().hash(&mut state);
+                    rectangle(&mut state, &source.unwrap_or_default());
+                    floats(&mut state, tint);
+                    floats(&mut state, &[*opacity, *rotation]);
+                    filter.hash(&mut state);
+                    mem::discriminant(fit).hash(&mut state);
+                }
+                Image::Vector {
+                    handle,
+                    bounds,
+                    opacity,
+                    color,
+                    rotation,
+                } => {
+                    handle.id().hash(&mut state);
+                    rectangle(&mut state, bounds);
+                    floats(&mut state, &[*opacity, *rotation]);
+                    color.is_some().hash(&mut state);
+                    floats(&mut state, &color.unwrap_or_default());
+                }
+                Image::NinePatch {
+                    handle,
+                    bounds,
+                    insets,
+                } => {
+                    handle.id().hash(&mut state);
+                    rectangle(&mut state, bounds);
+                    floats(&mut state, insets);
+                }
+            }
+        }
+    }
+
+    state.finish()
+}
+
+fn floats(state: &mut Hasher, values: &[f32]) {
+    for value in values {
+        value.to_bits().hash(state);
+    }
+}
+
+fn rectangle(state: &mut Hasher, rectangle: &Rectangle) {
+    floats(
+        state,
+        &[rectangle.x, rectangle.y, rectangle.width, rectangle.height],
+    );
+}
+
+fn rounded_clip(state: &mut Hasher, rounded_clip: Option<&RoundedClip>) {
+    rounded_clip.is_some().hash(state);
+
+    if let Some(clip) = rounded_clip {
+        rectangle(state, &clip.bounds);
+        floats(state, &clip.border_radius);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::Quad;
+    use crate::{Point, Size};
+
+    fn layer(color: [f32; 4]) -> Layer<'static> {
+        let mut layer =
+            Layer::new(Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0)));
+
+        layer.quads.push(Quad {
+            position: [10.0, 10.0],
+            size: [20.0, 20.0],
+            color,
+            border_color: [0.0; 4],
+            border_radius: [0.0; 4],
+            border_width: [0.0; 4],
+            border_only: 0,
+            corner_smoothing: 0.0,
+        });
+
+        layer
+    }
+
+    #[test]
+    fn identical_frames_hash_equal() {
+        let a = [layer([1.0, 0.0, 0.0, 1.0])];
+        let b = [layer([1.0, 0.0, 0.0, 1.0])];
+        let c = [layer([1.0, 0.0, 0.0, 0.5])];
+
+        assert_eq!(hash_layers(&a), hash_layers(&b));
+        assert_ne!(hash_layers(&a), hash_layers(&c));
+    }
+}
@@ -0,0 +1,128 @@
+use crate::layer::{Layer, Quad};
+use crate::{alignment, Point, Rectangle};
+
+/// The top-most primitive of some layers found under a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hit {
+    /// A [`Quad`].
+    Quad {
+        /// The index of the [`Layer`] containing the [`Quad`].
+        layer: usize,
+
+        /// The index of the [`Quad`] in its [`Layer`].
+        index: usize,
+    },
+    /// An [`Image`](crate::layer::Image).
+    Image {
+        /// The index of the [`Layer`] containing the image.
+        layer: usize,
+
+        /// The index of the image in its [`Layer`].
+        index: usize,
+    },
+    /// A [`Text`](crate::layer::Text).
+    Text {
+        /// The index of the [`Layer`] containing the text.
+        layer: usize,
+
+        /// The index of the text in its [`Layer`].
+        index: usize,
+    },
+}
+
+pub(crate) fn test(layers: &[Layer<'_>], point: Point) -> Option<Hit> {
+    layers
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, layer)| layer.bounds.contains(point))
+        .find_map(|(i, layer)| test_layer(i, layer, point))
+}
+
+/// Tests the primitives of a single [`Layer`] in the reverse order that a
+/// renderer draws them.
+fn test_layer(layer: usize, current: &Layer<'_>, point: Point) -> Option<Hit> {
+    let text = current.text.iter().rposition(|text| {
+        let x = match text.horizontal_alignment {
+            alignment::Horizontal::Left => text.bounds.x,
+            alignment::Horizontal::Center => {
+                text.bounds.x - text.bounds.width / 2.0
+            }
+            alignment::Horizontal::Right => text.bounds.x - text.bounds.width,
+        };
+
+        let y = match text.vertical_alignment {
+            alignment::Vertical::Top => text.bounds.y,
+            alignment::Vertical::Center => {
+                text.bounds.y - text.bounds.height / 2.0
+            }
+            alignment::Vertical::Bottom => text.bounds.y - text.bounds.height,
+        };
+
+        // Infinite bounds cover the whole axis
+        (text.bounds.width.is_infinite()
+            || (x..=x + text.bounds.width).contains(&point.x))
+            && (text.bounds.height.is_infinite()
+                || (y..=y + text.bounds.height).contains(&point.y))
+    });
+
+    if let Some(index) = text {
+        return Some(Hit::Text { layer, index });
+    }
+
+    let image = current
+        .images
+        .iter()
+        .rposition(|image| image.bounds().contains(point));
+
+    if let Some(index) = image {
+        return Some(Hit::Image { layer, index });
+    }
+
+    current
+        .quads
+        .iter()
+        .rposition(|quad| contains(quad, point))
+        .map(|index| Hit::Quad { layer, index })
+}
+
+/// Returns whether the given [`Quad`] contains the given point, taking its
+/// rounded corners into account.
+fn contains(quad: &Quad, point: Point) -> bool {
+    let bounds = Rectangle {
+        x: quad.position[0],
+        y: quad.position[1],
+        width: quad.size[0],
+        height: quad.size[1],
+    };
+
+    if !bounds.contains(point) {
+        return false;
+    }
+
+    let center = bounds.center();
+    let max_radius = bounds.width.min(bounds.height) / 2.0;
+
+    // Same order as the CSS `border-radius` attribute
+    let [top_left, top_right, bottom_right, bottom_left] = quad.border_radius;
+
+    let radius = match (point.x > center.x, point.y > center.y) {
+        (false, false) => top_left,
+        (true, false) => top_right,
+        (true, true) => bottom_right,
+        (false, true) => bottom_left,
+    }
+    .min(max_radius);
+
+    // The center of the circle of the closest corner
+    let corner = Point::new(
+        point
+            .x
+            .clamp(bounds.x + radius, bounds.x + bounds.width - radius),
+        point
+            .y
+            .clamp(bounds.y + radius, bounds.y + bounds.height - radius),
+    );
+
+    point.distance(corner) <= radius
+}
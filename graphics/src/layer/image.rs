@@ -1,4 +1,5 @@
-use crate::Rectangle;
+use crate::image::Filter;
+use crate::{ImageFit, Rectangle, Size};
 use iced_native::{image, svg};
 
 /// A raster or vector image.
@@ -11,6 +12,33 @@ pub enum Image {
 
         /// The bounds of the image.
         bounds: Rectangle,
+
+        /// The region of the image to draw, in source pixels.
+        ///
+        /// If `None`, the whole image is drawn.
+        source: Option<Rectangle>,
+
+        /// The tint color of the image, in __linear RGB__.
+        ///
+        /// It is multiplied with the sampled color of each texel.
+        tint: [f32; 4],
+
+        /// The opacity of the image, in `[0, 1]`.
+        opacity: f32,
+
+        /// The rotation of the image, in radians.
+        ///
+        /// The image is rotated around the center of its `bounds`, which are
+        /// not rotated.
+        rotation: f32,
+
+        /// The [`Filter`] used to sample the image when it is scaled.
+        filter: Filter,
+
+        /// How the image fills its `bounds`.
+        ///
+        /// It is applied by [`Image::slices`].
+        fit: ImageFit,
     },
     /// A vector image.
     Vector {
@@ -19,5 +47,379 @@ pub enum Image {
 
         /// The bounds of the image.
         bounds: Rectangle,
+
+        /// The opacity of the image, in `[0, 1]`.
+        opacity: f32,
+
+        /// The color that replaces every fill and stroke color of the image,
+        /// in __linear RGB__.
+        ///
+        /// If `None`, the image keeps its own colors.
+        color: Option<[f32; 4]>,
+
+        /// The rotation of the image, in radians.
+        ///
+        /// The image is rotated around the center of its `bounds`, which are
+        /// not rotated.
+        rotation: f32,
     },
+    /// A raster image split into nine regions, where only the middle row and
+    /// column are stretched.
+    ///
+    /// This keeps the corners of scalable frames crisp.
+    NinePatch {
+        /// The handle of a raster image.
+        handle: image::Handle,
+
+        /// The bounds of the image.
+        bounds: Rectangle,
+
+        /// The margins of the image that are not stretched, in source pixels,
+        /// as `[top, right, bottom, left]`.
+        insets: [f32; 4],
+    },
+}
+
+/// A region of an [`Image`] drawn into a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slice {
+    /// The region of the image to sample, in source pixels.
+    pub source: Rectangle,
+
+    /// The rectangle where the region is drawn.
+    pub bounds: Rectangle,
+}
+
+impl Image {
+    /// Returns the bounds of the [`Image`].
+    pub fn bounds(&self) -> Rectangle {
+        match self {
+            Image::Raster { bounds, .. }
+            | Image::Vector { bounds, .. }
+            | Image::NinePatch { bounds, .. } => *bounds,
+        }
+    }
+
+    /// Returns the slices a renderer draws for the [`Image`], given the size
+    /// of the image in source pixels.
+    ///
+    /// A raster image is drawn as a single slice with its source region,
+    /// which is clamped to the image. The slice is placed in the bounds
+    /// following its [`ImageFit`], and the parts of the image outside of
+    /// them are cropped.
+    ///
+    /// A nine-patch image is split by its insets into nine slices, in rows.
+    /// The corners keep their size in source pixels, the edges are stretched
+    /// along one axis, and the center is stretched along both. If the bounds
+    /// are too small to fit the corners, they are shrunk proportionally.
+    ///
+    /// Slices of zero size are skipped.
+    pub fn slices(&self, image_size: Size) -> impl Iterator<Item = Slice> {
+        let whole = Some(Rectangle::with_size(image_size))
+            .filter(|whole| whole.width > 0.0 && whole.height > 0.0);
+
+        let mut slices = [None; 9];
+
+        match self {
+            Image::Raster {
+                bounds,
+                source,
+                fit,
+                ..
+            } => {
+                let source = match source {
+                    Some(source) => {
+                        whole.and_then(|whole| source.intersection(&whole))
+                    }
+                    None => whole,
+                };
+
+                slices[0] = source.and_then(|source| {
+                    let fitted = fit.fit(source.size(), *bounds);
+                    let visible = fitted.intersection(bounds)?;
+
+                    Some(Slice {
+                        source: crop(source, fitted, visible),
+                        bounds: visible,
+                    })
+                });
+            }
+            Image::Vector { bounds, .. } => {
+                slices[0] = whole.map(|source| Slice {
+                    source,
+                    bounds: *bounds,
+                });
+            }
+            Image::NinePatch {
+                bounds,
+                insets: [top, right, bottom, left],
+                ..
+            } => {
+                let columns = split(
+                    image_size.width,
+                    (*left, *right),
+                    (bounds.x, bounds.width),
+                );
+
+                let rows = split(
+                    image_size.height,
+                    (*top, *bottom),
+                    (bounds.y, bounds.height),
+                );
+
+                for (i, slice) in slices.iter_mut().enumerate() {
+                    let ((source_y, source_height), (y, height)) = rows[i / 3];
+                    let ((source_x, source_width), (x, width)) = columns[i % 3];
+
+                    *slice = Some(Slice {
+                        source: Rectangle {
+                            x: source_x,
+                            y: source_y,
+                            width: source_width,
+                            height: source_height,
+                        },
+                        bounds: Rectangle {
+                            x,
+                            y,
+                            width,
+                            height,
+                        },
+                    })
+                    .filter(|slice| {
+                        is_visible(slice.source) && is_visible(slice.bounds)
+                    });
+                }
+            }
+        }
+
+        slices.into_iter().flatten()
+    }
+}
+
+/// Splits a side of a nine-patch image into its three spans, as pairs of the
+/// offset and the length of the span in the image and in the bounds.
+///
+/// The margins of the image keep their length, unless the bounds are too
+/// small to fit them.
+fn split(
+    image_length: f32,
+    (start, end): (f32, f32),
+    (offset, length): (f32, f32),
+) -> [((f32, f32), (f32, f32)); 3] {
+    let start = start.clamp(0.0, image_length);
+    let end = end.clamp(0.0, image_length - start);
+
+    let shrink = if start + end > length {
+        (length / (start + end)).max(0.0)
+    } else {
+        1.0
+    };
+
+    let (start_length, end_length) = (start * shrink, end * shrink);
+
+    [
+        ((0.0, start), (offset, start_length)),
+        (
+            (start, image_length - start - end),
+            (offset + start_length, length - start_length - end_length),
+        ),
+        (
+            (image_length - end, end),
+            (offset + length - end_length, end_length),
+        ),
+    ]
+}
+
+/// Returns the part of the source region of an image drawn in the given
+/// rectangle that ends up in its visible part.
+pub(super) fn crop(
+    source: Rectangle,
+    drawn: Rectangle,
+    visible: Rectangle,
+) -> Rectangle {
+    let scale_x = source.width / drawn.width;
+    let scale_y = source.height / drawn.height;
+
+    Rectangle {
+        x: source.x + (visible.x - drawn.x) * scale_x,
+        y: source.y + (visible.y - drawn.y) * scale_y,
+        width: visible.width * scale_x,
+        height: visible.height * scale_y,
+    }
+}
+
+fn is_visible(rectangle: Rectangle) -> bool {
+    rectangle.width > 0.0 && rectangle.height > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    fn raster(bounds: Rectangle, source: Option<Rectangle>) -> Image {
+        fitted(bounds, source, ImageFit::Fill)
+    }
+
+    fn fitted(
+        bounds: Rectangle,
+        source: Option<Rectangle>,
+        fit: ImageFit,
+    ) -> Image {
+        Image::Raster {
+            handle: image::Handle::from_pixels(1, 1, vec![0; 4]),
+            bounds,
+            source,
+            tint: [1.0; 4],
+            opacity: 1.0,
+            rotation: 0.0,
+            filter: Filter::Linear,
+            fit,
+        }
+    }
+
+    fn slice(source: [f32; 4], bounds: [f32; 4]) -> Slice {
+        let rectangle = |[x, y, width, height]: [f32; 4]| Rectangle {
+            x,
+            y,
+            width,
+            height,
+        };
+
+        Slice {
+            source: rectangle(source),
+            bounds: rectangle(bounds),
+        }
+    }
+
+    fn nine_patch(bounds: Rectangle) -> Image {
+        Image::NinePatch {
+            handle: image::Handle::from_pixels(1, 1, vec![0; 4]),
+            bounds,
+            insets: [4.0, 6.0, 8.0, 2.0],
+        }
+    }
+
+    #[test]
+    fn nine_patch_slices_stretch_only_the_middle() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 20.0), Size::new(100.0, 60.0));
+
+        let slices: Vec<_> =
+            nine_patch(bounds).slices(Size::new(30.0, 20.0)).collect();
+
+        assert_eq!(
+            slices,
+            [
+                slice([0.0, 0.0, 2.0, 4.0], [10.0, 20.0, 2.0, 4.0]),
+                slice([2.0, 0.0, 22.0, 4.0], [12.0, 20.0, 92.0, 4.0]),
+                slice([24.0, 0.0, 6.0, 4.0], [104.0, 20.0, 6.0, 4.0]),
+                slice([0.0, 4.0, 2.0, 8.0], [10.0, 24.0, 2.0, 48.0]),
+                slice([2.0, 4.0, 22.0, 8.0], [12.0, 24.0, 92.0, 48.0]),
+                slice([24.0, 4.0, 6.0, 8.0], [104.0, 24.0, 6.0, 48.0]),
+                slice([0.0, 12.0, 2.0, 8.0], [10.0, 72.0, 2.0, 8.0]),
+                slice([2.0, 12.0, 22.0, 8.0], [12.0, 72.0, 92.0, 8.0]),
+                slice([24.0, 12.0, 6.0, 8.0], [104.0, 72.0, 6.0, 8.0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn nine_patch_corners_shrink_to_fit_small_bounds() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 20.0), Size::new(4.0, 60.0));
+
+        let slices: Vec<_> =
+            nine_patch(bounds).slices(Size::new(30.0, 20.0)).collect();
+
+        // The middle column does not fit, so only the corners and the
+        // vertical edges are left
+        assert_eq!(slices.len(), 6);
+        assert_eq!(
+            slices[0],
+            slice([0.0, 0.0, 2.0, 4.0], [10.0, 20.0, 1.0, 4.0])
+        );
+        assert_eq!(
+            slices[1],
+            slice([24.0, 0.0, 6.0, 4.0], [11.0, 20.0, 3.0, 4.0])
+        );
+    }
+
+    #[test]
+    fn raster_slices_follow_their_fit() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 20.0), Size::new(50.0, 50.0));
+        let image_size = Size::new(100.0, 50.0);
+
+        let contain: Vec<_> = fitted(bounds, None, ImageFit::Contain)
+            .slices(image_size)
+            .collect();
+
+        assert_eq!(
+            contain,
+            [slice([0.0, 0.0, 100.0, 50.0], [10.0, 32.5, 50.0, 25.0])]
+        );
+
+        // The parts of the image outside of the bounds are cropped
+        let cover: Vec<_> = fitted(bounds, None, ImageFit::Cover)
+            .slices(image_size)
+            .collect();
+
+        assert_eq!(
+            cover,
+            [slice([25.0, 0.0, 50.0, 50.0], [10.0, 20.0, 50.0, 50.0])]
+        );
+
+        // The fit applies to the source region
+        let source =
+            Rectangle::new(Point::new(50.0, 0.0), Size::new(50.0, 50.0));
+        let region: Vec<_> = fitted(bounds, Some(source), ImageFit::Cover)
+            .slices(image_size)
+            .collect();
+
+        assert_eq!(
+            region,
+            [slice([50.0, 0.0, 50.0, 50.0], [10.0, 20.0, 50.0, 50.0])]
+        );
+    }
+
+    #[test]
+    fn raster_slices_sample_their_source_region() {
+        let bounds =
+            Rectangle::new(Point::new(10.0, 20.0), Size::new(30.0, 40.0));
+        let image_size = Size::new(100.0, 50.0);
+
+        let whole: Vec<_> = raster(bounds, None).slices(image_size).collect();
+
+        assert_eq!(
+            whole,
+            [Slice {
+                source: Rectangle::with_size(image_size),
+                bounds
+            }]
+        );
+
+        // The source region is clamped to the image
+        let source =
+            Rectangle::new(Point::new(80.0, 10.0), Size::new(40.0, 20.0));
+        let region: Vec<_> =
+            raster(bounds, Some(source)).slices(image_size).collect();
+
+        assert_eq!(
+            region,
+            [Slice {
+                source: Rectangle::new(
+                    Point::new(80.0, 10.0),
+                    Size::new(20.0, 20.0)
+                ),
+                bounds
+            }]
+        );
+
+        // A source region outside of the image draws nothing
+        let outside =
+            Rectangle::new(Point::new(200.0, 0.0), Size::new(10.0, 10.0));
+
+        assert_eq!(raster(bounds, Some(outside)).slices(image_size).count(), 0);
+    }
 }
@@ -1,9 +1,13 @@
 //! A collection of triangle primitives.
 use crate::triangle;
-use crate::{Gradient, Point, Rectangle};
+use crate::{Gradient, Point, Rectangle, Transformation};
+
+use glam::{Mat4, Vec3};
+
+use std::borrow::Cow;
 
 /// A mesh of triangles.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Mesh<'a> {
     /// A mesh of triangles with a solid color.
     Solid {
@@ -11,7 +15,11 @@ pub enum Mesh<'a> {
         origin: Point,
 
         /// The vertex and index buffers of the [`Mesh`].
-        buffers: &'a triangle::Mesh2D<triangle::ColoredVertex2D>,
+        ///
+        /// Its vertex colors are encoded in the [`AlphaMode`] of the frame.
+        ///
+        /// [`AlphaMode`]: triangle::AlphaMode
+        buffers: Cow<'a, triangle::Mesh2D<triangle::ColoredVertex2D>>,
 
         /// The clipping bounds of the [`Mesh`].
         clip_bounds: Rectangle<f32>,
@@ -22,13 +30,13 @@ pub enum Mesh<'a> {
         origin: Point,
 
         /// The vertex and index buffers of the [`Mesh`].
-        buffers: &'a triangle::Mesh2D<triangle::Vertex2D>,
+        buffers: Cow<'a, triangle::Mesh2D<triangle::Vertex2D>>,
 
         /// The clipping bounds of the [`Mesh`].
         clip_bounds: Rectangle<f32>,
 
         /// The gradient to apply to the [`Mesh`].
-        gradient: &'a Gradient,
+        gradient: Cow<'a, Gradient>,
     },
 }
 
@@ -57,6 +65,155 @@ impl Mesh<'_> {
             | Self::Gradient { clip_bounds, .. } => *clip_bounds,
         }
     }
+
+    /// Applies the given [`Transformation`] to the vertex positions of the
+    /// [`Mesh`], relative to its origin, producing owned buffers.
+    ///
+    /// The gradient of a [`Mesh::Gradient`] is transformed as well. The
+    /// origin and the clip bounds are left untouched.
+    pub fn transformed(self, transformation: Transformation) -> Self {
+        fn transform<'a, T: Clone>(
+            buffers: Cow<'a, triangle::Mesh2D<T>>,
+            transformation: Transformation,
+            position: impl Fn(&mut T) -> &mut [f32; 2],
+        ) -> Cow<'a, triangle::Mesh2D<T>> {
+            let mut buffers = buffers.into_owned();
+
+            let points: Vec<_> = buffers
+                .vertices
+                .iter_mut()
+                .map(|vertex| {
+                    let [x, y] = *position(vertex);
+
+                    Point::new(x, y)
+                })
+                .collect();
+
+            let mut transformed = vec![Point::ORIGIN; points.len()];
+            transformation.transform_points(&points, &mut transformed);
+
+            for (vertex, point) in buffers.vertices.iter_mut().zip(transformed)
+            {
+                *position(vertex) = [point.x, point.y];
+            }
+
+            Cow::Owned(buffers)
+        }
+
+        match self {
+            Self::Solid {
+                origin,
+                buffers,
+                clip_bounds,
+            } => Self::Solid {
+                origin,
+                buffers: transform(buffers, transformation, |vertex| {
+                    &mut vertex.position
+                }),
+                clip_bounds,
+            },
+            Self::Gradient {
+                origin,
+                buffers,
+                clip_bounds,
+                gradient,
+            } => {
+                let mut gradient = gradient.into_owned();
+
+                match &mut gradient {
+                    Gradient::Linear(linear) => {
+                        linear.start =
+                            transformation.transform_point(linear.start);
+                        linear.end = transformation.transform_point(linear.end);
+                    }
+                }
+
+                Self::Gradient {
+                    origin,
+                    buffers: transform(buffers, transformation, |vertex| {
+                        &mut vertex.position
+                    }),
+                    clip_bounds,
+                    gradient: Cow::Owned(gradient),
+                }
+            }
+        }
+    }
+}
+
+/// A mesh of triangles in 3D space.
+///
+/// Renderers draw it as a [`Mesh`], projected with [`Mesh3D::flattened`].
+#[derive(Debug, Clone)]
+pub struct Mesh3D<'a> {
+    /// The vertex and index buffers of the [`Mesh3D`].
+    ///
+    /// Its vertex colors are encoded in the [`AlphaMode`] of the frame.
+    ///
+    /// [`AlphaMode`]: triangle::AlphaMode
+    pub buffers: Cow<'a, triangle::Mesh3D<triangle::ColoredVertex3D>>,
+
+    /// The [`Transformation`] projecting the vertices of the [`Mesh3D`] into
+    /// the [`Layer`], with the Z axis used as depth.
+    ///
+    /// [`Layer`]: crate::layer::Layer
+    pub transformation: Transformation,
+
+    /// The clipping bounds of the [`Mesh3D`].
+    pub clip_bounds: Rectangle<f32>,
+}
+
+impl<'a> Mesh3D<'a> {
+    /// Projects the [`Mesh3D`] into a solid [`Mesh`] that can be drawn
+    /// without a depth buffer.
+    ///
+    /// Its triangles are sorted by the average depth of their projected
+    /// vertices, so the ones with a greater depth are drawn first and nearer
+    /// ones cover them. Intersecting triangles are not split, so they may
+    /// still cover each other incorrectly.
+    pub fn flattened(&self) -> Mesh<'static> {
+        let projection = Mat4::from(self.transformation);
+
+        let positions: Vec<Vec3> = self
+            .buffers
+            .vertices
+            .iter()
+            .map(|vertex| {
+                projection.project_point3(Vec3::from(vertex.position))
+            })
+            .collect();
+
+        let depth = |triangle: &[u32]| -> f32 {
+            triangle
+                .iter()
+                .map(|index| positions[*index as usize].z)
+                .sum()
+        };
+
+        let mut triangles: Vec<&[u32]> =
+            self.buffers.indices.chunks_exact(3).collect();
+
+        triangles.sort_by(|a, b| depth(b).total_cmp(&depth(a)));
+
+        Mesh::Solid {
+            origin: Point::ORIGIN,
+            buffers: Cow::Owned(triangle::Mesh2D {
+                vertices: self
+                    .buffers
+                    .vertices
+                    .iter()
+                    .zip(&positions)
+                    .map(|(vertex, position)| triangle::ColoredVertex2D {
+                        position: [position.x, position.y],
+                        color: vertex.color,
+                    })
+                    .collect(),
+                indices: triangles.concat(),
+                alpha_mode: self.buffers.alpha_mode,
+            }),
+            clip_bounds: self.clip_bounds,
+        }
+    }
 }
 
 /// The result of counting the attributes of a set of meshes.
@@ -91,3 +248,102 @@ pub fn attribute_count_of<'a>(meshes: &'a [Mesh<'a>]) -> AttributeCount {
             count
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gradient::{ColorStop, Linear};
+    use crate::{Color, Size};
+
+    #[test]
+    fn transformed_meshes_are_rotated_and_scaled() {
+        let transformation = Transformation::from_srt(
+            crate::Vector::new(2.0, 3.0),
+            std::f32::consts::FRAC_PI_2,
+            crate::Vector::new(0.0, 0.0),
+        );
+
+        let clip_bounds = Rectangle::new(Point::ORIGIN, Size::new(50.0, 50.0));
+
+        let solid = Mesh::Solid {
+            origin: Point::new(10.0, 10.0),
+            buffers: Cow::Owned(triangle::Mesh2D {
+                vertices: [[1.0, 0.0], [0.0, 1.0], [4.0, 5.0]]
+                    .map(|position| triangle::ColoredVertex2D {
+                        position,
+                        color: [0.0, 0.0, 0.0, 1.0],
+                    })
+                    .to_vec(),
+                indices: vec![0, 1, 2],
+                alpha_mode: triangle::AlphaMode::Straight,
+            }),
+            clip_bounds,
+        }
+        .transformed(transformation);
+
+        // Scaling by (2, 3) and rotating a quarter turn maps (x, y) to
+        // (-3y, 2x)
+        let expected = [[0.0, 2.0], [-3.0, 0.0], [-15.0, 8.0]];
+
+        match solid {
+            Mesh::Solid {
+                origin,
+                buffers,
+                clip_bounds: transformed_clip_bounds,
+            } => {
+                assert_eq!(origin, Point::new(10.0, 10.0));
+                assert_eq!(transformed_clip_bounds, clip_bounds);
+
+                for (vertex, expected) in buffers.vertices.iter().zip(expected)
+                {
+                    assert!((vertex.position[0] - expected[0]).abs() < 1e-5);
+                    assert!((vertex.position[1] - expected[1]).abs() < 1e-5);
+                }
+            }
+            Mesh::Gradient { .. } => unreachable!(),
+        }
+
+        let buffers = triangle::Mesh2D {
+            vertices: vec![triangle::Vertex2D {
+                position: [4.0, 5.0],
+            }],
+            indices: vec![0, 0, 0],
+            alpha_mode: triangle::AlphaMode::Straight,
+        };
+
+        let gradient = Gradient::Linear(Linear {
+            start: Point::new(1.0, 0.0),
+            end: Point::new(4.0, 5.0),
+            color_stops: vec![ColorStop {
+                offset: 0.0,
+                color: Color::BLACK,
+            }],
+        });
+
+        let gradient_mesh = Mesh::Gradient {
+            origin: Point::ORIGIN,
+            buffers: Cow::Borrowed(&buffers),
+            clip_bounds,
+            gradient: Cow::Borrowed(&gradient),
+        }
+        .transformed(transformation);
+
+        match gradient_mesh {
+            Mesh::Gradient {
+                buffers, gradient, ..
+            } => {
+                let [x, y] = buffers.vertices[0].position;
+
+                assert!((x + 15.0).abs() < 1e-5 && (y - 8.0).abs() < 1e-5);
+
+                let Gradient::Linear(linear) = gradient.as_ref();
+
+                assert!((linear.start.x - 0.0).abs() < 1e-5);
+                assert!((linear.start.y - 2.0).abs() < 1e-5);
+                assert!((linear.end.x + 15.0).abs() < 1e-5);
+                assert!((linear.end.y - 8.0).abs() < 1e-5);
+            }
+            Mesh::Solid { .. } => unreachable!(),
+        }
+    }
+}
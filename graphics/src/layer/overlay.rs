@@ -0,0 +1,107 @@
+use crate::{Font, Point};
+
+/// The style of the debug overlay of [`Layer::overlay_with`].
+///
+/// [`Layer::overlay_with`]: crate::layer::Layer::overlay_with
+#[derive(Debug, Clone, Copy)]
+pub struct OverlayStyle {
+    /// The color of the text, in __linear RGB__.
+    pub color: [f32; 4],
+
+    /// The color of the drop shadow of the text, in __linear RGB__.
+    ///
+    /// The shadow is offset proportionally to the `size` of the text.
+    pub shadow_color: [f32; 4],
+
+    /// The size of the text.
+    pub size: f32,
+
+    /// The position of the first line of text.
+    pub origin: Point,
+
+    /// The distance between the top of consecutive lines of text.
+    pub line_spacing: f32,
+
+    /// The font of the text.
+    pub font: Font,
+
+    /// The color of the rounded panel drawn behind the text, in
+    /// __linear RGB__, if any.
+    ///
+    /// The width of the panel is estimated from the length of the longest
+    /// line, since the text is not measured.
+    pub panel: Option<[f32; 4]>,
+}
+
+impl Default for OverlayStyle {
+    fn default() -> Self {
+        Self {
+            color: [0.9, 0.9, 0.9, 1.0],
+            shadow_color: [0.0, 0.0, 0.0, 1.0],
+            size: 20.0,
+            origin: Point::new(11.0, 11.0),
+            line_spacing: 25.0,
+            font: Font::Default,
+            panel: None,
+        }
+    }
+}
+
+impl OverlayStyle {
+    /// Scales the size, origin, and line spacing of the [`OverlayStyle`] by
+    /// the given factor.
+    pub fn scaled(self, factor: f32) -> Self {
+        Self {
+            size: self.size * factor,
+            origin: Point::new(self.origin.x * factor, self.origin.y * factor),
+            line_spacing: self.line_spacing * factor,
+            ..self
+        }
+    }
+}
+
+/// The style of the frame time graph of [`Layer::performance_graph`].
+///
+/// [`Layer::performance_graph`]: crate::layer::Layer::performance_graph
+#[derive(Debug, Clone, Copy)]
+pub struct PerformanceGraphStyle {
+    /// The color of the bars within the target frame time, in
+    /// __linear RGB__.
+    pub color: [f32; 4],
+
+    /// The color of the bars over the target frame time, in __linear RGB__.
+    pub over_budget_color: [f32; 4],
+
+    /// The color of the baseline drawn at the target frame time, in
+    /// __linear RGB__.
+    pub baseline_color: [f32; 4],
+
+    /// The color of the quad drawn behind the graph, in __linear RGB__, if
+    /// any.
+    pub background: Option<[f32; 4]>,
+
+    /// The target frame time, in milliseconds.
+    pub target_frame_time: f32,
+
+    /// The frame time at the top of the graph, in milliseconds.
+    ///
+    /// Longer frames are clamped to the top.
+    pub max_frame_time: f32,
+
+    /// The horizontal space between consecutive bars.
+    pub spacing: f32,
+}
+
+impl Default for PerformanceGraphStyle {
+    fn default() -> Self {
+        Self {
+            color: [0.2, 0.8, 0.2, 1.0],
+            over_budget_color: [0.9, 0.2, 0.1, 1.0],
+            baseline_color: [0.9, 0.9, 0.9, 1.0],
+            background: Some([0.0, 0.0, 0.0, 0.5]),
+            target_frame_time: 1000.0 / 60.0,
+            max_frame_time: 2000.0 / 60.0,
+            spacing: 1.0,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::layer::{Layer, Node};
+use crate::Rectangle;
+
+/// A pool of [`Layer`] allocations that can be reused across frames.
+///
+/// Recycling the layers of a frame keeps the capacity of all of their
+/// primitive lists, which avoids most reallocations when generating the
+/// layers of the next one. The stack used to traverse the primitives is kept
+/// as well.
+///
+/// The `layers` benchmark compares the allocations of fresh and pooled
+/// layers.
+#[derive(Debug, Default)]
+pub struct Pool {
+    layers: Vec<Layer<'static>>,
+    stack: Vec<Node<'static>>,
+}
+
+impl Pool {
+    /// Creates a new empty [`Pool`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the given layers to the [`Pool`], keeping their allocations.
+    pub fn recycle(&mut self, layers: Vec<Layer<'_>>) {
+        self.layers.extend(layers.into_iter().map(|layer| Layer {
+            bounds: Rectangle::default(),
+            quads: clear(layer.quads),
+            quad_gradients: clear(layer.quad_gradients),
+            quad_images: clear(layer.quad_images),
+            quad_batches: clear(layer.quad_batches),
+            shadows: clear(layer.shadows),
+            arcs: clear(layer.arcs),
+            meshes: retype(clear(layer.meshes)),
+            meshes_3d: retype(clear(layer.meshes_3d)),
+            text: retype(clear(layer.text)),
+            images: clear(layer.images),
+            rounded_clip: None,
+            antialias_clip: false,
+            tint: None,
+            cache_keys: clear(layer.cache_keys),
+        }));
+    }
+
+    /// Takes an empty [`Layer`] with the given clipping bounds out of the
+    /// [`Pool`], allocating a new one if needed.
+    pub(crate) fn take<'a>(&mut self, bounds: Rectangle) -> Layer<'a> {
+        match self.layers.pop() {
+            Some(layer) => Layer { bounds, ..layer },
+            None => Layer::new(bounds),
+        }
+    }
+
+    /// Takes the empty stack used to traverse primitives out of the [`Pool`].
+    pub(crate) fn take_stack<'a>(&mut self) -> Vec<Node<'a>> {
+        retype(std::mem::take(&mut self.stack))
+    }
+
+    /// Returns the given empty stack to the [`Pool`], keeping its allocation.
+    pub(crate) fn recycle_stack(&mut self, stack: Vec<Node<'_>>) {
+        self.stack = retype(stack);
+    }
+}
+
+/// Clears the given vector, shrinking it only when grossly over-allocated.
+fn clear<T>(mut vector: Vec<T>) -> Vec<T> {
+    const MIN_CAPACITY: usize = 64;
+
+    let used = vector.len();
+    vector.clear();
+
+    if vector.capacity() > (used * 4).max(MIN_CAPACITY) {
+        vector.shrink_to(used * 2);
+    }
+
+    vector
+}
+
+/// Changes the element type of an empty vector.
+///
+/// The standard library currently collects in place when both types have the
+/// same size and alignment, which is always the case when only their
+/// lifetimes differ, so the allocation is kept. This is not guaranteed,
+/// though; otherwise, the old allocation is freed and the returned vector
+/// starts empty, which is still correct.
+fn retype<T, U>(vector: Vec<T>) -> Vec<U> {
+    debug_assert!(vector.is_empty());
+
+    vector.into_iter().map(|_| unreachable!()).collect()
+}
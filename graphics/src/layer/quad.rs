@@ -1,3 +1,10 @@
+//! Draw colored rectangles with a border.
+use crate::layer::image::crop;
+use crate::layer::{RoundedClip, Slice};
+use crate::{ImageFit, ImageRepeat, Rectangle, Size};
+
+use iced_native::image;
+
 /// A colored rectangle with a border.
 ///
 /// This type can be directly uploaded to GPU memory.
@@ -19,8 +26,324 @@ pub struct Quad {
     /// The border radius of the [`Quad`].
     pub border_radius: [f32; 4],
 
-    /// The border width of the [`Quad`].
-    pub border_width: f32,
+    /// The border width of each side of the [`Quad`], in the order of the
+    /// CSS `border-width` attribute: top, right, bottom, and left.
+    pub border_width: [f32; 4],
+
+    /// Whether the [`Quad`] has a transparent fill and a visible border, in
+    /// which case a renderer can skip filling it.
+    ///
+    /// It is a `u32` instead of a `bool` to keep the [`Quad`] uploadable to
+    /// GPU memory; any value other than `0` means `true`.
+    pub border_only: u32,
+
+    /// The smoothing of the corners of the [`Quad`], from `0.0` for circular
+    /// corners to `1.0` for a full superellipse.
+    ///
+    /// Renderers that cannot draw superellipses keep the corners circular.
+    pub corner_smoothing: f32,
+}
+
+impl Quad {
+    /// Returns whether the [`Quad`] fully covers its bounds with opaque
+    /// colors.
+    ///
+    /// Rounded quads never are, since their corners are left uncovered.
+    pub fn is_opaque(&self) -> bool {
+        self.color[3] >= 1.0
+            && (self.border_width.iter().all(|width| *width == 0.0)
+                || self.border_color[3] >= 1.0)
+            && self.border_radius.iter().all(|radius| *radius == 0.0)
+    }
+
+    /// Sets the same border width on every side of the [`Quad`].
+    pub fn with_uniform_border(self, width: f32) -> Self {
+        Self {
+            border_width: [width; 4],
+            ..self
+        }
+    }
+
+    /// Returns whether only the border of the [`Quad`] needs to be drawn.
+    pub fn is_border_only(&self) -> bool {
+        self.border_only != 0
+    }
+
+    /// Merges the [`Quad`] with the given one, if both share the same color
+    /// and are adjacent along a whole edge.
+    ///
+    /// Only quads without borders and with square corners are merged, since
+    /// their union would lose the inner borders and corners.
+    pub(crate) fn merge(&self, other: &Quad) -> Option<Quad> {
+        let is_plain = |quad: &Quad| {
+            quad.border_width == [0.0; 4]
+                && quad.border_radius == [0.0; 4]
+                && !quad.is_border_only()
+        };
+
+        if !is_plain(self) || !is_plain(other) || self.color != other.color {
+            return None;
+        }
+
+        let [x, y] = self.position;
+        let [width, height] = self.size;
+        let [other_x, other_y] = other.position;
+        let [other_width, other_height] = other.size;
+
+        let is_aligned = y == other_y
+            && height == other_height
+            && (x + width == other_x || other_x + other_width == x);
+
+        let is_stacked = x == other_x
+            && width == other_width
+            && (y + height == other_y || other_y + other_height == y);
+
+        if !is_aligned && !is_stacked {
+            return None;
+        }
+
+        let position = [x.min(other_x), y.min(other_y)];
+
+        Some(Quad {
+            position,
+            size: [
+                (x + width).max(other_x + other_width) - position[0],
+                (y + height).max(other_y + other_height) - position[1],
+            ],
+            ..*self
+        })
+    }
+}
+
+/// A gradient painted over a part of a [`Quad`] of the same layer.
+///
+/// The [`Quad`] keeps the color of the first stop of the gradient, which
+/// renderers lacking support for its [`Shape`] can draw as a fallback.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    /// The index of the painted [`Quad`] in its layer.
+    pub quad: usize,
+
+    /// The part of the [`Quad`] painted by the [`Gradient`].
+    pub target: Target,
+
+    /// The [`Shape`] of the [`Gradient`].
+    pub shape: Shape,
+
+    /// The color stops of the [`Gradient`], as pairs of an offset in
+    /// `[0, 1]` and a color in __linear RGB__.
+    ///
+    /// They are sorted by offset and never empty.
+    pub stops: Vec<(f32, [f32; 4])>,
+}
+
+/// The part of a [`Quad`] painted by a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The background of the [`Quad`].
+    Background,
+
+    /// The border of the [`Quad`].
+    Border,
+}
+
+/// The shape of a [`Gradient`], in layer coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// A linear gradient between two points.
+    Linear {
+        /// The point where the [`Gradient`] starts.
+        start: [f32; 2],
+
+        /// The point where the [`Gradient`] ends.
+        end: [f32; 2],
+    },
+
+    /// A radial gradient around a center point.
+    Radial {
+        /// The center of the [`Gradient`].
+        center: [f32; 2],
+
+        /// The radius of the [`Gradient`].
+        radius: f32,
+    },
+
+    /// A conic gradient sweeping around a center point.
+    Conic {
+        /// The center of the [`Gradient`].
+        center: [f32; 2],
+
+        /// The angle where the [`Gradient`] starts, in radians, clockwise
+        /// from the positive X axis.
+        start_angle: f32,
+    },
+}
+
+/// A batch of quads sharing the same style, which a renderer can draw with a
+/// single instanced draw call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Batch {
+    /// The instances of the [`Batch`].
+    pub instances: Vec<Instance>,
+}
+
+impl Batch {
+    /// Returns the [`Quad`] of every instance of the [`Batch`], without any
+    /// border.
+    pub fn quads(&self) -> Vec<Quad> {
+        self.instances
+            .iter()
+            .map(|instance| Quad {
+                position: instance.position,
+                size: instance.size,
+                color: instance.color,
+                border_color: [0.0; 4],
+                border_radius: [0.0; 4],
+                border_width: [0.0; 4],
+                border_only: 0,
+                corner_smoothing: 0.0,
+            })
+            .collect()
+    }
+}
+
+/// A single quad of a [`Batch`].
+///
+/// This type can be directly uploaded to GPU memory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Instance {
+    /// The position of the [`Instance`].
+    pub position: [f32; 2],
+
+    /// The size of the [`Instance`].
+    pub size: [f32; 2],
+
+    /// The color of the [`Instance`], in __linear RGB__.
+    pub color: [f32; 4],
+}
+
+#[allow(unsafe_code)]
+unsafe impl bytemuck::Zeroable for Instance {}
+
+#[allow(unsafe_code)]
+unsafe impl bytemuck::Pod for Instance {}
+
+/// An image filling the background of a [`Quad`] of the same layer.
+///
+/// The [`Quad`] is drawn with a transparent background, and the image is
+/// drawn over it, clipped by its [`Image::clip`].
+#[derive(Debug, Clone)]
+pub struct Image {
+    /// The index of the filled [`Quad`] in its layer.
+    pub quad: usize,
+
+    /// The handle of the image.
+    pub handle: image::Handle,
+
+    /// How the image fills the [`Quad`], unless it is repeated.
+    pub fit: ImageFit,
+
+    /// The axes along which the image is repeated across the [`Quad`].
+    pub repeat: ImageRepeat,
+
+    /// The size of each repetition of the image, if any.
+    pub tile_size: Option<Size>,
+}
+
+impl Image {
+    /// Returns the region inside the border of the given [`Quad`], which
+    /// clips the [`Image`].
+    ///
+    /// Its corners follow the inner edge of the border, like renderers draw
+    /// it.
+    pub fn clip(&self, quad: &Quad) -> RoundedClip {
+        let [top, right, bottom, left] = quad.border_width;
+        let max_border_width = top.max(right).max(bottom).max(left);
+
+        RoundedClip {
+            bounds: Rectangle {
+                x: quad.position[0] + left,
+                y: quad.position[1] + top,
+                width: (quad.size[0] - left - right).max(0.0),
+                height: (quad.size[1] - top - bottom).max(0.0),
+            },
+            border_radius: quad
+                .border_radius
+                .map(|radius| (radius - max_border_width).max(0.0)),
+        }
+    }
+
+    /// Returns the slices a renderer draws for the [`Image`] filling the
+    /// given [`Quad`], given the size of the image in source pixels.
+    ///
+    /// The image is placed in the bounds of its [`Image::clip`] following
+    /// its [`ImageFit`], and the parts of the image outside of them are
+    /// cropped.
+    ///
+    /// A repeated image is instead drawn as a slice per tile, starting at the
+    /// top left corner of the bounds and repeated along its
+    /// [`ImageRepeat`] axes. Tiles are at least one pixel long on each
+    /// side.
+    pub fn slices(
+        &self,
+        quad: &Quad,
+        image_size: Size,
+    ) -> impl Iterator<Item = Slice> {
+        let bounds = self.clip(quad).bounds;
+
+        let whole = Some(Rectangle::with_size(image_size))
+            .filter(|whole| whole.width > 0.0 && whole.height > 0.0);
+
+        let (repeat_x, repeat_y) = match self.repeat {
+            ImageRepeat::NoRepeat => (false, false),
+            ImageRepeat::RepeatX => (true, false),
+            ImageRepeat::RepeatY => (false, true),
+            ImageRepeat::Repeat => (true, true),
+        };
+
+        let (first, columns, rows) = if repeat_x || repeat_y {
+            let tile = self.tile_size.unwrap_or(image_size);
+            let tile = Size::new(tile.width.max(1.0), tile.height.max(1.0));
+
+            let count = |is_repeated: bool, length: f32, tile_length: f32| {
+                if is_repeated {
+                    (length / tile_length).ceil().max(1.0) as usize
+                } else {
+                    1
+                }
+            };
+
+            (
+                Rectangle::new(bounds.position(), tile),
+                count(repeat_x, bounds.width, tile.width),
+                count(repeat_y, bounds.height, tile.height),
+            )
+        } else {
+            (self.fit.fit(image_size, bounds), 1, 1)
+        };
+
+        whole.into_iter().flat_map(move |whole| {
+            (0..rows)
+                .flat_map(move |row| {
+                    (0..columns).map(move |column| (row, column))
+                })
+                .filter_map(move |(row, column)| {
+                    let drawn = Rectangle {
+                        x: first.x + column as f32 * first.width,
+                        y: first.y + row as f32 * first.height,
+                        ..first
+                    };
+
+                    let visible = drawn.intersection(&bounds)?;
+
+                    Some(Slice {
+                        source: crop(whole, drawn, visible),
+                        bounds: visible,
+                    })
+                })
+        })
+    }
 }
 
 #[allow(unsafe_code)]
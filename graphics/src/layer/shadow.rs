@@ -0,0 +1,121 @@
+use crate::{Rectangle, Vector};
+
+/// A blurred shadow of a rounded rectangle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// The bounds of the element casting the [`Shadow`].
+    pub bounds: Rectangle,
+
+    /// The color of the [`Shadow`], in __linear RGB__.
+    pub color: [f32; 4],
+
+    /// The border radius of the element casting the [`Shadow`].
+    pub border_radius: [f32; 4],
+
+    /// The offset of the [`Shadow`] from its `bounds`.
+    pub offset: Vector,
+
+    /// The blur radius of the [`Shadow`].
+    ///
+    /// It controls how far the edges of the [`Shadow`] fade out, without
+    /// changing the size of the shape being blurred.
+    pub blur_radius: f32,
+
+    /// The spread radius of the [`Shadow`].
+    ///
+    /// It expands the offset `bounds` of the [`Shadow`] in every direction
+    /// before blurring them. Negative values shrink them instead.
+    pub spread_radius: f32,
+
+    /// Whether the [`Shadow`] is cast inside of its `bounds` instead of
+    /// outside.
+    pub inset: bool,
+
+    /// Whether the [`Shadow`] is a glow.
+    ///
+    /// Only the halo of a glow is drawn, leaving its `bounds` unfilled.
+    pub glow: bool,
+}
+
+impl Shadow {
+    /// Returns the offset `bounds` of the [`Shadow`] expanded by its spread
+    /// radius; i.e. the shape that is blurred.
+    pub fn spread_bounds(&self) -> Rectangle {
+        expand(self.bounds + self.offset, self.spread_radius)
+    }
+
+    /// Returns the rounded rectangle that is blurred to draw the [`Shadow`],
+    /// along with its border radius.
+    ///
+    /// Inset shadows are cast by the edges of a hole, so their offset
+    /// `bounds` are shrunk by the spread radius instead.
+    pub fn shape(&self) -> (Rectangle, [f32; 4]) {
+        let spread = if self.inset {
+            -self.spread_radius
+        } else {
+            self.spread_radius
+        };
+
+        (
+            expand(self.bounds + self.offset, spread),
+            self.border_radius.map(|radius| (radius + spread).max(0.0)),
+        )
+    }
+
+    /// Returns the region that the [`Shadow`] may cover.
+    ///
+    /// Drop shadows extend beyond their offset `bounds` by both their spread
+    /// and blur radii, while inset shadows are always clipped to their
+    /// `bounds`.
+    pub fn visible_bounds(&self) -> Rectangle {
+        if self.inset {
+            self.bounds
+        } else {
+            expand(self.spread_bounds(), self.blur_radius)
+        }
+    }
+
+    /// Merges the [`Shadow`] with the given one, if both share the same
+    /// parameters and the bounds of one contain the bounds of the other.
+    ///
+    /// The merged [`Shadow`] is the containing one.
+    pub(crate) fn merge(&self, other: &Shadow) -> Option<Shadow> {
+        if self.color != other.color
+            || self.border_radius != other.border_radius
+            || self.offset != other.offset
+            || self.blur_radius != other.blur_radius
+            || self.spread_radius != other.spread_radius
+            || self.inset != other.inset
+            || self.glow != other.glow
+        {
+            return None;
+        }
+
+        let contains = |outer: Rectangle, inner: Rectangle| {
+            outer.x <= inner.x
+                && outer.y <= inner.y
+                && inner.x + inner.width <= outer.x + outer.width
+                && inner.y + inner.height <= outer.y + outer.height
+        };
+
+        if contains(self.bounds, other.bounds) {
+            Some(*self)
+        } else if contains(other.bounds, self.bounds) {
+            Some(*other)
+        } else {
+            None
+        }
+    }
+}
+
+fn expand(bounds: Rectangle, amount: f32) -> Rectangle {
+    let width = (bounds.width + amount * 2.0).max(0.0);
+    let height = (bounds.height + amount * 2.0).max(0.0);
+
+    Rectangle {
+        x: bounds.center_x() - width / 2.0,
+        y: bounds.center_y() - height / 2.0,
+        width,
+        height,
+    }
+}
@@ -0,0 +1,204 @@
+use crate::color;
+use crate::layer::{Image, Layer, Mesh};
+use crate::{Point, Rectangle};
+
+use std::fmt::Write;
+
+impl Layer<'_> {
+    /// Dumps the given layers into a deterministic, human-readable string.
+    ///
+    /// Every layer is listed with its bounds, followed by its quads, text,
+    /// images, and meshes. Coordinates are rounded to two decimals and colors
+    /// are converted back to sRGB and printed as `#rrggbbaa`, so the output
+    /// is stable across platforms and suitable for golden tests.
+    pub fn to_snapshot_string(layers: &[Layer<'_>]) -> String {
+        let mut output = String::new();
+
+        for (i, layer) in layers.iter().enumerate() {
+            let _ = writeln!(output, "Layer {} {}", i, bounds(&layer.bounds));
+
+            for quad in &layer.quads {
+                let [x, y] = quad.position;
+                let [width, height] = quad.size;
+
+                let _ = writeln!(
+                    output,
+                    "  Quad {} {}",
+                    bounds(&Rectangle {
+                        x,
+                        y,
+                        width,
+                        height
+                    }),
+                    hex(quad.color)
+                );
+            }
+
+            for text in &layer.text {
+                let _ = writeln!(
+                    output,
+                    "  Text {:?} {} size {} {}",
+                    text.content,
+                    bounds(&text.bounds),
+                    number(text.size),
+                    hex(text.color)
+                );
+            }
+
+            for image in &layer.images {
+                let kind = match image {
+                    Image::Raster { .. } => "Raster",
+                    Image::Vector { .. } => "Vector",
+                    Image::NinePatch { .. } => "NinePatch",
+                };
+
+                let _ = writeln!(
+                    output,
+                    "  Image {} {}",
+                    kind,
+                    bounds(&image.bounds())
+                );
+            }
+
+            for mesh in &layer.meshes {
+                let kind = match mesh {
+                    Mesh::Solid { .. } => "Solid",
+                    Mesh::Gradient { .. } => "Gradient",
+                };
+
+                let _ = writeln!(
+                    output,
+                    "  Mesh {} at {} ({} indices) clip {}",
+                    kind,
+                    point(mesh.origin()),
+                    mesh.indices().len(),
+                    bounds(&mesh.clip_bounds())
+                );
+            }
+
+            for mesh in &layer.meshes_3d {
+                let _ = writeln!(
+                    output,
+                    "  Mesh3D ({} indices) clip {}",
+                    mesh.buffers.indices.len(),
+                    bounds(&mesh.clip_bounds)
+                );
+            }
+        }
+
+        output
+    }
+}
+
+/// Formats the given number with at most two decimals, without trailing
+/// zeros.
+fn number(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+
+    // Avoid printing negative zeros
+    if rounded == 0.0 {
+        return String::from("0");
+    }
+
+    let formatted = format!("{:.2}", rounded);
+
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_owned()
+    } else {
+        formatted
+    }
+}
+
+fn point(point: Point) -> String {
+    format!("({}, {})", number(point.x), number(point.y))
+}
+
+fn bounds(bounds: &Rectangle) -> String {
+    format!(
+        "[{}, {}, {}x{}]",
+        number(bounds.x),
+        number(bounds.y),
+        number(bounds.width),
+        number(bounds.height)
+    )
+}
+
+fn hex(linear: [f32; 4]) -> String {
+    let color = color::from_linear(linear);
+
+    let byte = |component: f32| (component.clamp(0.0, 1.0) * 255.0).round();
+
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        byte(color.r) as u8,
+        byte(color.g) as u8,
+        byte(color.b) as u8,
+        byte(color.a) as u8
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alignment;
+    use crate::layer::Layer;
+    use crate::Viewport;
+    use crate::{Background, BorderAlignment, ClipMode, Color, Font};
+    use crate::{LineHeight, Point, Primitive, Rectangle, Size, Truncate};
+
+    #[test]
+    fn snapshots_are_readable() {
+        let bounds =
+            Rectangle::new(Point::new(10.5, 20.0), Size::new(30.0, 40.0));
+
+        let primitives = [
+            Primitive::Quad {
+                bounds,
+                background: Background::Color(Color::from_rgb(1.0, 0.0, 0.0))
+                    .into(),
+                border_radius: [0.0; 4],
+                border_width: 0.0.into(),
+                border_color: Color::TRANSPARENT.into(),
+                border_alignment: BorderAlignment::Inside,
+                corner_smoothing: 0.0,
+            },
+            Primitive::Clip {
+                bounds,
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(Primitive::Text {
+                    content: String::from("Hello"),
+                    bounds: Rectangle::new(
+                        Point::new(1.0 / 3.0, 25.0),
+                        Size::new(f32::INFINITY, 10.0),
+                    ),
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.5),
+                    size: 16.0,
+                    letter_spacing: 0.0,
+                    line_height: LineHeight::default(),
+                    font: Font::Default,
+                    horizontal_alignment: alignment::Horizontal::Left,
+                    vertical_alignment: alignment::Vertical::Top,
+                    truncate: Truncate::None,
+                    max_lines: None,
+                    background: None,
+                    shadow: None,
+                    snap_baselines: false,
+                }),
+            },
+        ];
+
+        let viewport = Viewport::with_physical_size(Size::new(800, 600), 1.0);
+        let layers = Layer::generate(&primitives, &viewport);
+
+        assert_eq!(
+            Layer::to_snapshot_string(&layers),
+            "Layer 0 [0, 0, 800x600]\n  \
+             Quad [10.5, 20, 30x40] #ff0000ff\n\
+             Layer 1 [10.5, 20, 30x40]\n  \
+             Text \"Hello\" [0.33, 25, infx10] size 16 #00000080\n"
+        );
+    }
+}
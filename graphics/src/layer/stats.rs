@@ -0,0 +1,73 @@
+use crate::layer::Layer;
+
+use std::fmt;
+
+/// The amount of primitives of each kind in some layers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// The amount of quads.
+    pub quads: usize,
+
+    /// The amount of text runs.
+    pub text: usize,
+
+    /// The amount of images.
+    pub images: usize,
+
+    /// The amount of triangle meshes.
+    pub meshes: usize,
+}
+
+impl Counts {
+    fn of(layer: &Layer<'_>) -> Self {
+        Self {
+            quads: layer.quads.len(),
+            text: layer.text.len(),
+            images: layer.images.len(),
+            meshes: layer.meshes.len() + layer.meshes_3d.len(),
+        }
+    }
+}
+
+/// Statistics about the primitives emitted in a frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayerStats {
+    /// The [`Counts`] of each layer, in order.
+    pub layers: Vec<Counts>,
+
+    /// The aggregated [`Counts`] of all the layers.
+    pub total: Counts,
+}
+
+impl LayerStats {
+    /// Computes the [`LayerStats`] of the given layers.
+    pub fn new(layers: &[Layer<'_>]) -> Self {
+        let layers: Vec<Counts> = layers.iter().map(Counts::of).collect();
+
+        let total =
+            layers
+                .iter()
+                .fold(Counts::default(), |total, counts| Counts {
+                    quads: total.quads + counts.quads,
+                    text: total.text + counts.text,
+                    images: total.images + counts.images,
+                    meshes: total.meshes + counts.meshes,
+                });
+
+        Self { layers, total }
+    }
+}
+
+impl fmt::Display for LayerStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} layers | {} quads | {} text | {} images | {} meshes",
+            self.layers.len(),
+            self.total.quads,
+            self.total.text,
+            self.total.images,
+            self.total.meshes
+        )
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{alignment, Font, Rectangle};
+use crate::layer::RoundedClip;
+use crate::{alignment, Font, LineHeight, Rectangle, Truncate, Vector};
 
 /// A paragraph of text.
 #[derive(Debug, Clone, Copy)]
@@ -15,6 +16,12 @@ pub struct Text<'a> {
     /// The size of the [`Text`].
     pub size: f32,
 
+    /// The extra spacing between the letters of the [`Text`].
+    pub letter_spacing: f32,
+
+    /// The [`LineHeight`] of the [`Text`].
+    pub line_height: LineHeight,
+
     /// The font of the [`Text`].
     pub font: Font,
 
@@ -23,4 +30,41 @@ pub struct Text<'a> {
 
     /// The vertical alignment of the [`Text`].
     pub vertical_alignment: alignment::Vertical,
+
+    /// The way the [`Text`] is shortened when it overflows the width of its
+    /// `bounds`.
+    ///
+    /// It is always [`Truncate::None`] when the width is infinite.
+    pub truncate: Truncate,
+
+    /// The maximum amount of lines of the [`Text`], if any.
+    ///
+    /// Lines past the limit must not be drawn. If the [`Text`] is truncated,
+    /// the last visible line ends with an ellipsis.
+    pub max_lines: Option<usize>,
+
+    /// The color of the highlight drawn behind the glyph runs of the
+    /// [`Text`], in __linear RGB__, if any.
+    pub background: Option<[f32; 4]>,
+
+    /// The drop shadow drawn behind the glyphs of the [`Text`], if any.
+    pub shadow: Option<TextShadow>,
+
+    /// The rounded clipping region of the [`Text`], if any.
+    ///
+    /// Glyphs outside of its rounded corners should be masked out.
+    pub rounded_clip: Option<RoundedClip>,
+}
+
+/// The drop shadow of some [`Text`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextShadow {
+    /// The color of the [`TextShadow`], in __linear RGB__.
+    pub color: [f32; 4],
+
+    /// The offset of the [`TextShadow`] from its [`Text`].
+    pub offset: Vector,
+
+    /// The blur radius of the [`TextShadow`].
+    pub blur: f32,
 }
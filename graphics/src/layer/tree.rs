@@ -0,0 +1,152 @@
+use crate::layer::Layer;
+
+use std::mem;
+
+/// A list of layers together with the clip hierarchy between them.
+///
+/// Every layer, except the first one, is created by a clip, tint, or
+/// viewport-relative primitive found in another layer: its parent. Tools that
+/// reorder or merge layers can use this hierarchy to keep the nesting of the
+/// clips intact.
+#[derive(Debug)]
+pub struct LayerTree<'a> {
+    layers: Vec<Layer<'a>>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<'a> LayerTree<'a> {
+    pub(crate) fn new(
+        layers: Vec<Layer<'a>>,
+        parents: Vec<Option<usize>>,
+    ) -> Self {
+        debug_assert_eq!(layers.len(), parents.len());
+
+        Self { layers, parents }
+    }
+
+    /// Returns the layers of the [`LayerTree`], in drawing order.
+    pub fn layers(&self) -> &[Layer<'a>] {
+        &self.layers
+    }
+
+    /// Returns the index of the parent of the layer with the given index.
+    ///
+    /// The first layer has no parent.
+    pub fn parent(&self, layer: usize) -> Option<usize> {
+        self.parents.get(layer).copied().flatten()
+    }
+
+    /// Returns the indices of the direct children of the layer with the given
+    /// index, in drawing order.
+    pub fn children(&self, layer: usize) -> impl Iterator<Item = usize> + '_ {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(move |(_, parent)| **parent == Some(layer))
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the index of the parent of every layer, in drawing order.
+    pub fn parents(&self) -> &[Option<usize>] {
+        &self.parents
+    }
+
+    /// Merges consecutive layers with the same clipping region, reducing the
+    /// amount of draw calls needed to render them.
+    ///
+    /// Two layers are only merged when concatenating their primitives keeps
+    /// the draw order intact; i.e. when the primitives of the first layer
+    /// are all drawn before the primitives of the second one anyway. Layers
+    /// with different bounds are never merged, even if one contains the
+    /// other, since the merged layer would lose the tighter clip.
+    ///
+    /// A layer is only merged into its parent or into its previous sibling,
+    /// so the clip hierarchy stays intact. The children of a merged layer
+    /// become children of the layer it is merged into.
+    pub fn coalesce(self) -> Self {
+        let mut layers: Vec<Layer<'a>> = Vec::with_capacity(self.layers.len());
+        let mut parents = Vec::with_capacity(self.parents.len());
+
+        // The index of every original layer in the coalesced list
+        let mut indices = Vec::with_capacity(self.layers.len());
+
+        for (layer, parent) in self.layers.into_iter().zip(self.parents) {
+            let parent = parent.map(|parent| indices[parent]);
+            let last = layers.len().checked_sub(1);
+
+            let is_related = last.is_some()
+                && (parent == last
+                    || parent == parents.last().copied().flatten());
+
+            match layers.last_mut() {
+                Some(last) if is_related && last.can_merge(&layer) => {
+                    last.merge(layer);
+                }
+                _ => {
+                    layers.push(layer);
+                    parents.push(parent);
+                }
+            }
+
+            indices.push(layers.len() - 1);
+        }
+
+        Self::new(layers, parents)
+    }
+
+    /// Removes the layers that have nothing to draw, saving the backends from
+    /// setting up their clipping regions.
+    ///
+    /// The first layer is always kept, since it covers the whole viewport, and
+    /// so is any layer with children left, since they are nested in its clip.
+    /// The cache keys of a removed layer are moved to its parent, whose
+    /// content included the cached primitives.
+    pub fn prune(self) -> Self {
+        let Self {
+            mut layers,
+            parents,
+        } = self;
+
+        let mut is_kept = vec![false; layers.len()];
+        let mut has_children = vec![false; layers.len()];
+
+        // Children always come after their parent
+        for index in (0..layers.len()).rev() {
+            is_kept[index] =
+                index == 0 || has_children[index] || !layers[index].is_empty();
+
+            match parents[index] {
+                Some(parent) if is_kept[index] => has_children[parent] = true,
+                Some(parent) => {
+                    let cache_keys = mem::take(&mut layers[index].cache_keys);
+
+                    layers[parent].cache_keys.extend(cache_keys);
+                }
+                None => {}
+            }
+        }
+
+        // The index of every kept layer in the pruned list
+        let mut indices = vec![0; layers.len()];
+        let mut pruned_layers = Vec::with_capacity(layers.len());
+        let mut pruned_parents = Vec::with_capacity(layers.len());
+
+        for (index, (layer, parent)) in
+            layers.into_iter().zip(parents).enumerate()
+        {
+            if is_kept[index] {
+                indices[index] = pruned_layers.len();
+
+                pruned_layers.push(layer);
+                pruned_parents.push(parent.map(|parent| indices[parent]));
+            }
+        }
+
+        Self::new(pruned_layers, pruned_parents)
+    }
+
+    /// Consumes the [`LayerTree`] and returns its layers.
+    pub fn into_layers(self) -> Vec<Layer<'a>> {
+        self.layers
+    }
+}
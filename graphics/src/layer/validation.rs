@@ -0,0 +1,285 @@
+//! Check the generated layers before uploading them to the GPU.
+use crate::layer::{quad, Image, Layer};
+use crate::Rectangle;
+
+/// The largest coordinate magnitude considered valid.
+///
+/// Anything larger is almost certainly the result of a bug and may crash
+/// some graphics drivers.
+pub const MAX_COORDINATE: f32 = 1.0e7;
+
+/// The kind of primitive that failed validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// The clipping bounds of the layer itself.
+    Layer,
+    /// A [`Quad`](crate::layer::Quad).
+    Quad,
+    /// A [`quad::Gradient`].
+    QuadGradient,
+    /// A [`quad::Image`].
+    QuadImage,
+    /// A [`quad::Batch`].
+    QuadBatch,
+    /// A [`Shadow`](crate::layer::Shadow).
+    Shadow,
+    /// An [`Arc`](crate::layer::Arc).
+    Arc,
+    /// A [`Mesh`](crate::layer::Mesh).
+    Mesh,
+    /// A [`Mesh3D`](crate::layer::Mesh3D).
+    Mesh3D,
+    /// A [`Text`](crate::layer::Text).
+    Text,
+    /// An [`Image`].
+    Image,
+}
+
+/// A primitive with non-finite or out of range coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("{kind:?} #{index} of layer #{layer} has invalid coordinates")]
+pub struct ValidationError {
+    /// The index of the [`Layer`] containing the primitive.
+    pub layer: usize,
+
+    /// The [`Kind`] of the primitive.
+    pub kind: Kind,
+
+    /// The index of the primitive in its list of the [`Layer`].
+    pub index: usize,
+}
+
+/// Checks that all the primitives of the given layers have finite
+/// coordinates within [`MAX_COORDINATE`].
+///
+/// Text bounds may be infinite, since they only limit the layout of the text.
+pub fn validate_layers(
+    layers: &[Layer<'_>],
+) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+
+    for (i, layer) in layers.iter().enumerate() {
+        let mut check = |kind, index, valid: bool| {
+            if !valid {
+                errors.push(ValidationError {
+                    layer: i,
+                    kind,
+                    index,
+                });
+            }
+        };
+
+        check(Kind::Layer, 0, is_valid_rectangle(&layer.bounds));
+
+        for (index, quad) in layer.quads.iter().enumerate() {
+            check(
+                Kind::Quad,
+                index,
+                are_valid(&quad.position)
+                    && are_valid(&quad.size)
+                    && are_valid(&quad.border_radius)
+                    && are_valid(&quad.border_width),
+            );
+        }
+
+        for (index, gradient) in layer.quad_gradients.iter().enumerate() {
+            let valid = match &gradient.shape {
+                quad::Shape::Linear { start, end } => {
+                    are_valid(start) && are_valid(end)
+                }
+                quad::Shape::Radial { center, radius } => {
+                    are_valid(center) && is_valid(*radius)
+                }
+                quad::Shape::Conic {
+                    center,
+                    start_angle,
+                } => are_valid(center) && is_valid(*start_angle),
+            };
+
+            let stops = &gradient.stops;
+
+            check(
+                Kind::QuadGradient,
+                index,
+                valid
+                    && gradient.quad < layer.quads.len()
+                    && !stops.is_empty()
+                    && stops
+                        .iter()
+                        .all(|(offset, _)| (0.0..=1.0).contains(offset))
+                    && stops.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+            );
+        }
+
+        for (index, image) in layer.quad_images.iter().enumerate() {
+            check(Kind::QuadImage, index, image.quad < layer.quads.len());
+        }
+
+        for (index, batch) in layer.quad_batches.iter().enumerate() {
+            check(
+                Kind::QuadBatch,
+                index,
+                batch.instances.iter().all(|instance| {
+                    are_valid(&instance.position) && are_valid(&instance.size)
+                }),
+            );
+        }
+
+        for (index, shadow) in layer.shadows.iter().enumerate() {
+            check(
+                Kind::Shadow,
+                index,
+                is_valid_rectangle(&shadow.bounds)
+                    && are_valid(&shadow.border_radius)
+                    && are_valid(&[shadow.offset.x, shadow.offset.y])
+                    && are_valid(&[shadow.blur_radius, shadow.spread_radius]),
+            );
+        }
+
+        for (index, arc) in layer.arcs.iter().enumerate() {
+            check(
+                Kind::Arc,
+                index,
+                are_valid(&arc.center)
+                    && are_valid(&[
+                        arc.radius,
+                        arc.width,
+                        arc.start_angle,
+                        arc.end_angle,
+                    ]),
+            );
+        }
+
+        for (index, mesh) in layer.meshes.iter().enumerate() {
+            let origin = mesh.origin();
+
+            check(
+                Kind::Mesh,
+                index,
+                are_valid(&[origin.x, origin.y])
+                    && is_valid_rectangle(&mesh.clip_bounds()),
+            );
+        }
+
+        for (index, mesh) in layer.meshes_3d.iter().enumerate() {
+            check(
+                Kind::Mesh3D,
+                index,
+                are_valid(mesh.transformation.as_ref())
+                    && is_valid_rectangle(&mesh.clip_bounds),
+            );
+        }
+
+        for (index, text) in layer.text.iter().enumerate() {
+            check(
+                Kind::Text,
+                index,
+                are_valid(&[text.bounds.x, text.bounds.y, text.size])
+                    && text.shadow.iter().all(|shadow| {
+                        are_valid(&[
+                            shadow.offset.x,
+                            shadow.offset.y,
+                            shadow.blur,
+                        ])
+                    })
+                    && !text.bounds.width.is_nan()
+                    && !text.bounds.height.is_nan(),
+            );
+        }
+
+        for (index, image) in layer.images.iter().enumerate() {
+            let is_valid_source = match image {
+                Image::Raster {
+                    source: Some(source),
+                    ..
+                } => is_valid_rectangle(source),
+                Image::NinePatch { insets, .. } => are_valid(insets),
+                Image::Raster { source: None, .. } | Image::Vector { .. } => {
+                    true
+                }
+            };
+
+            check(
+                Kind::Image,
+                index,
+                is_valid_rectangle(&image.bounds()) && is_valid_source,
+            );
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn is_valid(value: f32) -> bool {
+    value.is_finite() && value.abs() <= MAX_COORDINATE
+}
+
+fn are_valid(values: &[f32]) -> bool {
+    values.iter().copied().all(is_valid)
+}
+
+fn is_valid_rectangle(rectangle: &Rectangle) -> bool {
+    are_valid(&[rectangle.x, rectangle.y, rectangle.width, rectangle.height])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::Quad;
+    use crate::{Point, Size};
+
+    fn quad(position: [f32; 2]) -> Quad {
+        Quad {
+            position,
+            size: [10.0, 10.0],
+            color: [0.0, 0.0, 0.0, 1.0],
+            border_color: [0.0; 4],
+            border_radius: [0.0; 4],
+            border_width: [0.0; 4],
+            border_only: 0,
+            corner_smoothing: 0.0,
+        }
+    }
+
+    #[test]
+    fn clean_layers_pass() {
+        let mut layer =
+            Layer::new(Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0)));
+
+        layer.quads.push(quad([10.0, 10.0]));
+
+        assert_eq!(validate_layers(&[layer]), Ok(()));
+    }
+
+    #[test]
+    fn nan_quads_are_reported() {
+        let bounds = Rectangle::new(Point::ORIGIN, Size::new(800.0, 600.0));
+
+        let mut layer = Layer::new(bounds);
+        layer.quads.push(quad([10.0, 10.0]));
+        layer.quads.push(quad([f32::NAN, 10.0]));
+
+        let mut huge = Layer::new(bounds);
+        huge.quads.push(quad([0.0, 1.0e9]));
+
+        assert_eq!(
+            validate_layers(&[layer, huge]),
+            Err(vec![
+                ValidationError {
+                    layer: 0,
+                    kind: Kind::Quad,
+                    index: 1,
+                },
+                ValidationError {
+                    layer: 1,
+                    kind: Kind::Quad,
+                    index: 0,
+                },
+            ])
+        );
+    }
+}
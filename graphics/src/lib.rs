@@ -22,17 +22,26 @@
 #![allow(clippy::inherent_to_string, clippy::type_complexity)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 mod antialiasing;
+mod border;
+mod clip_mode;
 mod error;
+mod line_height;
+mod paint;
 mod primitive;
+mod quad_instance;
+mod text_shadow;
 mod transformation;
+mod truncate;
 mod viewport;
 
 pub mod backend;
+pub mod color;
 pub mod font;
 pub mod gradient;
 pub mod image;
 pub mod layer;
 pub mod overlay;
+pub mod path;
 pub mod renderer;
 pub mod triangle;
 pub mod widget;
@@ -40,12 +49,21 @@ pub mod window;
 
 pub use antialiasing::Antialiasing;
 pub use backend::Backend;
+pub use border::{BorderAlignment, BorderColor, BorderWidth};
+pub use clip_mode::ClipMode;
 pub use error::Error;
 pub use gradient::Gradient;
 pub use layer::Layer;
-pub use primitive::Primitive;
+pub use line_height::LineHeight;
+pub use paint::{GradientError, ImageFit, ImageRepeat, Paint};
+pub use primitive::{Primitive, PrimitiveVisitor};
+pub use quad_instance::QuadInstance;
 pub use renderer::Renderer;
-pub use transformation::Transformation;
+pub use text_shadow::TextShadow;
+pub use transformation::{
+    Transformation, TranslateRotateScale, TranslateScale,
+};
+pub use truncate::Truncate;
 pub use viewport::Viewport;
 pub use window::compositor;
 
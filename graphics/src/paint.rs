@@ -0,0 +1,504 @@
+use crate::{Background, Color, Point, Rectangle, Size, Vector};
+
+use iced_native::image;
+
+/// The paint filling the background of a quad.
+///
+/// It extends [`Background`] with paints that cannot be cheaply copied, like
+/// images and gradients with any number of color stops.
+///
+/// A gradient without any stops leaves the quad transparent.
+#[derive(Debug, Clone)]
+pub enum Paint {
+    /// A [`Background`]
+    Background(Background),
+    /// A linear gradient with any number of color stops
+    ///
+    /// Like [`Background::LinearGradient`], it spans the quad along its
+    /// direction.
+    LinearGradient {
+        /// The direction of the gradient
+        direction: Vector,
+        /// The color stops of the gradient, as pairs of an offset and a color
+        ///
+        /// Offsets are fractions of the gradient line, in `[0, 1]`. Stops
+        /// are sorted by offset before being drawn.
+        stops: Vec<(f32, Color)>,
+    },
+    /// A radial gradient with any number of color stops
+    RadialGradient {
+        /// The center of the gradient, relative to the top-left corner of the
+        /// quad
+        center: Point,
+        /// The radius of the gradient
+        radius: f32,
+        /// The color stops of the gradient, as pairs of an offset and a color
+        ///
+        /// Offsets are fractions of the radius, in `[0, 1]`. Stops are sorted
+        /// by offset before being drawn.
+        stops: Vec<(f32, Color)>,
+    },
+    /// A conic gradient, sweeping its colors around a center point
+    ConicGradient {
+        /// The center of the gradient
+        ///
+        /// It is expressed in the same coordinate system as the bounds of the
+        /// quad.
+        center: Point,
+        /// The angle where the gradient starts, in radians, clockwise from
+        /// the positive X axis
+        start_angle: f32,
+        /// The color stops of the gradient, as pairs of an offset and a color
+        ///
+        /// Offsets are fractions of a full turn, in `[0, 1]`. Stops are
+        /// sorted by offset before being drawn.
+        stops: Vec<(f32, Color)>,
+    },
+    /// An image
+    ///
+    /// It is clipped by the inner edge of the border of the quad.
+    Image {
+        /// The handle of the image
+        handle: image::Handle,
+        /// How the image fills the quad, unless it is repeated
+        fit: ImageFit,
+        /// The axes along which the image is repeated across the quad
+        repeat: ImageRepeat,
+        /// The size of each repetition of the image, if any
+        ///
+        /// Otherwise, the image is repeated at its intrinsic size.
+        tile_size: Option<Size>,
+    },
+}
+
+/// The way an image fills its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFit {
+    /// The image is stretched to fill its bounds.
+    Fill,
+    /// The image is scaled to fit inside its bounds, preserving its aspect
+    /// ratio.
+    Contain,
+    /// The image is scaled to cover its bounds, preserving its aspect ratio.
+    ///
+    /// The parts of the image outside of its bounds are cropped.
+    Cover,
+    /// The image behaves like [`ImageFit::Contain`], but it is never scaled
+    /// up beyond its intrinsic size.
+    ScaleDown,
+}
+
+impl ImageFit {
+    /// Returns the rectangle where an image of the given intrinsic size is
+    /// drawn to fill the given bounds.
+    ///
+    /// The rectangle is centered in the bounds, except for [`ImageFit::Fill`],
+    /// which uses the bounds directly. With [`ImageFit::Cover`], it may exceed
+    /// the bounds, which should clip it.
+    pub fn fit(self, image_size: Size, bounds: Rectangle) -> Rectangle {
+        if image_size.width <= 0.0 || image_size.height <= 0.0 {
+            return bounds;
+        }
+
+        let contain = (bounds.width / image_size.width)
+            .min(bounds.height / image_size.height);
+
+        let scale = match self {
+            ImageFit::Fill => return bounds,
+            ImageFit::Contain => contain,
+            ImageFit::Cover => (bounds.width / image_size.width)
+                .max(bounds.height / image_size.height),
+            ImageFit::ScaleDown => contain.min(1.0),
+        };
+
+        let size =
+            Size::new(image_size.width * scale, image_size.height * scale);
+
+        Rectangle {
+            x: bounds.x + (bounds.width - size.width) / 2.0,
+            y: bounds.y + (bounds.height - size.height) / 2.0,
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+impl Default for ImageFit {
+    fn default() -> Self {
+        ImageFit::Fill
+    }
+}
+
+/// The axes along which an image is repeated to fill its bounds.
+///
+/// A repeated image is tiled at its `tile_size`, or at its intrinsic size,
+/// and ignores its [`ImageFit`], which only applies to
+/// [`ImageRepeat::NoRepeat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageRepeat {
+    /// The image is drawn once, following its [`ImageFit`].
+    NoRepeat,
+    /// The image is repeated horizontally.
+    RepeatX,
+    /// The image is repeated vertically.
+    RepeatY,
+    /// The image is repeated in both directions.
+    Repeat,
+}
+
+impl Default for ImageRepeat {
+    fn default() -> Self {
+        ImageRepeat::NoRepeat
+    }
+}
+
+/// An error produced when validating the inputs of a gradient [`Paint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum GradientError {
+    /// The gradient has no color stops.
+    #[error("the gradient has no color stops")]
+    NoStops,
+
+    /// The offset of a stop is not within `[0, 1]`.
+    #[error("the offset of stop #{index} is not within [0, 1]")]
+    OffsetOutOfRange {
+        /// The index of the stop
+        index: usize,
+    },
+
+    /// A stop is placed before the previous one.
+    #[error("stop #{index} is placed before the previous one")]
+    UnorderedStops {
+        /// The index of the stop
+        index: usize,
+    },
+
+    /// The angle of the gradient is not finite.
+    #[error("the angle of the gradient is not finite")]
+    NonFiniteAngle,
+
+    /// The radius of the gradient is negative or not finite.
+    #[error("the radius of the gradient is negative or not finite")]
+    InvalidRadius,
+}
+
+impl Paint {
+    /// Creates a [`Paint::LinearGradient`] from an angle in degrees, following
+    /// the convention of [`Background::linear_gradient_deg`].
+    ///
+    /// The stops must not be empty, and their offsets must be sorted and
+    /// within `[0, 1]`.
+    pub fn try_linear_gradient(
+        angle_degrees: f32,
+        stops: Vec<(f32, Color)>,
+    ) -> Result<Self, GradientError> {
+        validate_angle(angle_degrees)?;
+        validate_stops(&stops)?;
+
+        Ok(Paint::LinearGradient {
+            direction: Background::linear_gradient_direction(angle_degrees),
+            stops,
+        })
+    }
+
+    /// Creates a [`Paint::RadialGradient`].
+    ///
+    /// The radius must be finite and not negative. The stops must not be
+    /// empty, and their offsets must be sorted and within `[0, 1]`.
+    pub fn try_radial_gradient(
+        center: Point,
+        radius: f32,
+        stops: Vec<(f32, Color)>,
+    ) -> Result<Self, GradientError> {
+        validate_radius(radius)?;
+        validate_stops(&stops)?;
+
+        Ok(Paint::RadialGradient {
+            center,
+            radius,
+            stops,
+        })
+    }
+
+    /// Creates a [`Paint::ConicGradient`].
+    ///
+    /// The start angle must be finite. The stops must not be empty, and their
+    /// offsets must be sorted and within `[0, 1]`.
+    pub fn try_conic_gradient(
+        center: Point,
+        start_angle: f32,
+        stops: Vec<(f32, Color)>,
+    ) -> Result<Self, GradientError> {
+        validate_angle(start_angle)?;
+        validate_stops(&stops)?;
+
+        Ok(Paint::ConicGradient {
+            center,
+            start_angle,
+            stops,
+        })
+    }
+
+    /// Creates a [`Paint::LinearGradient`], like
+    /// [`Paint::try_linear_gradient`].
+    ///
+    /// # Panics
+    /// Panics in debug builds if the inputs are invalid. In release builds,
+    /// they are sanitized instead.
+    pub fn linear_gradient(
+        angle_degrees: f32,
+        stops: Vec<(f32, Color)>,
+    ) -> Self {
+        let validation =
+            validate_angle(angle_degrees).and_then(|()| validate_stops(&stops));
+
+        Paint::LinearGradient {
+            direction: Background::linear_gradient_direction(finite_or_zero(
+                angle_degrees,
+            )),
+            stops: valid_or_sanitized(validation, stops),
+        }
+    }
+
+    /// Creates a [`Paint::RadialGradient`], like
+    /// [`Paint::try_radial_gradient`].
+    ///
+    /// # Panics
+    /// Panics in debug builds if the inputs are invalid. In release builds,
+    /// they are sanitized instead.
+    pub fn radial_gradient(
+        center: Point,
+        radius: f32,
+        stops: Vec<(f32, Color)>,
+    ) -> Self {
+        let validation =
+            validate_radius(radius).and_then(|()| validate_stops(&stops));
+
+        Paint::RadialGradient {
+            center,
+            radius: finite_or_zero(radius).max(0.0),
+            stops: valid_or_sanitized(validation, stops),
+        }
+    }
+
+    /// Creates a [`Paint::ConicGradient`], like
+    /// [`Paint::try_conic_gradient`].
+    ///
+    /// # Panics
+    /// Panics in debug builds if the inputs are invalid. In release builds,
+    /// they are sanitized instead.
+    pub fn conic_gradient(
+        center: Point,
+        start_angle: f32,
+        stops: Vec<(f32, Color)>,
+    ) -> Self {
+        let validation =
+            validate_angle(start_angle).and_then(|()| validate_stops(&stops));
+
+        Paint::ConicGradient {
+            center,
+            start_angle: finite_or_zero(start_angle),
+            stops: valid_or_sanitized(validation, stops),
+        }
+    }
+}
+
+fn validate_angle(angle: f32) -> Result<(), GradientError> {
+    if angle.is_finite() {
+        Ok(())
+    } else {
+        Err(GradientError::NonFiniteAngle)
+    }
+}
+
+fn validate_radius(radius: f32) -> Result<(), GradientError> {
+    if radius.is_finite() && radius >= 0.0 {
+        Ok(())
+    } else {
+        Err(GradientError::InvalidRadius)
+    }
+}
+
+fn validate_stops(stops: &[(f32, Color)]) -> Result<(), GradientError> {
+    if stops.is_empty() {
+        return Err(GradientError::NoStops);
+    }
+
+    for (index, (offset, _)) in stops.iter().enumerate() {
+        if !(0.0..=1.0).contains(offset) {
+            return Err(GradientError::OffsetOutOfRange { index });
+        }
+
+        if index > 0 && *offset < stops[index - 1].0 {
+            return Err(GradientError::UnorderedStops { index });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the given stops if the gradient is valid. Otherwise, reports the
+/// error in debug builds and sanitizes the stops in release builds.
+fn valid_or_sanitized(
+    validation: Result<(), GradientError>,
+    stops: Vec<(f32, Color)>,
+) -> Vec<(f32, Color)> {
+    match validation {
+        Ok(()) => stops,
+        Err(error) => {
+            if cfg!(debug_assertions) {
+                panic!("invalid gradient: {}", error);
+            }
+
+            sanitize_stops(stops)
+        }
+    }
+}
+
+fn finite_or_zero(value: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        0.0
+    }
+}
+
+/// Drops the stops with a NaN offset, clamps the rest to `[0, 1]`, and sorts
+/// them.
+fn sanitize_stops(stops: Vec<(f32, Color)>) -> Vec<(f32, Color)> {
+    let mut stops: Vec<_> = stops
+        .into_iter()
+        .filter(|(offset, _)| !offset.is_nan())
+        .map(|(offset, color)| (offset.clamp(0.0, 1.0), color))
+        .collect();
+
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    stops
+}
+
+impl From<Background> for Paint {
+    fn from(background: Background) -> Self {
+        Paint::Background(background)
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Paint::Background(Background::Color(color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops(offsets: &[f32]) -> Vec<(f32, Color)> {
+        offsets
+            .iter()
+            .map(|offset| (*offset, Color::WHITE))
+            .collect()
+    }
+
+    #[test]
+    fn invalid_gradients_are_rejected() {
+        assert_eq!(
+            Paint::try_linear_gradient(90.0, Vec::new()).unwrap_err(),
+            GradientError::NoStops
+        );
+        assert_eq!(
+            Paint::try_linear_gradient(90.0, stops(&[0.0, 1.5])).unwrap_err(),
+            GradientError::OffsetOutOfRange { index: 1 }
+        );
+        assert_eq!(
+            Paint::try_linear_gradient(90.0, stops(&[f32::NAN])).unwrap_err(),
+            GradientError::OffsetOutOfRange { index: 0 }
+        );
+        assert_eq!(
+            Paint::try_linear_gradient(90.0, stops(&[0.0, 0.6, 0.4]))
+                .unwrap_err(),
+            GradientError::UnorderedStops { index: 2 }
+        );
+        assert_eq!(
+            Paint::try_linear_gradient(f32::NAN, stops(&[0.0])).unwrap_err(),
+            GradientError::NonFiniteAngle
+        );
+        assert_eq!(
+            Paint::try_conic_gradient(
+                Point::ORIGIN,
+                f32::INFINITY,
+                stops(&[0.0])
+            )
+            .unwrap_err(),
+            GradientError::NonFiniteAngle
+        );
+        assert_eq!(
+            Paint::try_radial_gradient(Point::ORIGIN, -1.0, stops(&[0.0]))
+                .unwrap_err(),
+            GradientError::InvalidRadius
+        );
+    }
+
+    #[test]
+    fn valid_gradients_are_created() {
+        let gradient =
+            Paint::try_linear_gradient(90.0, stops(&[0.0, 0.5, 0.5, 1.0]));
+
+        match gradient {
+            Ok(Paint::LinearGradient { direction, stops }) => {
+                assert!((direction.x - 1.0).abs() < 1.0e-6);
+                assert!(direction.y.abs() < 1.0e-6);
+                assert_eq!(stops.len(), 4);
+            }
+            gradient => panic!("expected a linear gradient: {:?}", gradient),
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid gradient")]
+    fn invalid_gradients_panic_in_debug_builds() {
+        let _ = Paint::radial_gradient(Point::ORIGIN, 10.0, stops(&[2.0]));
+    }
+
+    #[test]
+    fn invalid_stops_are_sanitized() {
+        assert_eq!(
+            sanitize_stops(stops(&[0.8, f32::NAN, -1.0, 2.0])),
+            stops(&[0.0, 0.8, 1.0])
+        );
+    }
+
+    #[test]
+    fn fits_preserve_the_aspect_ratio_of_images() {
+        // A wide image in a square
+        let image = Size::new(200.0, 100.0);
+        let bounds =
+            Rectangle::new(Point::new(10.0, 10.0), Size::new(50.0, 50.0));
+
+        assert_eq!(ImageFit::Fill.fit(image, bounds), bounds);
+        assert_eq!(
+            ImageFit::Contain.fit(image, bounds),
+            Rectangle::new(Point::new(10.0, 22.5), Size::new(50.0, 25.0))
+        );
+        assert_eq!(
+            ImageFit::Cover.fit(image, bounds),
+            Rectangle::new(Point::new(-15.0, 10.0), Size::new(100.0, 50.0))
+        );
+        assert_eq!(
+            ImageFit::ScaleDown.fit(image, bounds),
+            ImageFit::Contain.fit(image, bounds)
+        );
+
+        // Small images are not scaled up
+        let icon = Size::new(20.0, 10.0);
+
+        assert_eq!(
+            ImageFit::ScaleDown.fit(icon, bounds),
+            Rectangle::new(Point::new(25.0, 30.0), icon)
+        );
+        assert_eq!(
+            ImageFit::Contain.fit(icon, bounds),
+            ImageFit::Contain.fit(image, bounds)
+        );
+    }
+}
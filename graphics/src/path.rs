@@ -0,0 +1,421 @@
+//! Describe shapes made of straight line segments.
+use crate::Point;
+
+use lyon::tessellation;
+
+/// A shape made of one or more contours of straight line segments.
+///
+/// Unlike the path of a canvas, it is not tessellated until layers are
+/// generated, which lets it be stored directly in a [`Primitive`].
+///
+/// [`Primitive`]: crate::Primitive
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+/// A segment of a [`Path`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    /// Starts a new contour at the given [`Point`].
+    MoveTo(Point),
+
+    /// Draws a straight line from the current position to the given
+    /// [`Point`].
+    LineTo(Point),
+
+    /// Closes the current contour.
+    Close,
+}
+
+/// The fill rule defines how to determine what is inside and what is outside of
+/// a shape.
+///
+/// See the [SVG specification][1].
+///
+/// [1]: https://www.w3.org/TR/SVG/painting.html#FillRuleProperty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+impl Default for FillRule {
+    fn default() -> Self {
+        FillRule::NonZero
+    }
+}
+
+impl From<FillRule> for tessellation::FillRule {
+    fn from(rule: FillRule) -> tessellation::FillRule {
+        match rule {
+            FillRule::NonZero => tessellation::FillRule::NonZero,
+            FillRule::EvenOdd => tessellation::FillRule::EvenOdd,
+        }
+    }
+}
+
+/// The shape used at the end of open subpaths when they are stroked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    /// The stroke for each sub-path does not extend beyond its two endpoints.
+    Butt,
+    /// At the end of each sub-path, the shape representing the stroke will be
+    /// extended by a square.
+    Square,
+    /// At the end of each sub-path, the shape representing the stroke will be
+    /// extended by a semicircle.
+    Round,
+}
+
+impl From<LineCap> for tessellation::LineCap {
+    fn from(line_cap: LineCap) -> tessellation::LineCap {
+        match line_cap {
+            LineCap::Butt => tessellation::LineCap::Butt,
+            LineCap::Square => tessellation::LineCap::Square,
+            LineCap::Round => tessellation::LineCap::Round,
+        }
+    }
+}
+
+impl Default for LineCap {
+    fn default() -> LineCap {
+        LineCap::Butt
+    }
+}
+
+/// The shape used at the corners of paths or basic shapes when they are
+/// stroked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    /// A sharp corner.
+    Miter,
+    /// A round corner.
+    Round,
+    /// A bevelled corner.
+    Bevel,
+}
+
+impl From<LineJoin> for tessellation::LineJoin {
+    fn from(line_join: LineJoin) -> tessellation::LineJoin {
+        match line_join {
+            LineJoin::Miter => tessellation::LineJoin::Miter,
+            LineJoin::Round => tessellation::LineJoin::Round,
+            LineJoin::Bevel => tessellation::LineJoin::Bevel,
+        }
+    }
+}
+
+impl Default for LineJoin {
+    fn default() -> LineJoin {
+        LineJoin::Miter
+    }
+}
+
+impl Path {
+    /// Creates a new empty [`Path`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a closed [`Path`] going through the given points.
+    pub fn polygon(points: impl IntoIterator<Item = Point>) -> Self {
+        let mut points = points.into_iter();
+
+        let path = match points.next() {
+            Some(first) => {
+                points.fold(Self::new().move_to(first), Path::line_to)
+            }
+            None => Self::new(),
+        };
+
+        path.close()
+    }
+
+    /// Starts a new contour of the [`Path`] at the given [`Point`].
+    pub fn move_to(mut self, point: Point) -> Self {
+        self.segments.push(Segment::MoveTo(point));
+        self
+    }
+
+    /// Adds a straight line from the current position of the [`Path`] to the
+    /// given [`Point`].
+    pub fn line_to(mut self, point: Point) -> Self {
+        self.segments.push(Segment::LineTo(point));
+        self
+    }
+
+    /// Closes the current contour of the [`Path`].
+    pub fn close(mut self) -> Self {
+        self.segments.push(Segment::Close);
+        self
+    }
+
+    /// Returns the segments of the [`Path`].
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Returns the points of each contour of the [`Path`].
+    ///
+    /// A closing point equal to the start of its contour is skipped.
+    pub fn contours(&self) -> Vec<Vec<Point>> {
+        self.subpaths()
+            .into_iter()
+            .map(|(contour, _)| contour)
+            .collect()
+    }
+
+    /// Returns the points of each contour of the [`Path`], together with
+    /// whether it is closed.
+    fn subpaths(&self) -> Vec<(Vec<Point>, bool)> {
+        let mut subpaths: Vec<(Vec<Point>, bool)> = Vec::new();
+        let mut current = Vec::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::MoveTo(point) => {
+                    subpaths.push((std::mem::take(&mut current), false));
+                    current.push(*point);
+                }
+                Segment::LineTo(point) => current.push(*point),
+                Segment::Close => {
+                    subpaths.push((std::mem::take(&mut current), true));
+                }
+            }
+        }
+
+        subpaths.push((current, false));
+
+        subpaths
+            .into_iter()
+            .filter_map(|(mut contour, is_closed)| {
+                contour.dedup();
+
+                if contour.len() > 1 && contour.first() == contour.last() {
+                    let _ = contour.pop();
+                }
+
+                (!contour.is_empty()).then_some((contour, is_closed))
+            })
+            .collect()
+    }
+
+    /// Converts the [`Path`] into a path that `lyon` can tessellate.
+    fn to_lyon(&self) -> lyon::path::Path {
+        let mut builder = lyon::path::Path::builder();
+
+        for (contour, is_closed) in self.subpaths() {
+            let mut points = contour
+                .into_iter()
+                .map(|point| lyon::math::point(point.x, point.y));
+
+            if let Some(first) = points.next() {
+                let _ = builder.begin(first);
+
+                for point in points {
+                    let _ = builder.line_to(point);
+                }
+
+                builder.end(is_closed);
+            }
+        }
+
+        builder.build()
+    }
+
+    /// Tessellates the interior of the [`Path`] into triangles, returning
+    /// their vertices and indices.
+    ///
+    /// All of its contours are tessellated together, so the given
+    /// [`FillRule`] decides which of their overlapping areas are filled.
+    pub(crate) fn tessellate(
+        &self,
+        fill_rule: FillRule,
+    ) -> (Vec<Point>, Vec<u32>) {
+        let mut buffers: tessellation::VertexBuffers<Point, u32> =
+            tessellation::VertexBuffers::new();
+
+        let result = tessellation::FillTessellator::new().tessellate_path(
+            &self.to_lyon(),
+            &tessellation::FillOptions::default()
+                .with_fill_rule(fill_rule.into()),
+            &mut tessellation::BuffersBuilder::new(
+                &mut buffers,
+                |vertex: tessellation::FillVertex<'_>| {
+                    let position = vertex.position();
+
+                    Point::new(position.x, position.y)
+                },
+            ),
+        );
+
+        triangles(buffers, result)
+    }
+
+    /// Tessellates the outline of the [`Path`] stroked with the given width,
+    /// returning the vertices and indices of its triangles.
+    ///
+    /// The segments, joins, and caps of the outline do not overlap, so
+    /// translucent strokes are blended only once.
+    pub(crate) fn stroke(
+        &self,
+        width: f32,
+        line_cap: LineCap,
+        line_join: LineJoin,
+    ) -> (Vec<Point>, Vec<u32>) {
+        if width.is_nan() || width <= 0.0 {
+            return (Vec::new(), Vec::new());
+        }
+
+        let mut buffers: tessellation::VertexBuffers<Point, u32> =
+            tessellation::VertexBuffers::new();
+
+        let result = tessellation::StrokeTessellator::new().tessellate_path(
+            &self.to_lyon(),
+            &tessellation::StrokeOptions::default()
+                .with_line_width(width)
+                .with_line_cap(line_cap.into())
+                .with_line_join(line_join.into()),
+            &mut tessellation::BuffersBuilder::new(
+                &mut buffers,
+                |vertex: tessellation::StrokeVertex<'_, '_>| {
+                    let position = vertex.position();
+
+                    Point::new(position.x, position.y)
+                },
+            ),
+        );
+
+        triangles(buffers, result)
+    }
+}
+
+/// Returns the vertices and indices of the given buffers, unless their
+/// tessellation failed.
+fn triangles(
+    buffers: tessellation::VertexBuffers<Point, u32>,
+    result: tessellation::TessellationResult,
+) -> (Vec<Point>, Vec<u32>) {
+    if let Err(error) = result {
+        log::warn!("Failed to tessellate path: {:?}", error);
+
+        return (Vec::new(), Vec::new());
+    }
+
+    (buffers.vertices, buffers.indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(vertices: &[Point], indices: &[u32]) -> f32 {
+        indices
+            .chunks(3)
+            .map(|triangle| {
+                let [a, b, c] =
+                    [0, 1, 2].map(|i| vertices[triangle[i] as usize]);
+
+                ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)).abs()
+                    / 2.0
+            })
+            .sum()
+    }
+
+    fn square(min: f32, max: f32) -> [Point; 4] {
+        [
+            Point::new(min, min),
+            Point::new(max, min),
+            Point::new(max, max),
+            Point::new(min, max),
+        ]
+    }
+
+    #[test]
+    fn concave_polygon_is_triangulated_without_overlaps() {
+        // An L shape, whose area is 3
+        let path = Path::polygon([
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 2.0),
+        ]);
+
+        let (vertices, indices) = path.tessellate(FillRule::NonZero);
+
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(indices.len(), 4 * 3);
+        assert_eq!(area(&vertices, &indices), 3.0);
+    }
+
+    #[test]
+    fn ring_leaves_its_hole_unfilled() {
+        let ring = |inner: [Point; 4]| {
+            let outer = Path::polygon(square(0.0, 10.0));
+
+            inner
+                .into_iter()
+                .skip(1)
+                .fold(outer.move_to(inner[0]), Path::line_to)
+                .close()
+        };
+
+        let mut reversed = square(3.0, 7.0);
+        reversed.reverse();
+
+        let filled = |path: Path, fill_rule| {
+            let (vertices, indices) = path.tessellate(fill_rule);
+
+            area(&vertices, &indices)
+        };
+
+        assert_eq!(filled(ring(square(3.0, 7.0)), FillRule::EvenOdd), 84.0);
+        assert_eq!(filled(ring(reversed), FillRule::EvenOdd), 84.0);
+        assert_eq!(filled(ring(reversed), FillRule::NonZero), 84.0);
+        assert_eq!(filled(ring(square(3.0, 7.0)), FillRule::NonZero), 100.0);
+    }
+
+    #[test]
+    fn caps_and_joins_extend_the_stroke() {
+        let path = Path::new()
+            .move_to(Point::new(0.0, 0.0))
+            .line_to(Point::new(10.0, 0.0))
+            .line_to(Point::new(10.0, 10.0));
+
+        let extent = |line_cap, line_join| {
+            let (vertices, _) = path.stroke(2.0, line_cap, line_join);
+
+            vertices.iter().fold((0.0f32, 0.0f32), |(min, max), point| {
+                (min.min(point.x), max.max(point.x))
+            })
+        };
+
+        assert_eq!(extent(LineCap::Butt, LineJoin::Bevel), (0.0, 11.0));
+        assert_eq!(extent(LineCap::Square, LineJoin::Bevel), (-1.0, 11.0));
+        assert_eq!(extent(LineCap::Butt, LineJoin::Miter), (0.0, 11.0));
+    }
+
+    #[test]
+    fn stroked_joins_do_not_overlap() {
+        let path = Path::new()
+            .move_to(Point::new(0.0, 0.0))
+            .line_to(Point::new(10.0, 0.0))
+            .line_to(Point::new(10.0, 10.0));
+
+        let stroked = |line_join| {
+            let (vertices, indices) =
+                path.stroke(2.0, LineCap::Butt, line_join);
+
+            area(&vertices, &indices)
+        };
+
+        // Two 10x2 segments sharing a 1x1 square at the inner corner, plus
+        // the outer corner of the join
+        assert!((stroked(LineJoin::Miter) - 40.0).abs() < 1e-3);
+        assert!((stroked(LineJoin::Bevel) - 39.5).abs() < 1e-3);
+    }
+}
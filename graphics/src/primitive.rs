@@ -1,11 +1,34 @@
+//! Describe what to draw with a tree of primitives.
+//!
+//! Primitive trees can be arbitrarily deep, for instance when widgets are
+//! deeply nested. Every traversal of a tree uses an explicit stack instead of
+//! recursion, so deep trees cannot overflow the call stack. This includes
+//! processing a tree into layers, as well as hashing, bounding, formatting,
+//! visiting, and dropping it.
+mod hash;
+mod visitor;
+
+pub use visitor::PrimitiveVisitor;
+
 use iced_native::image;
 use iced_native::svg;
-use iced_native::{Background, Color, Font, Rectangle, Size, Vector};
+use iced_native::{Color, Font, Point, Rectangle, Size, Vector};
 
 use crate::alignment;
+use crate::border::{BorderAlignment, BorderColor, BorderWidth};
+use crate::clip_mode::ClipMode;
 use crate::gradient::Gradient;
+use crate::image::Filter;
+use crate::line_height::LineHeight;
+use crate::paint::{ImageFit, Paint};
+use crate::path::{FillRule, LineCap, LineJoin, Path};
+use crate::quad_instance::QuadInstance;
+use crate::text_shadow::TextShadow;
+use crate::transformation::Transformation;
 use crate::triangle;
+use crate::truncate::Truncate;
 
+use std::hash::{Hash, Hasher as _};
 use std::sync::Arc;
 
 /// A rendering primitive.
@@ -28,25 +51,116 @@ pub enum Primitive {
         color: Color,
         /// The size of the text
         size: f32,
+        /// The extra spacing between letters of the text
+        letter_spacing: f32,
+        /// The height of the lines of the text
+        line_height: LineHeight,
         /// The font of the text
         font: Font,
         /// The horizontal alignment of the text
         horizontal_alignment: alignment::Horizontal,
         /// The vertical alignment of the text
         vertical_alignment: alignment::Vertical,
+        /// The way the text is shortened when it overflows the width of its
+        /// bounds
+        ///
+        /// It only has an effect when the width of the bounds is finite.
+        truncate: Truncate,
+        /// The maximum amount of lines of the text, if any
+        ///
+        /// Lines past the limit are not drawn. If the text is truncated, the
+        /// last visible line ends with an ellipsis.
+        max_lines: Option<usize>,
+        /// The color of the highlight drawn behind the glyphs of the text,
+        /// if any
+        background: Option<Color>,
+        /// The drop shadow drawn behind the glyphs of the text, if any
+        shadow: Option<TextShadow>,
+        /// Whether to snap the top of the text to the pixel grid of the device
+        ///
+        /// This keeps the baselines of body text crisp on low DPI screens.
+        snap_baselines: bool,
     },
     /// A quad primitive
     Quad {
         /// The bounds of the quad
         bounds: Rectangle,
         /// The background of the quad
-        background: Background,
+        background: Paint,
         /// The border radius of the quad
         border_radius: [f32; 4],
-        /// The border width of the quad
-        border_width: f32,
+        /// The border width of each side of the quad
+        border_width: BorderWidth,
         /// The border color of the quad
-        border_color: Color,
+        border_color: BorderColor,
+        /// The position of the border of the quad relative to its bounds
+        border_alignment: BorderAlignment,
+        /// The smoothing of the corners of the quad, from `0.0` for circular
+        /// corners to `1.0` for a full superellipse, or "squircle"
+        ///
+        /// Renderers that cannot draw superellipses fall back to circular
+        /// corners.
+        corner_smoothing: f32,
+    },
+    /// A batch of quads without borders, which only differ in their bounds
+    /// and color
+    ///
+    /// Renderers can draw a whole batch with a single instanced draw call,
+    /// which is a lot cheaper than drawing many quads of a large grid one by
+    /// one.
+    QuadBatch {
+        /// The quads of the batch
+        quads: Vec<QuadInstance>,
+    },
+    /// A drop shadow primitive
+    ///
+    /// The shadow is cast outside of its bounds.
+    Shadow {
+        /// The bounds of the element casting the shadow
+        bounds: Rectangle,
+        /// The color of the shadow
+        color: Color,
+        /// The border radius of the element casting the shadow
+        border_radius: [f32; 4],
+        /// The offset of the shadow
+        offset: Vector,
+        /// The blur radius of the shadow, which controls how far its edges
+        /// fade out
+        blur_radius: f32,
+        /// The spread radius of the shadow, which expands its bounds before
+        /// blurring
+        spread_radius: f32,
+    },
+    /// An inner shadow primitive
+    ///
+    /// The shadow is cast inside of its bounds and clipped to them.
+    InnerShadow {
+        /// The bounds of the element casting the shadow
+        bounds: Rectangle,
+        /// The color of the shadow
+        color: Color,
+        /// The blur radius of the shadow
+        blur_radius: f32,
+        /// The offset of the shadow
+        offset: Vector,
+        /// The border radius of the element casting the shadow
+        border_radius: [f32; 4],
+    },
+    /// A soft colored halo hugging the edges of some bounds
+    ///
+    /// Unlike a shadow, the bounds themselves are not filled. This can be
+    /// useful to draw focus rings.
+    Glow {
+        /// The bounds hugged by the glow
+        bounds: Rectangle,
+        /// The color of the glow
+        color: Color,
+        /// How far the glow fades out from the edges of its bounds
+        radius: f32,
+        /// Whether the glow is drawn inside of its bounds instead of outside
+        inset: bool,
+        /// The border radius of the bounds
+        border_radius: [f32; 4],
     },
     /// An image primitive
     Image {
@@ -54,6 +168,35 @@ pub enum Primitive {
         handle: image::Handle,
         /// The bounds of the image
         bounds: Rectangle,
+        /// The region of the image to draw, in source pixels
+        ///
+        /// If `None`, the whole image is drawn.
+        source: Option<Rectangle>,
+        /// The tint color of the image, multiplied with each of its texels
+        tint: Color,
+        /// The opacity of the image, in `[0, 1]`
+        opacity: f32,
+        /// The rotation of the image around the center of its bounds, in
+        /// radians
+        rotation: f32,
+        /// The [`Filter`] used to sample the image when it is scaled
+        filter: Filter,
+        /// How the image fills its bounds
+        fit: ImageFit,
+    },
+    /// A nine-patch image primitive
+    ///
+    /// The image is split into nine regions by its insets. The corners keep
+    /// their size, while the middle row and column are stretched to fill the
+    /// bounds.
+    NinePatch {
+        /// The handle of the image
+        handle: image::Handle,
+        /// The bounds of the image
+        bounds: Rectangle,
+        /// The margins of the image that are not stretched, in source pixels,
+        /// as `[top, right, bottom, left]`
+        insets: [f32; 4],
     },
     /// An SVG primitive
     Svg {
@@ -62,11 +205,38 @@ pub enum Primitive {
 
         /// The bounds of the viewport
         bounds: Rectangle,
+
+        /// The opacity of the SVG, in `[0, 1]`
+        opacity: f32,
+
+        /// The color that replaces every fill and stroke color of the SVG
+        ///
+        /// If `None`, the SVG keeps its own colors.
+        color: Option<Color>,
+
+        /// The rotation of the SVG around the center of its bounds, in radians
+        rotation: f32,
     },
     /// A clip primitive
     Clip {
         /// The bounds of the clip
         bounds: Rectangle,
+        /// Whether the edges of the clip should be anti-aliased
+        ///
+        /// Backends feather the edges by a pixel, which avoids jagged edges
+        /// when the bounds fall on fractional pixels.
+        antialias: bool,
+        /// The way the clip combines with the clipping bounds of its parent
+        mode: ClipMode,
+        /// The content of the clip
+        content: Box<Primitive>,
+    },
+    /// A clip primitive with rounded corners
+    RoundedClip {
+        /// The bounds of the clip
+        bounds: Rectangle,
+        /// The border radius of the clip
+        border_radius: [f32; 4],
         /// The content of the clip
         content: Box<Primitive>,
     },
@@ -78,9 +248,107 @@ pub enum Primitive {
         /// The primitive to translate
         content: Box<Primitive>,
     },
+    /// A primitive that applies a translation along the X, Y, and Z axes
+    ///
+    /// The Z component can be used by renderers with a depth buffer to sort
+    /// overlapping content. Otherwise, it is ignored.
+    Translate3D {
+        /// The translation along the X, Y, and Z axes
+        translation: [f32; 3],
+
+        /// The primitive to translate
+        content: Box<Primitive>,
+    },
+    /// A primitive that applies a uniform scale
+    Scale {
+        /// The scale factor
+        scale: f32,
+
+        /// The primitive to scale
+        content: Box<Primitive>,
+    },
+    /// A primitive that clips its content to bounds given as fractions of
+    /// the viewport
+    ///
+    /// The content is positioned relative to the origin of the resolved
+    /// bounds. This can be useful for overlays sized after the window.
+    ViewportRelative {
+        /// The bounds of the primitive, as fractions (`0.0..=1.0`) of the
+        /// logical size of the viewport
+        fractional_bounds: Rectangle,
+
+        /// The content of the primitive
+        content: Box<Primitive>,
+    },
+    /// A primitive that tints its content with a translucent color
+    ///
+    /// The color is composited once over everything drawn up to the end of
+    /// the content, within the clipping bounds of its layer. The primitives
+    /// that follow are drawn over the tint. This can be useful to dim the
+    /// content behind a modal.
+    Tint {
+        /// The color composited over the content
+        ///
+        /// A fully transparent color leaves the content untouched.
+        color: Color,
+
+        /// The primitive to tint
+        content: Box<Primitive>,
+    },
+    /// A selection marquee primitive
+    ///
+    /// It is drawn as a translucent fill with a 1 physical pixel border,
+    /// snapped to the pixel grid of the device so it stays crisp while
+    /// dragging.
+    Marquee {
+        /// The bounds of the marquee
+        bounds: Rectangle,
+        /// The fill color of the marquee
+        fill: Color,
+        /// The border color of the marquee
+        border: Color,
+    },
+    /// A text caret primitive
+    ///
+    /// It is drawn as a quad 1 logical pixel wide that fades in and out
+    /// following its blink phase.
+    Caret {
+        /// The position of the top of the caret
+        position: Point,
+        /// The height of the caret
+        height: f32,
+        /// The color of the caret
+        color: Color,
+        /// The phase of the blink cycle of the caret, in `[0, 1)`
+        ///
+        /// The caret is fully opaque at `0.0` and fully transparent at `0.5`.
+        blink_phase: f32,
+    },
+    /// A stroked circular arc primitive
+    ///
+    /// The arc sweeps clockwise from its start angle, covering the given
+    /// fraction of a full turn. This makes a determinate progress indicator a
+    /// single primitive.
+    Arc {
+        /// The center of the arc
+        center: Point,
+        /// The radius of the arc
+        radius: f32,
+        /// The stroke width of the arc
+        width: f32,
+        /// The angle where the arc starts, in radians
+        start_angle: f32,
+        /// The fraction of a full turn swept by the arc, in `[0, 1]`
+        progress: f32,
+        /// The color of the arc
+        color: Color,
+    },
     /// A low-level primitive to render a mesh of triangles with a solid color.
     ///
     /// It can be used to render many kinds of geometry freely.
+    ///
+    /// The color of each vertex is interpolated across its triangles, which
+    /// can be used to draw smooth vertex-color gradients.
     SolidMesh {
         /// The vertices and indices of the mesh.
         buffers: triangle::Mesh2D<triangle::ColoredVertex2D>,
@@ -105,18 +373,727 @@ pub enum Primitive {
         /// The [`Gradient`] to apply to the mesh.
         gradient: Gradient,
     },
+    /// A filled path primitive
+    ///
+    /// The path is tessellated into a mesh of triangles during layer
+    /// generation.
+    Fill {
+        /// The path to fill
+        path: Path,
+        /// The fill rule of the path
+        fill_rule: FillRule,
+        /// The color of the fill
+        color: Color,
+    },
+    /// A stroked path primitive
+    ///
+    /// The outline of the stroke is tessellated into a mesh of triangles
+    /// during layer generation.
+    Stroke {
+        /// The path to stroke
+        path: Path,
+        /// The width of the stroke
+        width: f32,
+        /// The color of the stroke
+        color: Color,
+        /// The shape used at the ends of the open contours of the path
+        line_cap: LineCap,
+        /// The shape used at the corners of the path
+        line_join: LineJoin,
+    },
+    /// A low-level primitive to render a mesh of triangles in 3D space.
+    ///
+    /// The vertices are colored like the ones of a [`Primitive::SolidMesh`].
+    /// Renderers draw its triangles from back to front, without a depth
+    /// buffer.
+    Mesh3D {
+        /// The vertices and indices of the mesh.
+        buffers: triangle::Mesh3D<triangle::ColoredVertex3D>,
+
+        /// The [`Transformation`] projecting the vertices of the mesh into
+        /// logical coordinates, with the Z axis used as depth.
+        transformation: Transformation,
+    },
     /// A cached primitive.
     ///
     /// This can be useful if you are implementing a widget where primitive
     /// generation is expensive.
     Cached {
+        /// The key identifying the contents of the cache
+        ///
+        /// It must change whenever the cached primitive does, which lets a
+        /// renderer reuse any work done for it in previous frames. The
+        /// [`Primitive::content_hash`] of the cached primitive is a good key.
+        key: u64,
+
         /// The cached primitive
         cache: Arc<Primitive>,
     },
 }
 
+impl Primitive {
+    /// Computes a hash of the contents of the [`Primitive`] tree.
+    ///
+    /// Floats are quantized to a fixed precision before being hashed, so tiny
+    /// rounding differences do not change the hash. Images are hashed by the
+    /// id of their handle, and cached primitives by their key.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = iced_native::Hasher::default();
+
+        self.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Computes the bounding box of the [`Primitive`] tree after applying the
+    /// given [`Transformation`].
+    ///
+    /// Clips limit the bounds of their content, and infinite text bounds are
+    /// excluded from the union along their infinite axis.
+    ///
+    /// Viewport-relative primitives are excluded, since their bounds depend
+    /// on the viewport.
+    ///
+    /// Returns `None` if the tree has nothing to draw.
+    pub fn bounds(&self, transformation: Transformation) -> Option<Rectangle> {
+        enum Step<'a> {
+            Visit(&'a Primitive, Transformation),
+            /// Unites the bounds of the given amount of children.
+            Union(usize),
+            /// Intersects the bounds of some content with its clip bounds.
+            Clip(Rectangle),
+        }
+
+        let mut stack = vec![Step::Visit(self, transformation)];
+        let mut results: Vec<Option<Rectangle>> = Vec::new();
+
+        while let Some(step) = stack.pop() {
+            let (primitive, transformation) = match step {
+                Step::Visit(primitive, transformation) => {
+                    (primitive, transformation)
+                }
+                Step::Union(children) => {
+                    let bounds = results
+                        .drain(results.len() - children..)
+                        .flatten()
+                        .reduce(union);
+
+                    results.push(bounds);
+                    continue;
+                }
+                Step::Clip(bounds) => {
+                    let content = results.pop().flatten();
+
+                    results.push(
+                        content
+                            .and_then(|content| content.intersection(&bounds)),
+                    );
+                    continue;
+                }
+            };
+
+            match primitive {
+                Primitive::Group { primitives } => {
+                    stack.push(Step::Union(primitives.len()));
+                    stack.extend(primitives.iter().rev().map(|primitive| {
+                        Step::Visit(primitive, transformation)
+                    }));
+                }
+                Primitive::Clip {
+                    bounds, content, ..
+                }
+                | Primitive::RoundedClip {
+                    bounds, content, ..
+                } => {
+                    stack.push(Step::Clip(
+                        transformation.transform_rectangle(*bounds),
+                    ));
+                    stack.push(Step::Visit(content, transformation));
+                }
+                Primitive::Translate {
+                    translation,
+                    content,
+                } => stack.push(Step::Visit(
+                    content,
+                    transformation
+                        * Transformation::translate(
+                            translation.x,
+                            translation.y,
+                        ),
+                )),
+                Primitive::Translate3D {
+                    translation: [x, y, z],
+                    content,
+                } => stack.push(Step::Visit(
+                    content,
+                    transformation.translated_3d(*x, *y, *z),
+                )),
+                Primitive::Scale { scale, content } => stack.push(Step::Visit(
+                    content,
+                    transformation * Transformation::scale(*scale, *scale),
+                )),
+                Primitive::Tint { content, .. } => {
+                    stack.push(Step::Visit(content, transformation))
+                }
+                Primitive::Cached { cache, .. } => {
+                    stack.push(Step::Visit(cache, transformation))
+                }
+                _ => results.push(primitive.leaf_bounds(transformation)),
+            }
+        }
+
+        results.pop().flatten()
+    }
+
+    /// Computes the bounds of a [`Primitive`] without children.
+    fn leaf_bounds(&self, transformation: Transformation) -> Option<Rectangle> {
+        match self {
+            Primitive::None | Primitive::ViewportRelative { .. } => None,
+            Primitive::Text {
+                bounds,
+                horizontal_alignment,
+                vertical_alignment,
+                ..
+            } => {
+                let (x, width) = if bounds.width.is_infinite() {
+                    (bounds.x, 0.0)
+                } else {
+                    match horizontal_alignment {
+                        alignment::Horizontal::Left => (bounds.x, bounds.width),
+                        alignment::Horizontal::Center => {
+                            (bounds.x - bounds.width / 2.0, bounds.width)
+                        }
+                        alignment::Horizontal::Right => {
+                            (bounds.x - bounds.width, bounds.width)
+                        }
+                    }
+                };
+
+                let (y, height) = if bounds.height.is_infinite() {
+                    (bounds.y, 0.0)
+                } else {
+                    match vertical_alignment {
+                        alignment::Vertical::Top => (bounds.y, bounds.height),
+                        alignment::Vertical::Center => {
+                            (bounds.y - bounds.height / 2.0, bounds.height)
+                        }
+                        alignment::Vertical::Bottom => {
+                            (bounds.y - bounds.height, bounds.height)
+                        }
+                    }
+                };
+
+                Some(transformation.transform_rectangle(Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                }))
+            }
+            Primitive::Quad {
+                bounds,
+                border_width,
+                border_alignment,
+                ..
+            } => {
+                let [top, right, bottom, left] =
+                    border_width.0.map(|width| border_alignment.outset(width));
+
+                Some(transformation.transform_rectangle(Rectangle {
+                    x: bounds.x - left,
+                    y: bounds.y - top,
+                    width: bounds.width + left + right,
+                    height: bounds.height + top + bottom,
+                }))
+            }
+            Primitive::QuadBatch { quads } => quads
+                .iter()
+                .map(|quad| quad.bounds)
+                .reduce(union)
+                .map(|bounds| transformation.transform_rectangle(bounds)),
+            Primitive::InnerShadow { bounds, .. }
+            | Primitive::Image { bounds, .. }
+            | Primitive::NinePatch { bounds, .. }
+            | Primitive::Svg { bounds, .. }
+            | Primitive::Marquee { bounds, .. } => {
+                Some(transformation.transform_rectangle(*bounds))
+            }
+            Primitive::Shadow {
+                bounds,
+                offset,
+                blur_radius,
+                spread_radius,
+                ..
+            } => {
+                let extent = blur_radius + spread_radius;
+
+                Some(transformation.transform_rectangle(Rectangle {
+                    x: bounds.x + offset.x - extent,
+                    y: bounds.y + offset.y - extent,
+                    width: bounds.width + extent * 2.0,
+                    height: bounds.height + extent * 2.0,
+                }))
+            }
+            Primitive::Glow {
+                bounds,
+                radius,
+                inset,
+                ..
+            } => {
+                let extent = if *inset { 0.0 } else { *radius };
+
+                Some(transformation.transform_rectangle(Rectangle {
+                    x: bounds.x - extent,
+                    y: bounds.y - extent,
+                    width: bounds.width + extent * 2.0,
+                    height: bounds.height + extent * 2.0,
+                }))
+            }
+            Primitive::Caret {
+                position, height, ..
+            } => Some(transformation.transform_rectangle(Rectangle::new(
+                *position,
+                Size::new(1.0, *height),
+            ))),
+            Primitive::Arc {
+                center,
+                radius,
+                width,
+                ..
+            } => {
+                let extent = radius + width / 2.0;
+
+                Some(transformation.transform_rectangle(Rectangle {
+                    x: center.x - extent,
+                    y: center.y - extent,
+                    width: extent * 2.0,
+                    height: extent * 2.0,
+                }))
+            }
+            Primitive::SolidMesh { size, .. }
+            | Primitive::GradientMesh { size, .. } => Some(
+                transformation.transform_rectangle(Rectangle::with_size(*size)),
+            ),
+            Primitive::Fill { path, .. } => path
+                .contours()
+                .into_iter()
+                .flatten()
+                .map(|point| {
+                    Rectangle::new(
+                        transformation.transform_point(point),
+                        Size::ZERO,
+                    )
+                })
+                .reduce(union),
+            Primitive::Stroke { path, width, .. } => path
+                .contours()
+                .into_iter()
+                .flatten()
+                .map(|point| {
+                    let extent = width / 2.0;
+
+                    Rectangle {
+                        x: point.x - extent,
+                        y: point.y - extent,
+                        width: extent * 2.0,
+                        height: extent * 2.0,
+                    }
+                })
+                .reduce(union)
+                .map(|bounds| transformation.transform_rectangle(bounds)),
+            Primitive::Mesh3D {
+                buffers,
+                transformation: projection,
+            } => {
+                let projection = transformation * *projection;
+
+                buffers
+                    .vertices
+                    .iter()
+                    .map(|vertex| {
+                        Rectangle::new(
+                            projection.project_point(vertex.position),
+                            Size::ZERO,
+                        )
+                    })
+                    .reduce(union)
+            }
+            // Primitives with children are handled by `Primitive::bounds`
+            Primitive::Group { .. }
+            | Primitive::Clip { .. }
+            | Primitive::RoundedClip { .. }
+            | Primitive::Translate { .. }
+            | Primitive::Translate3D { .. }
+            | Primitive::Scale { .. }
+            | Primitive::Tint { .. }
+            | Primitive::Cached { .. } => None,
+        }
+    }
+
+    /// Formats the [`Primitive`] tree in a readable, indented way.
+    ///
+    /// Large payloads, like mesh buffers or long text, are summarized. This
+    /// can be useful to debug the output of a layout.
+    pub fn tree_string(&self) -> String {
+        let mut output = String::new();
+        let mut stack = vec![(self, 0)];
+
+        while let Some((primitive, depth)) = stack.pop() {
+            output.push_str(&"  ".repeat(depth));
+            output.push_str(&primitive.tree_line());
+            output.push('\n');
+
+            match primitive {
+                Primitive::Group { primitives } => {
+                    stack.extend(
+                        primitives
+                            .iter()
+                            .rev()
+                            .map(|primitive| (primitive, depth + 1)),
+                    );
+                }
+                Primitive::Clip { content, .. }
+                | Primitive::RoundedClip { content, .. }
+                | Primitive::Translate { content, .. }
+                | Primitive::Translate3D { content, .. }
+                | Primitive::Scale { content, .. }
+                | Primitive::Tint { content, .. }
+                | Primitive::ViewportRelative { content, .. } => {
+                    stack.push((content.as_ref(), depth + 1));
+                }
+                Primitive::Cached { cache, .. } => {
+                    stack.push((cache.as_ref(), depth + 1));
+                }
+                _ => {}
+            }
+        }
+
+        output
+    }
+
+    /// Returns the line describing the [`Primitive`] itself in its
+    /// [`Primitive::tree_string`].
+    fn tree_line(&self) -> String {
+        const MAX_TEXT_LENGTH: usize = 16;
+
+        match self {
+            Primitive::None => String::from("None"),
+            Primitive::Group { primitives } => {
+                format!("Group({} children)", primitives.len())
+            }
+            Primitive::Text {
+                content, bounds, ..
+            } => {
+                let summary = if content.chars().count() > MAX_TEXT_LENGTH {
+                    format!(
+                        "{}...",
+                        content
+                            .chars()
+                            .take(MAX_TEXT_LENGTH)
+                            .collect::<String>()
+                    )
+                } else {
+                    content.clone()
+                };
+
+                format!("Text({:?}) {}", summary, format_bounds(bounds))
+            }
+            Primitive::Quad { bounds, .. } => {
+                format!("Quad {}", format_bounds(bounds))
+            }
+            Primitive::QuadBatch { quads } => {
+                format!("QuadBatch({} quads)", quads.len())
+            }
+            Primitive::Shadow { bounds, .. } => {
+                format!("Shadow {}", format_bounds(bounds))
+            }
+            Primitive::InnerShadow { bounds, .. } => {
+                format!("InnerShadow {}", format_bounds(bounds))
+            }
+            Primitive::Glow { bounds, inset, .. } => format!(
+                "Glow {}{}",
+                format_bounds(bounds),
+                if *inset { " inset" } else { "" }
+            ),
+            Primitive::Image { bounds, .. } => {
+                format!("Image {}", format_bounds(bounds))
+            }
+            Primitive::NinePatch { bounds, insets, .. } => {
+                format!(
+                    "NinePatch {} insets {:?}",
+                    format_bounds(bounds),
+                    insets
+                )
+            }
+            Primitive::Svg { bounds, .. } => {
+                format!("Svg {}", format_bounds(bounds))
+            }
+            Primitive::Clip { bounds, .. } => {
+                format!("Clip {}", format_bounds(bounds))
+            }
+            Primitive::RoundedClip {
+                bounds,
+                border_radius,
+                ..
+            } => {
+                format!(
+                    "RoundedClip {} radius {:?}",
+                    format_bounds(bounds),
+                    border_radius
+                )
+            }
+            Primitive::Translate { translation, .. } => {
+                format!("Translate({}, {})", translation.x, translation.y)
+            }
+            Primitive::Translate3D {
+                translation: [x, y, z],
+                ..
+            } => format!("Translate3D({}, {}, {})", x, y, z),
+            Primitive::Scale { scale, .. } => format!("Scale({})", scale),
+            Primitive::ViewportRelative {
+                fractional_bounds, ..
+            } => {
+                format!("ViewportRelative {}", format_bounds(fractional_bounds))
+            }
+            Primitive::Tint { color, .. } => format!(
+                "Tint({}, {}, {}, {})",
+                color.r, color.g, color.b, color.a
+            ),
+            Primitive::Marquee { bounds, .. } => {
+                format!("Marquee {}", format_bounds(bounds))
+            }
+            Primitive::Caret {
+                position, height, ..
+            } => {
+                format!(
+                    "Caret({}, {}) height {}",
+                    position.x, position.y, height
+                )
+            }
+            Primitive::Arc {
+                center,
+                radius,
+                progress,
+                ..
+            } => {
+                format!(
+                    "Arc({}, {}) radius {} progress {}",
+                    center.x, center.y, radius, progress
+                )
+            }
+            Primitive::SolidMesh { buffers, .. } => {
+                format!("SolidMesh({} verts)", buffers.vertices.len())
+            }
+            Primitive::GradientMesh { buffers, .. } => {
+                format!("GradientMesh({} verts)", buffers.vertices.len())
+            }
+            Primitive::Fill { path, .. } => {
+                format!("Fill({} segments)", path.segments().len())
+            }
+            Primitive::Stroke { path, width, .. } => {
+                format!(
+                    "Stroke({} segments) width {}",
+                    path.segments().len(),
+                    width
+                )
+            }
+            Primitive::Mesh3D { buffers, .. } => {
+                format!("Mesh3D({} verts)", buffers.vertices.len())
+            }
+            Primitive::Cached { key, .. } => format!("Cached({})", key),
+        }
+    }
+}
+
+fn union(a: Rectangle, b: Rectangle) -> Rectangle {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+
+    Rectangle {
+        x,
+        y,
+        width: (a.x + a.width).max(b.x + b.width) - x,
+        height: (a.y + a.height).max(b.y + b.height) - y,
+    }
+}
+
+fn format_bounds(bounds: &Rectangle) -> String {
+    format!(
+        "[{}, {}, {}x{}]",
+        bounds.x, bounds.y, bounds.width, bounds.height
+    )
+}
+
 impl Default for Primitive {
     fn default() -> Primitive {
         Primitive::None
     }
 }
+
+impl Drop for Primitive {
+    /// Drops the children of the [`Primitive`] with an explicit stack.
+    fn drop(&mut self) {
+        fn take_children(
+            primitive: &mut Primitive,
+            stack: &mut Vec<Primitive>,
+        ) {
+            match primitive {
+                Primitive::Group { primitives } => stack.append(primitives),
+                Primitive::Clip { content, .. }
+                | Primitive::RoundedClip { content, .. }
+                | Primitive::Translate { content, .. }
+                | Primitive::Translate3D { content, .. }
+                | Primitive::Scale { content, .. }
+                | Primitive::ViewportRelative { content, .. }
+                | Primitive::Tint { content, .. }
+                    // Taken content is left as `None`
+                    if !matches!(content.as_ref(), Primitive::None) =>
+                {
+                    stack.push(std::mem::take(content.as_mut()));
+                }
+                // Shared caches are dropped by their last owner
+                Primitive::Cached { cache, .. } => {
+                    if let Some(cache) = Arc::get_mut(cache) {
+                        stack.push(std::mem::take(cache));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut stack = Vec::new();
+
+        take_children(self, &mut stack);
+
+        // Every child is dropped without children of its own
+        while let Some(mut primitive) = stack.pop() {
+            take_children(&mut primitive, &mut stack);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Background;
+
+    #[test]
+    fn tree_string_is_indented() {
+        let bounds = Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 10.0,
+            height: 20.0,
+        };
+
+        let primitive = Primitive::Group {
+            primitives: vec![
+                Primitive::Clip {
+                    bounds,
+                    antialias: false,
+                    mode: ClipMode::Intersect,
+                    content: Box::new(Primitive::Quad {
+                        bounds,
+                        background: Background::Color(Color::BLACK).into(),
+                        border_radius: [0.0; 4],
+                        border_width: 0.0.into(),
+                        border_color: BorderColor::default(),
+                        border_alignment: BorderAlignment::Inside,
+                        corner_smoothing: 0.0,
+                    }),
+                },
+                Primitive::Text {
+                    content: String::from("Hello, this is a long text"),
+                    bounds,
+                    color: Color::BLACK,
+                    size: 20.0,
+                    letter_spacing: 0.0,
+                    line_height: LineHeight::default(),
+                    font: Font::Default,
+                    horizontal_alignment: alignment::Horizontal::Left,
+                    vertical_alignment: alignment::Vertical::Top,
+                    truncate: Truncate::None,
+                    max_lines: None,
+                    background: None,
+                    shadow: None,
+                    snap_baselines: false,
+                },
+            ],
+        };
+
+        assert_eq!(
+            primitive.tree_string(),
+            "Group(2 children)\n  \
+             Clip [0, 0, 10x20]\n    \
+             Quad [0, 0, 10x20]\n  \
+             Text(\"Hello, this is a...\") [0, 0, 10x20]\n"
+        );
+    }
+
+    fn quad(x: f32, y: f32) -> Primitive {
+        Primitive::Quad {
+            bounds: Rectangle::new(Point::new(x, y), Size::new(10.0, 10.0)),
+            background: Background::Color(Color::BLACK).into(),
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        }
+    }
+
+    #[test]
+    fn bounds_union_offset_quads() {
+        let group = Primitive::Group {
+            primitives: vec![quad(0.0, 0.0), quad(30.0, 20.0), Primitive::None],
+        };
+
+        assert_eq!(
+            group.bounds(Transformation::identity()),
+            Some(Rectangle::new(Point::ORIGIN, Size::new(40.0, 30.0)))
+        );
+        assert_eq!(
+            group.bounds(Transformation::scale(2.0, 2.0)),
+            Some(Rectangle::new(Point::ORIGIN, Size::new(80.0, 60.0)))
+        );
+        assert_eq!(
+            Primitive::Group { primitives: vec![] }
+                .bounds(Transformation::identity()),
+            None
+        );
+    }
+
+    #[test]
+    fn bounds_are_limited_by_clips() {
+        let clipped = Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Clip {
+                bounds: Rectangle::new(Point::ORIGIN, Size::new(15.0, 15.0)),
+                antialias: false,
+                mode: ClipMode::Intersect,
+                content: Box::new(Primitive::Group {
+                    primitives: vec![quad(0.0, 0.0), quad(30.0, 20.0)],
+                }),
+            }),
+        };
+
+        assert_eq!(
+            clipped.bounds(Transformation::identity()),
+            Some(Rectangle::new(Point::new(5.0, 5.0), Size::new(15.0, 15.0)))
+        );
+    }
+
+    #[test]
+    fn content_hash_follows_contents() {
+        let tree = |x| Primitive::Translate {
+            translation: Vector::new(5.0, 5.0),
+            content: Box::new(Primitive::Group {
+                primitives: vec![quad(x, 0.0), quad(30.0, 20.0)],
+            }),
+        };
+
+        assert_eq!(tree(0.0).content_hash(), tree(0.0).content_hash());
+        assert_ne!(tree(0.0).content_hash(), tree(1.0).content_hash());
+        assert_eq!(tree(0.0).content_hash(), tree(1.0e-5).content_hash());
+    }
+}
@@ -0,0 +1,495 @@
+use crate::gradient::Gradient;
+use crate::path::Segment;
+use crate::primitive::Primitive;
+use crate::{
+    Background, BorderColor, Color, Font, LineHeight, Paint, Point, Rectangle,
+    Vector,
+};
+
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+/// The amount of steps per unit that floats are quantized to before being
+/// hashed.
+const QUANTIZATION: f32 = 1024.0;
+
+impl Hash for Primitive {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // The tree is hashed in pre-order
+        let mut stack = Vec::new();
+        let mut primitive = self;
+
+        loop {
+            node(primitive, state, &mut stack);
+
+            match stack.pop() {
+                Some(next) => primitive = next,
+                None => break,
+            }
+        }
+    }
+}
+
+/// Hashes a single [`Primitive`], pushing its children to the given stack in
+/// reverse order.
+fn node<'a, H: Hasher>(
+    primitive: &'a Primitive,
+    state: &mut H,
+    stack: &mut Vec<&'a Primitive>,
+) {
+    mem::discriminant(primitive).hash(state);
+
+    match primitive {
+        Primitive::None => {}
+        Primitive::Group { primitives } => {
+            primitives.len().hash(state);
+            stack.extend(primitives.iter().rev());
+        }
+        Primitive::Text {
+            content,
+            bounds,
+            color,
+            size,
+            letter_spacing,
+            line_height,
+            font,
+            horizontal_alignment,
+            vertical_alignment,
+            truncate,
+            max_lines,
+            background,
+            shadow,
+            snap_baselines,
+        } => {
+            content.hash(state);
+            rectangle(state, bounds);
+            colors(state, &[*color]);
+            floats(state, &[*size, *letter_spacing]);
+
+            match line_height {
+                LineHeight::Relative(factor) => {
+                    false.hash(state);
+                    floats(state, &[*factor]);
+                }
+                LineHeight::Absolute(height) => {
+                    true.hash(state);
+                    floats(state, &[*height]);
+                }
+            }
+
+            match font {
+                Font::Default => 0.hash(state),
+                Font::External { name, .. } => name.hash(state),
+            }
+
+            horizontal_alignment.hash(state);
+            vertical_alignment.hash(state);
+            truncate.hash(state);
+            max_lines.hash(state);
+            background.is_some().hash(state);
+
+            if let Some(background) = background {
+                colors(state, &[*background]);
+            }
+
+            shadow.is_some().hash(state);
+
+            if let Some(shadow) = shadow {
+                colors(state, &[shadow.color]);
+                vector(state, shadow.offset);
+                floats(state, &[shadow.blur]);
+            }
+
+            snap_baselines.hash(state);
+        }
+        Primitive::QuadBatch { quads } => {
+            quads.len().hash(state);
+
+            for quad in quads {
+                rectangle(state, &quad.bounds);
+                colors(state, &[quad.color]);
+            }
+        }
+        Primitive::Quad {
+            bounds,
+            background,
+            border_radius,
+            border_width,
+            border_color,
+            border_alignment,
+            corner_smoothing,
+        } => {
+            rectangle(state, bounds);
+            paint(state, background);
+            floats(state, border_radius);
+            floats(state, &border_width.0);
+            mem::discriminant(border_color).hash(state);
+
+            match border_color {
+                BorderColor::Solid(color) => colors(state, &[*color]),
+                BorderColor::Gradient {
+                    start,
+                    end,
+                    start_color,
+                    end_color,
+                } => {
+                    points(state, &[*start, *end]);
+                    colors(state, &[*start_color, *end_color]);
+                }
+            }
+
+            border_alignment.hash(state);
+            floats(state, &[*corner_smoothing]);
+        }
+        Primitive::Shadow {
+            bounds,
+            color,
+            border_radius,
+            offset,
+            blur_radius,
+            spread_radius,
+        } => {
+            rectangle(state, bounds);
+            colors(state, &[*color]);
+            floats(state, border_radius);
+            vector(state, *offset);
+            floats(state, &[*blur_radius, *spread_radius]);
+        }
+        Primitive::InnerShadow {
+            bounds,
+            color,
+            blur_radius,
+            offset,
+            border_radius,
+        } => {
+            rectangle(state, bounds);
+            colors(state, &[*color]);
+            floats(state, &[*blur_radius]);
+            vector(state, *offset);
+            floats(state, border_radius);
+        }
+        Primitive::Glow {
+            bounds,
+            color,
+            radius,
+            inset,
+            border_radius,
+        } => {
+            rectangle(state, bounds);
+            colors(state, &[*color]);
+            floats(state, &[*radius]);
+            inset.hash(state);
+            floats(state, border_radius);
+        }
+        Primitive::Image {
+            handle,
+            bounds,
+            source,
+            tint,
+            opacity,
+            rotation,
+            filter,
+            fit,
+        } => {
+            handle.id().hash(state);
+            rectangle(state, bounds);
+            source.is_some().hash(state);
+
+            if let Some(source) = source {
+                rectangle(state, source);
+            }
+
+            colors(state, &[*tint]);
+            floats(state, &[*opacity, *rotation]);
+            filter.hash(state);
+            mem::discriminant(fit).hash(state);
+        }
+        Primitive::NinePatch {
+            handle,
+            bounds,
+            insets,
+        } => {
+            handle.id().hash(state);
+            rectangle(state, bounds);
+            floats(state, insets);
+        }
+        Primitive::Svg {
+            handle,
+            bounds,
+            opacity,
+            color,
+            rotation,
+        } => {
+            handle.id().hash(state);
+            rectangle(state, bounds);
+            floats(state, &[*opacity, *rotation]);
+            color.is_some().hash(state);
+
+            if let Some(color) = color {
+                colors(state, &[*color]);
+            }
+        }
+        Primitive::Clip {
+            bounds,
+            antialias,
+            mode,
+            content,
+        } => {
+            rectangle(state, bounds);
+            antialias.hash(state);
+            mode.hash(state);
+            stack.push(content);
+        }
+        Primitive::RoundedClip {
+            bounds,
+            border_radius,
+            content,
+        } => {
+            rectangle(state, bounds);
+            floats(state, border_radius);
+            stack.push(content);
+        }
+        Primitive::Translate {
+            translation,
+            content,
+        } => {
+            vector(state, *translation);
+            stack.push(content);
+        }
+        Primitive::Translate3D {
+            translation,
+            content,
+        } => {
+            floats(state, translation);
+            stack.push(content);
+        }
+        Primitive::Scale { scale, content } => {
+            floats(state, &[*scale]);
+            stack.push(content);
+        }
+        Primitive::Tint { color, content } => {
+            colors(state, &[*color]);
+            stack.push(content);
+        }
+        Primitive::ViewportRelative {
+            fractional_bounds,
+            content,
+        } => {
+            rectangle(state, fractional_bounds);
+            stack.push(content);
+        }
+        Primitive::Marquee {
+            bounds,
+            fill,
+            border,
+        } => {
+            rectangle(state, bounds);
+            colors(state, &[*fill, *border]);
+        }
+        Primitive::Caret {
+            position,
+            height,
+            color,
+            blink_phase,
+        } => {
+            points(state, &[*position]);
+            floats(state, &[*height, *blink_phase]);
+            colors(state, &[*color]);
+        }
+        Primitive::Arc {
+            center,
+            radius,
+            width,
+            start_angle,
+            progress,
+            color,
+        } => {
+            points(state, &[*center]);
+            floats(state, &[*radius, *width, *start_angle, *progress]);
+            colors(state, &[*color]);
+        }
+        Primitive::SolidMesh { buffers, size } => {
+            mem::discriminant(&buffers.alpha_mode).hash(state);
+            floats(state, bytemuck::cast_slice(&buffers.vertices));
+            buffers.indices.hash(state);
+            floats(state, &[size.width, size.height]);
+        }
+        Primitive::GradientMesh {
+            buffers,
+            size,
+            gradient,
+        } => {
+            floats(state, bytemuck::cast_slice(&buffers.vertices));
+            buffers.indices.hash(state);
+            floats(state, &[size.width, size.height]);
+
+            match gradient {
+                Gradient::Linear(linear) => {
+                    points(state, &[linear.start, linear.end]);
+                    linear.color_stops.len().hash(state);
+
+                    for stop in &linear.color_stops {
+                        floats(state, &[stop.offset]);
+                        colors(state, &[stop.color]);
+                    }
+                }
+            }
+        }
+        Primitive::Fill {
+            path,
+            fill_rule,
+            color,
+        } => {
+            segments(state, path.segments());
+            mem::discriminant(fill_rule).hash(state);
+            colors(state, &[*color]);
+        }
+        Primitive::Stroke {
+            path,
+            width,
+            color,
+            line_cap,
+            line_join,
+        } => {
+            segments(state, path.segments());
+            floats(state, &[*width]);
+            colors(state, &[*color]);
+            mem::discriminant(line_cap).hash(state);
+            mem::discriminant(line_join).hash(state);
+        }
+        Primitive::Mesh3D {
+            buffers,
+            transformation,
+        } => {
+            mem::discriminant(&buffers.alpha_mode).hash(state);
+            floats(state, bytemuck::cast_slice(&buffers.vertices));
+            buffers.indices.hash(state);
+            floats(state, transformation.as_ref());
+        }
+        // The key changes whenever the contents of the cache do
+        Primitive::Cached { key, .. } => key.hash(state),
+    }
+}
+
+fn segments(state: &mut impl Hasher, values: &[Segment]) {
+    values.len().hash(state);
+
+    for segment in values {
+        mem::discriminant(segment).hash(state);
+
+        match segment {
+            Segment::MoveTo(point) | Segment::LineTo(point) => {
+                points(state, &[*point]);
+            }
+            Segment::Close => {}
+        }
+    }
+}
+
+/// Hashes the given floats quantized to a fixed precision, so tiny
+/// differences caused by rounding errors do not change the hash.
+fn floats(state: &mut impl Hasher, values: &[f32]) {
+    for value in values {
+        ((value * QUANTIZATION).round() as i64).hash(state);
+    }
+}
+
+fn points(state: &mut impl Hasher, values: &[Point]) {
+    for point in values {
+        floats(state, &[point.x, point.y]);
+    }
+}
+
+fn vector(state: &mut impl Hasher, vector: Vector) {
+    floats(state, &[vector.x, vector.y]);
+}
+
+fn rectangle(state: &mut impl Hasher, rectangle: &Rectangle) {
+    floats(
+        state,
+        &[rectangle.x, rectangle.y, rectangle.width, rectangle.height],
+    );
+}
+
+fn colors(state: &mut impl Hasher, values: &[Color]) {
+    for color in values {
+        floats(state, &[color.r, color.g, color.b, color.a]);
+    }
+}
+
+fn color_stops(state: &mut impl Hasher, stops: &[(f32, Color)]) {
+    stops.len().hash(state);
+
+    for (offset, color) in stops {
+        floats(state, &[*offset]);
+        colors(state, &[*color]);
+    }
+}
+
+fn paint(state: &mut impl Hasher, paint: &Paint) {
+    mem::discriminant(paint).hash(state);
+
+    match paint {
+        Paint::Background(background) => {
+            mem::discriminant(background).hash(state);
+
+            match background {
+                Background::Color(color) => colors(state, &[*color]),
+                Background::LinearGradient {
+                    direction,
+                    start,
+                    end,
+                } => {
+                    vector(state, *direction);
+                    colors(state, &[*start, *end]);
+                }
+                Background::RadialGradient {
+                    center,
+                    radius,
+                    start,
+                    end,
+                } => {
+                    points(state, &[*center]);
+                    floats(state, &[*radius]);
+                    colors(state, &[*start, *end]);
+                }
+            }
+        }
+        Paint::LinearGradient { direction, stops } => {
+            vector(state, *direction);
+            color_stops(state, stops);
+        }
+        Paint::RadialGradient {
+            center,
+            radius,
+            stops,
+        } => {
+            points(state, &[*center]);
+            floats(state, &[*radius]);
+            color_stops(state, stops);
+        }
+        Paint::ConicGradient {
+            center,
+            start_angle,
+            stops,
+        } => {
+            points(state, &[*center]);
+            floats(state, &[*start_angle]);
+            color_stops(state, stops);
+        }
+        Paint::Image {
+            handle,
+            fit,
+            repeat,
+            tile_size,
+        } => {
+            handle.id().hash(state);
+            mem::discriminant(fit).hash(state);
+            repeat.hash(state);
+
+            if let Some(size) = tile_size {
+                floats(state, &[size.width, size.height]);
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::primitive::Primitive;
+use crate::Transformation;
+
+/// A visitor of the nodes of a [`Primitive`] tree.
+///
+/// Every method has an empty default implementation, so a visitor only needs
+/// to implement the hooks it cares about. [`PrimitiveVisitor::walk`] traverses
+/// the tree and accumulates the transformation of every node, which is given
+/// to each hook.
+pub trait PrimitiveVisitor {
+    /// Visits a [`Primitive::Quad`] or [`Primitive::QuadBatch`].
+    fn visit_quad(
+        &mut self,
+        _primitive: &Primitive,
+        _transformation: Transformation,
+    ) {
+    }
+
+    /// Visits a [`Primitive::Text`].
+    fn visit_text(
+        &mut self,
+        _primitive: &Primitive,
+        _transformation: Transformation,
+    ) {
+    }
+
+    /// Visits a [`Primitive::Image`], [`Primitive::NinePatch`], or
+    /// [`Primitive::Svg`].
+    fn visit_image(
+        &mut self,
+        _primitive: &Primitive,
+        _transformation: Transformation,
+    ) {
+    }
+
+    /// Visits a [`Primitive::SolidMesh`], [`Primitive::GradientMesh`],
+    /// [`Primitive::Fill`], [`Primitive::Stroke`], or [`Primitive::Mesh3D`].
+    fn visit_mesh(
+        &mut self,
+        _primitive: &Primitive,
+        _transformation: Transformation,
+    ) {
+    }
+
+    /// Visits any other primitive without children, like shadows, arcs, or
+    /// carets.
+    fn visit_other(
+        &mut self,
+        _primitive: &Primitive,
+        _transformation: Transformation,
+    ) {
+    }
+
+    /// Enters a primitive with children: a [`Primitive::Group`],
+    /// [`Primitive::Clip`], [`Primitive::RoundedClip`],
+    /// [`Primitive::Translate`], [`Primitive::Translate3D`],
+    /// [`Primitive::Scale`], [`Primitive::Tint`],
+    /// [`Primitive::ViewportRelative`], or [`Primitive::Cached`].
+    ///
+    /// The given transformation is the one of the primitive itself, not of
+    /// its children.
+    fn enter(
+        &mut self,
+        _primitive: &Primitive,
+        _transformation: Transformation,
+    ) {
+    }
+
+    /// Exits a primitive with children, once all of them have been visited.
+    ///
+    /// It receives the same arguments as the matching
+    /// [`PrimitiveVisitor::enter`].
+    fn exit(
+        &mut self,
+        _primitive: &Primitive,
+        _transformation: Transformation,
+    ) {
+    }
+
+    /// Visits the given [`Primitive`] and all of its children in drawing
+    /// order, starting with the given transformation.
+    fn walk(&mut self, primitive: &Primitive, transformation: Transformation) {
+        enum Step<'a> {
+            Visit(&'a Primitive, Transformation),
+            Exit(&'a Primitive, Transformation),
+        }
+
+        let mut stack = vec![Step::Visit(primitive, transformation)];
+
+        while let Some(step) = stack.pop() {
+            let (primitive, transformation) = match step {
+                Step::Visit(primitive, transformation) => {
+                    (primitive, transformation)
+                }
+                Step::Exit(primitive, transformation) => {
+                    self.exit(primitive, transformation);
+                    continue;
+                }
+            };
+
+            let children = match primitive {
+                Primitive::None => continue,
+                Primitive::Quad { .. } | Primitive::QuadBatch { .. } => {
+                    self.visit_quad(primitive, transformation);
+                    continue;
+                }
+                Primitive::Text { .. } => {
+                    self.visit_text(primitive, transformation);
+                    continue;
+                }
+                Primitive::Image { .. }
+                | Primitive::NinePatch { .. }
+                | Primitive::Svg { .. } => {
+                    self.visit_image(primitive, transformation);
+                    continue;
+                }
+                Primitive::SolidMesh { .. }
+                | Primitive::GradientMesh { .. }
+                | Primitive::Fill { .. }
+                | Primitive::Stroke { .. }
+                | Primitive::Mesh3D { .. } => {
+                    self.visit_mesh(primitive, transformation);
+                    continue;
+                }
+                Primitive::Shadow { .. }
+                | Primitive::InnerShadow { .. }
+                | Primitive::Glow { .. }
+                | Primitive::Marquee { .. }
+                | Primitive::Caret { .. }
+                | Primitive::Arc { .. } => {
+                    self.visit_other(primitive, transformation);
+                    continue;
+                }
+                Primitive::Group { primitives } => primitives
+                    .iter()
+                    .map(|primitive| (primitive, transformation))
+                    .collect(),
+                Primitive::Clip { content, .. }
+                | Primitive::RoundedClip { content, .. }
+                | Primitive::Tint { content, .. } => {
+                    vec![(content.as_ref(), transformation)]
+                }
+                // The visitor does not know the viewport, so the content is
+                // visited in the coordinates of the resolved bounds
+                Primitive::ViewportRelative { content, .. } => {
+                    vec![(content.as_ref(), transformation)]
+                }
+                Primitive::Translate {
+                    translation,
+                    content,
+                } => vec![(
+                    content.as_ref(),
+                    transformation.translated_vector(*translation),
+                )],
+                Primitive::Translate3D {
+                    translation: [x, y, z],
+                    content,
+                } => vec![(
+                    content.as_ref(),
+                    transformation.translated_3d(*x, *y, *z),
+                )],
+                Primitive::Scale { scale, content } => vec![(
+                    content.as_ref(),
+                    transformation * Transformation::scale(*scale, *scale),
+                )],
+                Primitive::Cached { cache, .. } => {
+                    vec![(cache.as_ref(), transformation)]
+                }
+            };
+
+            self.enter(primitive, transformation);
+
+            stack.push(Step::Exit(primitive, transformation));
+            stack.extend(children.into_iter().rev().map(
+                |(primitive, transformation)| {
+                    Step::Visit(primitive, transformation)
+                },
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Background, BorderAlignment, Color, Point, Rectangle};
+    use crate::{ClipMode, Size, Vector};
+
+    fn quad(x: f32) -> Primitive {
+        Primitive::Quad {
+            bounds: Rectangle::new(Point::new(x, 0.0), Size::new(10.0, 10.0)),
+            background: Background::Color(Color::BLACK).into(),
+            border_radius: [0.0; 4],
+            border_width: 0.0.into(),
+            border_color: Color::TRANSPARENT.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
+        }
+    }
+
+    #[derive(Default)]
+    struct Quads {
+        origins: Vec<Point>,
+        depth: usize,
+        max_depth: usize,
+    }
+
+    impl PrimitiveVisitor for Quads {
+        fn visit_quad(
+            &mut self,
+            primitive: &Primitive,
+            transformation: Transformation,
+        ) {
+            if let Primitive::Quad { bounds, .. } = primitive {
+                self.origins
+                    .push(transformation.transform_point(bounds.position()));
+            }
+        }
+
+        fn enter(&mut self, _primitive: &Primitive, _: Transformation) {
+            self.depth += 1;
+            self.max_depth = self.max_depth.max(self.depth);
+        }
+
+        fn exit(&mut self, _primitive: &Primitive, _: Transformation) {
+            self.depth -= 1;
+        }
+    }
+
+    #[test]
+    fn visitors_count_nested_quads() {
+        let primitive = Primitive::Group {
+            primitives: vec![
+                quad(0.0),
+                Primitive::Translate {
+                    translation: Vector::new(5.0, 10.0),
+                    content: Box::new(Primitive::Scale {
+                        scale: 2.0,
+                        content: Box::new(Primitive::Group {
+                            primitives: vec![quad(1.0), quad(2.0)],
+                        }),
+                    }),
+                },
+                Primitive::Clip {
+                    bounds: Rectangle::new(
+                        Point::ORIGIN,
+                        Size::new(50.0, 50.0),
+                    ),
+                    antialias: false,
+                    mode: ClipMode::Intersect,
+                    content: Box::new(quad(3.0)),
+                },
+            ],
+        };
+
+        let mut visitor = Quads::default();
+        visitor.walk(&primitive, Transformation::identity());
+
+        assert_eq!(visitor.origins.len(), 4);
+        assert_eq!(
+            visitor.origins,
+            [
+                Point::new(0.0, 0.0),
+                Point::new(7.0, 10.0),
+                Point::new(9.0, 10.0),
+                Point::new(3.0, 0.0),
+            ]
+        );
+        assert_eq!(visitor.depth, 0);
+        assert_eq!(visitor.max_depth, 4);
+    }
+}
@@ -0,0 +1,13 @@
+use crate::{Color, Rectangle};
+
+/// A single quad of a [`Primitive::QuadBatch`].
+///
+/// [`Primitive::QuadBatch`]: crate::Primitive::QuadBatch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadInstance {
+    /// The bounds of the quad.
+    pub bounds: Rectangle,
+
+    /// The color of the quad.
+    pub color: Color,
+}
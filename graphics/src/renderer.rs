@@ -1,12 +1,16 @@
 //! Create a renderer from a [`Backend`].
 use crate::backend::{self, Backend};
-use crate::{Primitive, Vector};
+use crate::image::Filter;
+use crate::{
+    BorderAlignment, ClipMode, ImageFit, LineHeight, Paint, Primitive,
+    Truncate, Vector,
+};
 use iced_native::image;
 use iced_native::layout;
 use iced_native::renderer;
 use iced_native::svg;
 use iced_native::text::{self, Text};
-use iced_native::{Background, Element, Font, Point, Rectangle, Size};
+use iced_native::{Background, Color, Element, Font, Point, Rectangle, Size};
 
 pub use iced_native::renderer::Style;
 
@@ -75,6 +79,8 @@ where
 
         self.primitives.push(Primitive::Clip {
             bounds,
+            antialias: false,
+            mode: ClipMode::Intersect,
             content: Box::new(Primitive::Group {
                 primitives: layer_primitives,
             }),
@@ -108,10 +114,12 @@ where
     ) {
         self.primitives.push(Primitive::Quad {
             bounds: quad.bounds,
-            background: background.into(),
+            background: Paint::Background(background.into()),
             border_radius: quad.border_radius.into(),
-            border_width: quad.border_width,
-            border_color: quad.border_color,
+            border_width: quad.border_width.into(),
+            border_color: quad.border_color.into(),
+            border_alignment: BorderAlignment::Inside,
+            corner_smoothing: 0.0,
         });
     }
 
@@ -169,10 +177,17 @@ where
             content: text.content.to_string(),
             bounds: text.bounds,
             size: text.size,
+            letter_spacing: 0.0,
+            line_height: LineHeight::default(),
             color: text.color,
             font: text.font,
             horizontal_alignment: text.horizontal_alignment,
             vertical_alignment: text.vertical_alignment,
+            truncate: Truncate::None,
+            max_lines: None,
+            background: None,
+            shadow: None,
+            snap_baselines: false,
         });
     }
 }
@@ -188,7 +203,16 @@ where
     }
 
     fn draw(&mut self, handle: image::Handle, bounds: Rectangle) {
-        self.draw_primitive(Primitive::Image { handle, bounds })
+        self.draw_primitive(Primitive::Image {
+            handle,
+            bounds,
+            source: None,
+            tint: Color::WHITE,
+            opacity: 1.0,
+            rotation: 0.0,
+            filter: Filter::default(),
+            fit: ImageFit::default(),
+        })
     }
 }
 
@@ -201,6 +225,12 @@ where
     }
 
     fn draw(&mut self, handle: svg::Handle, bounds: Rectangle) {
-        self.draw_primitive(Primitive::Svg { handle, bounds })
+        self.draw_primitive(Primitive::Svg {
+            handle,
+            bounds,
+            opacity: 1.0,
+            color: None,
+            rotation: 0.0,
+        })
     }
 }
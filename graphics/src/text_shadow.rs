@@ -0,0 +1,14 @@
+use crate::{Color, Vector};
+
+/// A drop shadow drawn behind the glyphs of some text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextShadow {
+    /// The color of the shadow.
+    pub color: Color,
+
+    /// The offset of the shadow from the text.
+    pub offset: Vector,
+
+    /// The blur radius of the shadow.
+    pub blur: f32,
+}
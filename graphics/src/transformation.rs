@@ -1,4 +1,6 @@
-use glam::{Mat4, Vec3};
+use crate::{Point, Rectangle, Size, Vector, Viewport};
+
+use glam::{Mat4, Quat, Vec3, Vec4};
 use std::ops::Mul;
 
 /// A 2D transformation matrix.
@@ -6,30 +8,366 @@ use std::ops::Mul;
 pub struct Transformation(Mat4);
 
 impl Transformation {
+    /// The default tolerance of [`Transformation::approx_eq`].
+    pub const DEFAULT_EPSILON: f32 = 1.0e-6;
+
     /// Get the identity transformation.
     pub fn identity() -> Transformation {
         Transformation(Mat4::IDENTITY)
     }
 
+    /// Creates a [`Transformation`] from the given raw matrix.
+    ///
+    /// This allows wrapping any custom projection, like a perspective one,
+    /// while reusing all of the `transform_*` helpers.
+    pub fn from_mat4(matrix: Mat4) -> Transformation {
+        Transformation(matrix)
+    }
+
     /// Creates an orthographic projection.
-    #[rustfmt::skip]
     pub fn orthographic(width: u32, height: u32) -> Transformation {
+        Self::orthographic_f32(width as f32, height as f32)
+    }
+
+    /// Creates an orthographic projection from fractional dimensions.
+    ///
+    /// This avoids rounding the logical size of a viewport with a
+    /// fractional scale factor.
+    pub fn orthographic_f32(width: f32, height: f32) -> Transformation {
+        Self::orthographic_with_depth(width, height, -1.0, 1.0)
+    }
+
+    /// Creates an orthographic projection with the given near and far
+    /// planes.
+    #[rustfmt::skip]
+    pub fn orthographic_with_depth(
+        width: f32,
+        height: f32,
+        near: f32,
+        far: f32,
+    ) -> Transformation {
         Transformation(Mat4::orthographic_rh_gl(
-            0.0, width as f32,
-            height as f32, 0.0,
-            -1.0, 1.0
+            0.0, width,
+            height, 0.0,
+            near, far
         ))
     }
 
+    /// Creates the [`Transformation`] from physical window pixels to clip
+    /// space of the given [`Viewport`].
+    ///
+    /// This is the projection of the [`Viewport`].
+    pub fn window_to_clip(viewport: &Viewport) -> Transformation {
+        viewport.projection()
+    }
+
+    /// Creates the [`Transformation`] from clip space to physical window
+    /// pixels of the given [`Viewport`].
+    ///
+    /// This is the inverse of [`Transformation::window_to_clip`], which is
+    /// useful to map clip space positions back for input handling. An empty
+    /// [`Viewport`] has no inverse, in which case the identity is returned.
+    pub fn clip_to_window(viewport: &Viewport) -> Transformation {
+        Self::window_to_clip(viewport)
+            .inverse()
+            .unwrap_or_else(Self::identity)
+    }
+
+    /// Creates the [`Transformation`] from physical window pixels to the
+    /// logical coordinates of the given [`Viewport`].
+    pub fn window_to_viewport(viewport: &Viewport) -> Transformation {
+        let scale = 1.0 / viewport.scale_factor() as f32;
+
+        Self::scale(scale, scale)
+    }
+
+    /// Creates the [`Transformation`] from the logical coordinates of the
+    /// given [`Viewport`] to clip space.
+    ///
+    /// This is how renderers project the contents of layers.
+    pub fn viewport_to_clip(viewport: &Viewport) -> Transformation {
+        let scale = viewport.scale_factor() as f32;
+
+        Self::window_to_clip(viewport) * Self::scale(scale, scale)
+    }
+
+    /// Returns the inverse of the [`Transformation`], if it is invertible.
+    pub fn inverse(&self) -> Option<Transformation> {
+        let determinant = self.0.determinant();
+
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        Some(Transformation(self.0.inverse()))
+    }
+
     /// Creates a translate transformation.
     pub fn translate(x: f32, y: f32) -> Transformation {
         Transformation(Mat4::from_translation(Vec3::new(x, y, 0.0)))
     }
 
+    /// Creates a translate transformation from the given [`Vector`].
+    pub fn translate_vector(translation: Vector) -> Transformation {
+        Self::translate(translation.x, translation.y)
+    }
+
+    /// Returns the [`Transformation`] that results from applying the given
+    /// translation, in local coordinates, before this one.
+    ///
+    /// This matches [`TranslateScale::translated`].
+    pub fn translated_vector(self, translation: Vector) -> Transformation {
+        self * Self::translate_vector(translation)
+    }
+
+    /// Creates a translate transformation that also moves along the Z axis.
+    ///
+    /// The Z component is kept in the matrix for renderers with a depth
+    /// buffer, while [`Transformation::transform_point`] ignores it.
+    pub fn translate_3d(x: f32, y: f32, z: f32) -> Transformation {
+        Transformation(Mat4::from_translation(Vec3::new(x, y, z)))
+    }
+
+    /// Returns the [`Transformation`] that results from applying the given
+    /// 3D translation, in local coordinates, before this one.
+    pub fn translated_3d(self, x: f32, y: f32, z: f32) -> Transformation {
+        self * Self::translate_3d(x, y, z)
+    }
+
     /// Creates a scale transformation.
     pub fn scale(x: f32, y: f32) -> Transformation {
         Transformation(Mat4::from_scale(Vec3::new(x, y, 1.0)))
     }
+
+    /// Creates a skew transformation, given the shear angles of the X and Y
+    /// axes in radians.
+    ///
+    /// Points are shifted horizontally proportionally to their Y coordinate,
+    /// and vertically proportionally to their X coordinate.
+    pub fn skew(x_radians: f32, y_radians: f32) -> Transformation {
+        Transformation(Mat4::from_cols(
+            Vec4::new(1.0, y_radians.tan(), 0.0, 0.0),
+            Vec4::new(x_radians.tan(), 1.0, 0.0, 0.0),
+            Vec4::Z,
+            Vec4::W,
+        ))
+    }
+
+    /// Returns the [`Transformation`] that results from applying the given
+    /// skew, in local coordinates, before this one.
+    pub fn skewed(self, x_radians: f32, y_radians: f32) -> Transformation {
+        self * Self::skew(x_radians, y_radians)
+    }
+
+    /// Applies the [`Transformation`] to the given [`Point`].
+    pub fn transform_point(&self, point: Point) -> Point {
+        let point = self.0.transform_point3(Vec3::new(point.x, point.y, 0.0));
+
+        Point::new(point.x, point.y)
+    }
+
+    /// Applies the [`Transformation`] to all the given points, writing the
+    /// results to `out`.
+    ///
+    /// It produces the same results as calling
+    /// [`Transformation::transform_point`] for every point, but reads the
+    /// matrix only once.
+    ///
+    /// # Panics
+    /// Panics if `points` and `out` have different lengths.
+    pub fn transform_points(&self, points: &[Point], out: &mut [Point]) {
+        assert_eq!(
+            points.len(),
+            out.len(),
+            "points and out must have the same length"
+        );
+
+        let Mat4 {
+            x_axis,
+            y_axis,
+            w_axis,
+            ..
+        } = self.0;
+
+        for (point, out) in points.iter().zip(out) {
+            *out = Point::new(
+                x_axis.x * point.x + y_axis.x * point.y + w_axis.x,
+                x_axis.y * point.x + y_axis.y * point.y + w_axis.y,
+            );
+        }
+    }
+
+    /// Applies the [`Transformation`] to the given 3D position, including the
+    /// perspective division, and returns the XY coordinates of the result.
+    pub fn project_point(&self, position: [f32; 3]) -> Point {
+        let point = self.0.project_point3(Vec3::from(position));
+
+        Point::new(point.x, point.y)
+    }
+
+    /// Applies the [`Transformation`] to the given [`Rectangle`], returning
+    /// the axis-aligned bounding box of the result.
+    pub fn transform_rectangle(&self, rectangle: Rectangle) -> Rectangle {
+        let corners = [
+            Point::new(rectangle.x, rectangle.y),
+            Point::new(rectangle.x + rectangle.width, rectangle.y),
+            Point::new(rectangle.x, rectangle.y + rectangle.height),
+            Point::new(
+                rectangle.x + rectangle.width,
+                rectangle.y + rectangle.height,
+            ),
+        ]
+        .map(|corner| self.transform_point(corner));
+
+        let (min, max) = corners.iter().skip(1).fold(
+            (corners[0], corners[0]),
+            |(min, max), corner| {
+                (
+                    Point::new(min.x.min(corner.x), min.y.min(corner.y)),
+                    Point::new(max.x.max(corner.x), max.y.max(corner.y)),
+                )
+            },
+        );
+
+        Rectangle {
+            x: min.x,
+            y: min.y,
+            width: max.x - min.x,
+            height: max.y - min.y,
+        }
+    }
+
+    /// Returns whether all the elements of this [`Transformation`] are within
+    /// `epsilon` of the elements of `other`.
+    ///
+    /// This is useful to compare transformations resulting from a series of
+    /// floating point operations. See [`Transformation::DEFAULT_EPSILON`].
+    pub fn approx_eq(&self, other: &Transformation, epsilon: f32) -> bool {
+        self.0.abs_diff_eq(other.0, epsilon)
+    }
+
+    /// Linearly interpolates between this [`Transformation`] and `other`.
+    ///
+    /// Transformations free of rotation are interpolated element-wise, which
+    /// is cheap and exact. Otherwise, both transformations are decomposed into
+    /// translation, rotation, and scale, which are interpolated separately,
+    /// following the shorter arc for the rotation.
+    ///
+    /// `t` is clamped to `[0, 1]`, and both ends return the exact
+    /// transformations given.
+    pub fn lerp(&self, other: &Transformation, t: f32) -> Transformation {
+        if t <= 0.0 {
+            return *self;
+        } else if t >= 1.0 {
+            return *other;
+        }
+
+        if !self.is_rotation_free() || !other.is_rotation_free() {
+            if let (Some(a), Some(b)) = (self.decompose(), other.decompose()) {
+                let (translation_a, rotation_a, scale_a) = a;
+                let (translation_b, rotation_b, scale_b) = b;
+
+                let mut delta =
+                    (rotation_b - rotation_a) % (2.0 * std::f32::consts::PI);
+
+                if delta > std::f32::consts::PI {
+                    delta -= 2.0 * std::f32::consts::PI;
+                } else if delta < -std::f32::consts::PI {
+                    delta += 2.0 * std::f32::consts::PI;
+                }
+
+                return Self::from_decomposed(
+                    translation_a + (translation_b - translation_a) * t,
+                    rotation_a + delta * t,
+                    scale_a + (scale_b - scale_a) * t,
+                );
+            }
+        }
+
+        Transformation(self.0 * (1.0 - t) + other.0 * t)
+    }
+
+    /// Returns whether the [`Transformation`] has no rotation nor shear in
+    /// the XY plane.
+    fn is_rotation_free(&self) -> bool {
+        self.0.x_axis.y == 0.0 && self.0.y_axis.x == 0.0
+    }
+
+    /// Decomposes an affine 2D [`Transformation`] into its translation,
+    /// rotation around the Z axis in radians, and X/Y scale.
+    ///
+    /// Returns `None` if the [`Transformation`] has any other component, like
+    /// perspective, depth, or shear.
+    ///
+    /// A negative determinant is represented as a negative Y scale.
+    pub fn decompose(&self) -> Option<(Vector, f32, Vector)> {
+        let Mat4 {
+            x_axis,
+            y_axis,
+            z_axis,
+            w_axis,
+        } = self.0;
+
+        let is_affine_2d = x_axis.z == 0.0
+            && x_axis.w == 0.0
+            && y_axis.z == 0.0
+            && y_axis.w == 0.0
+            && z_axis == Vec4::Z
+            && w_axis.z == 0.0
+            && w_axis.w == 1.0;
+
+        let scale_x = x_axis.x.hypot(x_axis.y);
+        let scale_y = y_axis.x.hypot(y_axis.y);
+
+        if !is_affine_2d || scale_x == 0.0 || scale_y == 0.0 {
+            return None;
+        }
+
+        let is_sheared = (x_axis.x * y_axis.x + x_axis.y * y_axis.y).abs()
+            > Self::DEFAULT_EPSILON * scale_x * scale_y;
+
+        if is_sheared {
+            return None;
+        }
+
+        let rotation = x_axis.y.atan2(x_axis.x);
+        let determinant = x_axis.x * y_axis.y - y_axis.x * x_axis.y;
+
+        Some((
+            Vector::new(w_axis.x, w_axis.y),
+            rotation,
+            Vector::new(scale_x, determinant / scale_x),
+        ))
+    }
+
+    /// Creates a 2D [`Transformation`] that scales, rotates around the Z axis,
+    /// and translates, in that order.
+    ///
+    /// This is the inverse of [`Transformation::decompose`].
+    pub fn from_decomposed(
+        translation: Vector,
+        rotation: f32,
+        scale: Vector,
+    ) -> Transformation {
+        Transformation(Mat4::from_scale_rotation_translation(
+            Vec3::new(scale.x, scale.y, 1.0),
+            Quat::from_rotation_z(rotation),
+            Vec3::new(translation.x, translation.y, 0.0),
+        ))
+    }
+
+    /// Creates a 2D [`Transformation`] from the given scale, rotation around
+    /// the Z axis in radians, and translation.
+    ///
+    /// They are composed in the conventional `T * R * S` order, which means
+    /// that points are scaled first, then rotated, and finally translated.
+    pub fn from_srt(
+        scale: Vector,
+        rotation: f32,
+        translation: Vector,
+    ) -> Transformation {
+        Self::from_decomposed(translation, rotation, scale)
+    }
 }
 
 impl Mul for Transformation {
@@ -52,8 +390,1101 @@ impl From<Transformation> for [f32; 16] {
     }
 }
 
+impl From<Mat4> for Transformation {
+    fn from(matrix: Mat4) -> Self {
+        Self::from_mat4(matrix)
+    }
+}
+
 impl From<Transformation> for Mat4 {
     fn from(transformation: Transformation) -> Self {
         transformation.0
     }
 }
+
+/// A 2D transformation composed of a translation and a uniform scale.
+///
+/// It is cheaper to apply than a [`Transformation`] and is used to
+/// accumulate the transformations of nested primitives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TranslateScale {
+    /// The translation of the [`TranslateScale`].
+    pub translation: Vector,
+
+    /// The uniform scale of the [`TranslateScale`].
+    pub scale: f32,
+}
+
+impl TranslateScale {
+    /// The identity [`TranslateScale`].
+    pub const IDENTITY: Self = Self {
+        translation: Vector::new(0.0, 0.0),
+        scale: 1.0,
+    };
+
+    /// Creates a new [`TranslateScale`] with the given translation and scale.
+    pub const fn new(translation: Vector, scale: f32) -> Self {
+        Self { translation, scale }
+    }
+
+    /// Creates the [`TranslateScale`] that fits the `content` rectangle into
+    /// a viewport of the given size, preserving its aspect ratio.
+    ///
+    /// The content is centered in the viewport, leaving at least `padding`
+    /// around it on every side. Empty content is only centered.
+    pub fn fit_rect_into(
+        content: Rectangle,
+        viewport: Size,
+        padding: f32,
+    ) -> Self {
+        let available = Size::new(
+            (viewport.width - padding * 2.0).max(0.0),
+            (viewport.height - padding * 2.0).max(0.0),
+        );
+
+        let scale = (available.width / content.width)
+            .min(available.height / content.height);
+
+        let scale = if scale.is_finite() { scale } else { 1.0 };
+        let center = content.center();
+
+        Self {
+            translation: Vector::new(
+                viewport.width / 2.0 - center.x * scale,
+                viewport.height / 2.0 - center.y * scale,
+            ),
+            scale,
+        }
+    }
+
+    /// Returns the [`TranslateScale`] that results from applying the given
+    /// translation, in local coordinates, before this one.
+    pub fn translated(self, translation: Vector) -> Self {
+        Self {
+            translation: self.translation + translation * self.scale,
+            scale: self.scale,
+        }
+    }
+
+    /// Returns the [`TranslateScale`] that results from applying the given
+    /// scale, in local coordinates, before this one.
+    pub fn scaled(self, scale: f32) -> Self {
+        Self {
+            translation: self.translation,
+            scale: self.scale * scale,
+        }
+    }
+
+    /// Linearly interpolates the translation and scale of this
+    /// [`TranslateScale`] and `other`.
+    ///
+    /// `t` is clamped to `[0, 1]`, and both ends return the exact values
+    /// given.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        if t <= 0.0 {
+            return *self;
+        } else if t >= 1.0 {
+            return *other;
+        }
+
+        Self {
+            translation: self.translation
+                + (other.translation - self.translation) * t,
+            scale: self.scale + (other.scale - self.scale) * t,
+        }
+    }
+
+    /// Applies the [`TranslateScale`] to the given [`Point`].
+    ///
+    /// Infinite coordinates stay infinite, even with a zero scale.
+    pub fn transform_point(&self, point: Point) -> Point {
+        debug_assert!(
+            !point.x.is_nan() && !point.y.is_nan(),
+            "transforming a NaN point: {:?}",
+            point
+        );
+
+        let transform = |coordinate: f32, translation: f32| {
+            // `0.0 * f32::INFINITY` is NaN
+            if coordinate.is_infinite() {
+                if self.scale < 0.0 {
+                    -coordinate
+                } else {
+                    coordinate
+                }
+            } else {
+                coordinate * self.scale + translation
+            }
+        };
+
+        Point::new(
+            transform(point.x, self.translation.x),
+            transform(point.y, self.translation.y),
+        )
+    }
+
+    /// Applies the [`TranslateScale`] to all the given points, writing the
+    /// results to `out`.
+    ///
+    /// It produces the same results as calling
+    /// [`TranslateScale::transform_point`] for every point. Unless the scale
+    /// is zero or the translation is infinite, infinite coordinates need no
+    /// special handling, so the points are transformed with a plain
+    /// multiply-add that can be vectorized.
+    ///
+    /// # Panics
+    /// Panics if `points` and `out` have different lengths.
+    pub fn transform_points(&self, points: &[Point], out: &mut [Point]) {
+        assert_eq!(
+            points.len(),
+            out.len(),
+            "points and out must have the same length"
+        );
+
+        let Self { translation, scale } = *self;
+
+        if scale == 0.0
+            || !translation.x.is_finite()
+            || !translation.y.is_finite()
+        {
+            for (point, out) in points.iter().zip(out) {
+                *out = self.transform_point(*point);
+            }
+
+            return;
+        }
+
+        for (point, out) in points.iter().zip(out) {
+            debug_assert!(
+                !point.x.is_nan() && !point.y.is_nan(),
+                "transforming a NaN point: {:?}",
+                point
+            );
+
+            *out = Point::new(
+                point.x * scale + translation.x,
+                point.y * scale + translation.y,
+            );
+        }
+    }
+
+    /// Applies the [`TranslateScale`] to the given [`Vector`], ignoring its
+    /// translation.
+    pub fn transform_vector(&self, vector: Vector) -> Vector {
+        vector * self.scale
+    }
+
+    /// Applies the scale of the [`TranslateScale`] to the given scalar.
+    pub fn transform_scalar(&self, scalar: f32) -> f32 {
+        scalar * self.scale
+    }
+
+    /// Applies the [`TranslateScale`] to the given [`Rectangle`].
+    ///
+    /// Infinite dimensions are preserved. A negative scale flips the
+    /// rectangle, which keeps its dimensions non-negative.
+    pub fn transform_rectangle(&self, rectangle: Rectangle) -> Rectangle {
+        let position =
+            self.transform_point(Point::new(rectangle.x, rectangle.y));
+
+        let transform_length = |length: f32| {
+            if length.is_infinite() && self.scale >= 0.0 {
+                length
+            } else if length.is_infinite() {
+                -length
+            } else {
+                self.transform_scalar(length)
+            }
+        };
+
+        // A flipped edge extends backwards from the transformed position
+        let span = |start: f32, length: f32| {
+            if length < 0.0 {
+                (start + length, -length)
+            } else {
+                (start, length)
+            }
+        };
+
+        let (x, width) = span(position.x, transform_length(rectangle.width));
+        let (y, height) = span(position.y, transform_length(rectangle.height));
+
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Applies the [`TranslateScale`] to the given [`Rectangle`] and clips
+    /// the result to the given finite `bounds`.
+    ///
+    /// Unlike intersecting the result of
+    /// [`TranslateScale::transform_rectangle`], infinite edges are clamped
+    /// before taking part in any arithmetic, so the result is always finite.
+    ///
+    /// Returns `None` if the transformed rectangle is outside of the
+    /// `bounds`.
+    pub fn transform_rectangle_clamped(
+        &self,
+        rectangle: Rectangle,
+        bounds: Rectangle,
+    ) -> Option<Rectangle> {
+        // An infinite position plus an infinite length is NaN
+        let end = |start: f32, length: f32| {
+            if length.is_infinite() {
+                length
+            } else {
+                start + length
+            }
+        };
+
+        // Transforming both corners keeps track of infinite edges, even if
+        // they are flipped
+        let a = self.transform_point(Point::new(rectangle.x, rectangle.y));
+        let b = self.transform_point(Point::new(
+            end(rectangle.x, rectangle.width),
+            end(rectangle.y, rectangle.height),
+        ));
+
+        let x = a.x.min(b.x).max(bounds.x);
+        let y = a.y.min(b.y).max(bounds.y);
+
+        let right = a.x.max(b.x).min(bounds.x + bounds.width);
+        let bottom = a.y.max(b.y).min(bounds.y + bounds.height);
+
+        let width = right - x;
+        let height = bottom - y;
+
+        if width > 0.0 && height > 0.0 {
+            Some(Rectangle {
+                x,
+                y,
+                width,
+                height,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Mul<Point> for TranslateScale {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        self.transform_point(point)
+    }
+}
+
+impl Mul<Vector> for TranslateScale {
+    type Output = Vector;
+
+    fn mul(self, vector: Vector) -> Vector {
+        self.transform_vector(vector)
+    }
+}
+
+impl Mul<Rectangle> for TranslateScale {
+    type Output = Rectangle;
+
+    fn mul(self, rectangle: Rectangle) -> Rectangle {
+        self.transform_rectangle(rectangle)
+    }
+}
+
+impl Default for TranslateScale {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<TranslateScale> for Transformation {
+    fn from(transformation: TranslateScale) -> Self {
+        Transformation::translate(
+            transformation.translation.x,
+            transformation.translation.y,
+        ) * Transformation::scale(transformation.scale, transformation.scale)
+    }
+}
+
+/// A 2D transformation composed of a translation, a rotation, and a uniform
+/// scale.
+///
+/// It extends [`TranslateScale`] with a rotation, while staying cheaper to
+/// apply than a [`Transformation`]. Points are scaled first, then rotated,
+/// and finally translated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TranslateRotateScale {
+    /// The translation of the [`TranslateRotateScale`].
+    pub translation: Vector,
+
+    /// The rotation of the [`TranslateRotateScale`], in radians.
+    ///
+    /// Positive angles rotate clockwise on screen, since the Y axis points
+    /// down.
+    pub rotation: f32,
+
+    /// The uniform scale of the [`TranslateRotateScale`].
+    pub scale: f32,
+}
+
+impl TranslateRotateScale {
+    /// The identity [`TranslateRotateScale`].
+    pub const IDENTITY: Self = Self {
+        translation: Vector::new(0.0, 0.0),
+        rotation: 0.0,
+        scale: 1.0,
+    };
+
+    /// Creates a new [`TranslateRotateScale`] with the given translation,
+    /// rotation, and scale.
+    pub const fn new(translation: Vector, rotation: f32, scale: f32) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Returns the [`TranslateRotateScale`] that results from applying the
+    /// given translation, in local coordinates, before this one.
+    pub fn translated(self, translation: Vector) -> Self {
+        Self {
+            translation: self.translation + self.transform_vector(translation),
+            ..self
+        }
+    }
+
+    /// Returns the [`TranslateRotateScale`] that results from applying the
+    /// given rotation, in local coordinates, before this one.
+    pub fn rotated(self, rotation: f32) -> Self {
+        Self {
+            rotation: self.rotation + rotation,
+            ..self
+        }
+    }
+
+    /// Returns the [`TranslateRotateScale`] that results from applying the
+    /// given scale, in local coordinates, before this one.
+    pub fn scaled(self, scale: f32) -> Self {
+        Self {
+            scale: self.scale * scale,
+            ..self
+        }
+    }
+
+    /// Applies the [`TranslateRotateScale`] to the given [`Point`].
+    pub fn transform_point(&self, point: Point) -> Point {
+        let vector = self.transform_vector(Vector::new(point.x, point.y));
+
+        Point::new(vector.x + self.translation.x, vector.y + self.translation.y)
+    }
+
+    /// Applies the [`TranslateRotateScale`] to the given [`Vector`], ignoring
+    /// its translation.
+    pub fn transform_vector(&self, vector: Vector) -> Vector {
+        let (sin, cos) = self.rotation.sin_cos();
+
+        Vector::new(
+            (vector.x * cos - vector.y * sin) * self.scale,
+            (vector.x * sin + vector.y * cos) * self.scale,
+        )
+    }
+
+    /// Applies the scale of the [`TranslateRotateScale`] to the given scalar.
+    pub fn transform_scalar(&self, scalar: f32) -> f32 {
+        scalar * self.scale
+    }
+
+    /// Applies the [`TranslateRotateScale`] to the given [`Rectangle`],
+    /// returning the axis-aligned bounding box of the result.
+    ///
+    /// Without rotation, this behaves like
+    /// [`TranslateScale::transform_rectangle`] and preserves infinite
+    /// dimensions.
+    pub fn transform_rectangle(&self, rectangle: Rectangle) -> Rectangle {
+        if self.rotation == 0.0 {
+            return TranslateScale::new(self.translation, self.scale)
+                .transform_rectangle(rectangle);
+        }
+
+        let corners = [
+            Point::new(rectangle.x, rectangle.y),
+            Point::new(rectangle.x + rectangle.width, rectangle.y),
+            Point::new(rectangle.x, rectangle.y + rectangle.height),
+            Point::new(
+                rectangle.x + rectangle.width,
+                rectangle.y + rectangle.height,
+            ),
+        ]
+        .map(|corner| self.transform_point(corner));
+
+        let (min, max) = corners.iter().skip(1).fold(
+            (corners[0], corners[0]),
+            |(min, max), corner| {
+                (
+                    Point::new(min.x.min(corner.x), min.y.min(corner.y)),
+                    Point::new(max.x.max(corner.x), max.y.max(corner.y)),
+                )
+            },
+        );
+
+        Rectangle {
+            x: min.x,
+            y: min.y,
+            width: max.x - min.x,
+            height: max.y - min.y,
+        }
+    }
+}
+
+impl Mul<Point> for TranslateRotateScale {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        self.transform_point(point)
+    }
+}
+
+impl Mul<Vector> for TranslateRotateScale {
+    type Output = Vector;
+
+    fn mul(self, vector: Vector) -> Vector {
+        self.transform_vector(vector)
+    }
+}
+
+impl Mul<Rectangle> for TranslateRotateScale {
+    type Output = Rectangle;
+
+    fn mul(self, rectangle: Rectangle) -> Rectangle {
+        self.transform_rectangle(rectangle)
+    }
+}
+
+impl Default for TranslateRotateScale {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<TranslateScale> for TranslateRotateScale {
+    fn from(transformation: TranslateScale) -> Self {
+        Self::new(transformation.translation, 0.0, transformation.scale)
+    }
+}
+
+impl From<TranslateRotateScale> for Transformation {
+    fn from(transformation: TranslateRotateScale) -> Self {
+        Transformation::from_srt(
+            Vector::new(transformation.scale, transformation.scale),
+            transformation.rotation,
+            transformation.translation,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Transformation, b: Transformation) {
+        assert!(a.approx_eq(&b, 1e-5), "{:?} is not close to {:?}", a, b);
+    }
+
+    #[test]
+    fn lerp_translate_scale_is_element_wise() {
+        let a = Transformation::translate(0.0, 10.0)
+            * Transformation::scale(1.0, 1.0);
+        let b = Transformation::translate(20.0, 30.0)
+            * Transformation::scale(3.0, 2.0);
+
+        assert!(a.is_rotation_free() && b.is_rotation_free());
+
+        assert_close(
+            a.lerp(&b, 0.5),
+            Transformation::translate(10.0, 20.0)
+                * Transformation::scale(2.0, 1.5),
+        );
+    }
+
+    #[test]
+    fn lerp_rotation_is_decomposed() {
+        let a = Transformation::identity();
+        let b =
+            Transformation(Mat4::from_rotation_z(std::f32::consts::FRAC_PI_2));
+
+        assert!(!b.is_rotation_free());
+
+        // An element-wise interpolation would shrink the result
+        assert_close(
+            a.lerp(&b, 0.5),
+            Transformation(Mat4::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        );
+    }
+
+    #[test]
+    fn translating_by_a_vector_matches_its_components() {
+        let translation = Vector::new(3.0, -4.5);
+        let scale = Transformation::scale(2.0, 2.0);
+
+        assert_eq!(
+            Transformation::translate_vector(translation),
+            Transformation::translate(3.0, -4.5)
+        );
+        assert_eq!(
+            scale.translated_vector(translation),
+            scale * Transformation::translate(3.0, -4.5)
+        );
+        assert_eq!(
+            scale
+                .translated_vector(translation)
+                .transform_point(Point::ORIGIN),
+            TranslateScale::new(Vector::new(0.0, 0.0), 2.0)
+                .translated(translation)
+                .transform_point(Point::ORIGIN)
+        );
+    }
+
+    #[test]
+    fn lerp_endpoints_are_exact() {
+        let a = Transformation::from_srt(
+            Vector::new(2.0, 0.5),
+            0.3,
+            Vector::new(10.0, 20.0),
+        );
+        let b = Transformation::from_srt(
+            Vector::new(1.0, 1.0),
+            2.5,
+            Vector::new(-5.0, 7.0),
+        );
+
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, -1.0), a);
+        assert_eq!(a.lerp(&b, 2.0), b);
+    }
+
+    #[test]
+    fn translate_scale_lerp_averages_both_fields() {
+        let a = TranslateScale::new(Vector::new(0.1, 10.0), 0.3);
+        let b = TranslateScale::new(Vector::new(20.0, -30.0), 2.7);
+
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 5.0), b);
+        assert_eq!(
+            TranslateScale::IDENTITY
+                .lerp(&TranslateScale::new(Vector::new(20.0, -30.0), 3.0), 0.5),
+            TranslateScale::new(Vector::new(10.0, -15.0), 2.0)
+        );
+    }
+
+    #[test]
+    fn translate_scale_mul_delegates_to_transform() {
+        let transformation =
+            TranslateScale::new(Vector::new(10.0, -5.0), 2.0).scaled(1.5);
+
+        let point = Point::new(3.0, 4.0);
+        let vector = Vector::new(-1.0, 2.0);
+        let rectangle = Rectangle {
+            x: 1.0,
+            y: 2.0,
+            width: 3.0,
+            height: f32::INFINITY,
+        };
+
+        assert_eq!(
+            transformation * point,
+            transformation.transform_point(point)
+        );
+        assert_eq!(
+            transformation * vector,
+            transformation.transform_vector(vector)
+        );
+        assert_eq!(
+            transformation * rectangle,
+            transformation.transform_rectangle(rectangle)
+        );
+    }
+
+    #[test]
+    fn batch_transforms_match_single_transforms() {
+        let points: Vec<_> = (0..100)
+            .map(|i| Point::new(i as f32 * 1.5, 100.0 - i as f32 * 0.25))
+            .chain([Point::new(f32::INFINITY, f32::NEG_INFINITY)])
+            .collect();
+
+        let mut out = vec![Point::ORIGIN; points.len()];
+
+        let transformation = Transformation::translate(12.0, -3.0)
+            * Transformation(Mat4::from_rotation_z(0.75))
+            * Transformation::scale(2.0, 0.5);
+
+        transformation.transform_points(&points[..100], &mut out[..100]);
+
+        for (point, out) in points.iter().zip(&out[..100]) {
+            assert_eq!(*out, transformation.transform_point(*point));
+        }
+
+        let translate_scale = TranslateScale::new(Vector::new(5.0, 2.0), -3.0);
+
+        translate_scale.transform_points(&points, &mut out);
+
+        for (point, out) in points.iter().zip(&out) {
+            assert_eq!(*out, translate_scale.transform_point(*point));
+        }
+    }
+
+    #[test]
+    fn translate_rotate_scale_composes_in_local_coordinates() {
+        let quarter = std::f32::consts::FRAC_PI_2;
+
+        let transformation = TranslateRotateScale::IDENTITY
+            .translated(Vector::new(10.0, 0.0))
+            .rotated(quarter)
+            .scaled(2.0)
+            .translated(Vector::new(1.0, 0.0));
+
+        // The last translation happens in the rotated and scaled space
+        let point = transformation.transform_point(Point::ORIGIN);
+
+        assert!((point.x - 10.0).abs() < 1e-5);
+        assert!((point.y - 2.0).abs() < 1e-5);
+
+        // Rotating after translating moves the translation
+        let rotated_first = TranslateRotateScale::IDENTITY
+            .rotated(quarter)
+            .translated(Vector::new(10.0, 0.0));
+
+        let point = rotated_first.transform_point(Point::ORIGIN);
+
+        assert!((point.x - 0.0).abs() < 1e-5);
+        assert!((point.y - 10.0).abs() < 1e-5);
+
+        let bounds = rotated_first.transform_rectangle(Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 4.0,
+            height: 2.0,
+        });
+
+        assert!((bounds.x + 2.0).abs() < 1e-5);
+        assert!((bounds.y - 10.0).abs() < 1e-5);
+        assert!((bounds.width - 2.0).abs() < 1e-5);
+        assert!((bounds.height - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn translate_rotate_scale_matches_transformation() {
+        let transformation =
+            TranslateRotateScale::new(Vector::new(-7.0, 40.0), 0.3, 1.5)
+                .translated(Vector::new(2.0, 3.0))
+                .rotated(-1.1)
+                .scaled(0.5);
+
+        let matrix = Transformation::from(transformation);
+
+        for point in [
+            Point::ORIGIN,
+            Point::new(10.0, -4.0),
+            Point::new(-3.5, 8.25),
+        ] {
+            let a = transformation.transform_point(point);
+            let b = matrix.transform_point(point);
+
+            assert!((a.x - b.x).abs() < 1e-4 && (a.y - b.y).abs() < 1e-4);
+        }
+
+        let translate_scale = TranslateScale::new(Vector::new(5.0, 2.0), 3.0);
+
+        assert_close(
+            Transformation::from(TranslateRotateScale::from(translate_scale)),
+            Transformation::from(translate_scale),
+        );
+    }
+
+    #[test]
+    fn flipped_rectangles_keep_positive_dimensions() {
+        let rectangle = Rectangle {
+            x: 10.0,
+            y: 20.0,
+            width: 30.0,
+            height: 40.0,
+        };
+
+        let mirrored_x =
+            Transformation::scale(-1.0, 1.0).transform_rectangle(rectangle);
+
+        assert_eq!(
+            mirrored_x,
+            Rectangle {
+                x: -40.0,
+                y: 20.0,
+                width: 30.0,
+                height: 40.0,
+            }
+        );
+
+        let mirrored_y =
+            Transformation::scale(1.0, -1.0).transform_rectangle(rectangle);
+
+        assert_eq!(
+            mirrored_y,
+            Rectangle {
+                x: 10.0,
+                y: -60.0,
+                width: 30.0,
+                height: 40.0,
+            }
+        );
+
+        let flipped = TranslateScale::new(Vector::new(100.0, 100.0), -2.0);
+
+        assert_eq!(
+            flipped.transform_rectangle(rectangle),
+            Rectangle {
+                x: 20.0,
+                y: -20.0,
+                width: 60.0,
+                height: 80.0,
+            }
+        );
+        assert_eq!(
+            TranslateRotateScale::from(flipped).transform_rectangle(rectangle),
+            flipped.transform_rectangle(rectangle)
+        );
+
+        let unbounded = flipped.transform_rectangle(Rectangle {
+            width: f32::INFINITY,
+            ..rectangle
+        });
+
+        assert_eq!(unbounded.x, f32::NEG_INFINITY);
+        assert_eq!(unbounded.width, f32::INFINITY);
+    }
+
+    #[test]
+    fn clamped_rectangles_are_finite() {
+        let bounds = Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 800.0,
+            height: 600.0,
+        };
+
+        let text = Rectangle {
+            x: 10.0,
+            y: 20.0,
+            width: f32::INFINITY,
+            height: 30.0,
+        };
+
+        let transformation = TranslateScale::new(Vector::new(5.0, 0.0), 2.0);
+
+        assert_eq!(
+            transformation.transform_rectangle_clamped(text, bounds),
+            Some(Rectangle {
+                x: 25.0,
+                y: 40.0,
+                width: 775.0,
+                height: 60.0,
+            })
+        );
+
+        // Flipping moves the infinite edge to the left
+        let flipped = TranslateScale::new(Vector::new(400.0, 400.0), -1.0);
+
+        assert_eq!(
+            flipped.transform_rectangle_clamped(text, bounds),
+            Some(Rectangle {
+                x: 0.0,
+                y: 350.0,
+                width: 390.0,
+                height: 30.0,
+            })
+        );
+
+        let unbounded = Rectangle {
+            x: f32::NEG_INFINITY,
+            y: f32::NEG_INFINITY,
+            width: f32::INFINITY,
+            height: f32::INFINITY,
+        };
+
+        assert_eq!(
+            TranslateScale::IDENTITY
+                .transform_rectangle_clamped(unbounded, bounds),
+            Some(bounds)
+        );
+
+        assert_eq!(
+            transformation.transform_rectangle_clamped(
+                Rectangle { x: 1000.0, ..text },
+                bounds
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn window_points_round_trip_through_clip_space() {
+        let viewport = Viewport::with_physical_size(Size::new(800, 600), 2.0);
+
+        let window_to_clip = Transformation::window_to_clip(&viewport);
+        let clip_to_window = Transformation::clip_to_window(&viewport);
+
+        assert_eq!(
+            window_to_clip.transform_point(Point::new(400.0, 300.0)),
+            Point::ORIGIN
+        );
+        assert_eq!(
+            window_to_clip.transform_point(Point::ORIGIN),
+            Point::new(-1.0, 1.0)
+        );
+
+        for point in [
+            Point::ORIGIN,
+            Point::new(123.0, 456.0),
+            Point::new(800.0, 600.0),
+        ] {
+            let clip = window_to_clip.transform_point(point);
+            let window = clip_to_window.transform_point(clip);
+
+            assert!((window.x - point.x).abs() < 1e-3);
+            assert!((window.y - point.y).abs() < 1e-3);
+        }
+
+        // Logical coordinates go through the scale factor
+        let logical = Transformation::window_to_viewport(&viewport)
+            .transform_point(Point::new(400.0, 300.0));
+
+        assert_eq!(logical, Point::new(200.0, 150.0));
+        assert_eq!(
+            Transformation::viewport_to_clip(&viewport)
+                .transform_point(logical),
+            Point::ORIGIN
+        );
+    }
+
+    #[test]
+    fn singular_transformations_have_no_inverse() {
+        assert!(Transformation::scale(0.0, 1.0).inverse().is_none());
+        assert_close(
+            Transformation::translate(3.0, 4.0).inverse().unwrap(),
+            Transformation::translate(-3.0, -4.0),
+        );
+    }
+
+    #[test]
+    fn x_skew_shifts_points_by_their_height() {
+        let skew = Transformation::skew(std::f32::consts::FRAC_PI_4, 0.0);
+
+        assert_eq!(
+            skew.transform_point(Point::new(5.0, 0.0)),
+            Point::new(5.0, 0.0)
+        );
+
+        let point = skew.transform_point(Point::new(5.0, 2.0));
+
+        assert!((point.x - 7.0).abs() < 1e-5);
+        assert!((point.y - 2.0).abs() < 1e-5);
+
+        let skewed = Transformation::translate(10.0, 0.0)
+            .skewed(std::f32::consts::FRAC_PI_4, 0.0);
+
+        let bounds = skewed.transform_rectangle(Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 4.0,
+            height: 2.0,
+        });
+
+        assert!((bounds.x - 10.0).abs() < 1e-5);
+        assert!((bounds.y - 0.0).abs() < 1e-5);
+        assert!((bounds.width - 6.0).abs() < 1e-5);
+        assert!((bounds.height - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn approx_eq_tolerates_small_differences() {
+        let a = Transformation::translate(0.0, 5.0);
+        let b = Transformation::translate(1e-7, 5.0);
+
+        assert_ne!(a, b);
+        assert!(a.approx_eq(&b, Transformation::DEFAULT_EPSILON));
+        assert!(!a.approx_eq(&b, 1e-8));
+    }
+
+    #[test]
+    fn fit_rect_into_centers_and_fills_viewport() {
+        let content = Rectangle {
+            x: 100.0,
+            y: 50.0,
+            width: 200.0,
+            height: 100.0,
+        };
+
+        let transformation = TranslateScale::fit_rect_into(
+            content,
+            Size::new(800.0, 600.0),
+            20.0,
+        );
+
+        let fitted = transformation * content;
+
+        assert_eq!(fitted.center(), Point::new(400.0, 300.0));
+        assert_eq!(fitted.width, 760.0);
+        assert_eq!(fitted.height, 380.0);
+    }
+
+    #[test]
+    fn decompose_recovers_components() {
+        let translation = Vector::new(12.0, -3.0);
+        let rotation = 0.75;
+        let scale = Vector::new(2.0, 0.5);
+
+        let transformation = Transformation::translate(12.0, -3.0)
+            * Transformation(Mat4::from_rotation_z(rotation))
+            * Transformation::scale(2.0, 0.5);
+
+        assert_close(
+            transformation,
+            Transformation::from_decomposed(translation, rotation, scale),
+        );
+
+        let (t, r, s) = transformation.decompose().unwrap();
+
+        assert!((t.x - translation.x).abs() < 1e-5);
+        assert!((t.y - translation.y).abs() < 1e-5);
+        assert!((r - rotation).abs() < 1e-5);
+        assert!((s.x - scale.x).abs() < 1e-5);
+        assert!((s.y - scale.y).abs() < 1e-5);
+    }
+
+    #[test]
+    fn decompose_rejects_unsupported_components() {
+        assert!(Transformation::scale(2.0, -1.0).decompose().is_some());
+        assert!(Transformation::skew(0.5, 0.0).decompose().is_none());
+        assert!(Transformation(Mat4::perspective_rh_gl(1.0, 1.0, 0.1, 10.0))
+            .decompose()
+            .is_none());
+    }
+
+    #[test]
+    fn from_srt_matches_manual_chain() {
+        let scale = Vector::new(3.0, 1.5);
+        let rotation = -1.2;
+        let translation = Vector::new(-7.0, 40.0);
+
+        let transformation =
+            Transformation::from_srt(scale, rotation, translation);
+
+        let (t, r, s) = transformation.decompose().unwrap();
+
+        assert!((t.x - translation.x).abs() < 1e-5);
+        assert!((t.y - translation.y).abs() < 1e-5);
+        assert!((r - rotation).abs() < 1e-5);
+        assert!((s.x - scale.x).abs() < 1e-5);
+        assert!((s.y - scale.y).abs() < 1e-5);
+
+        let manual = Transformation::translate(translation.x, translation.y)
+            * Transformation(Mat4::from_rotation_z(rotation))
+            * Transformation::scale(scale.x, scale.y);
+
+        let point = Point::new(5.0, -2.0);
+        let a = transformation.transform_point(point);
+        let b = manual.transform_point(point);
+
+        assert!((a.x - b.x).abs() < 1e-4);
+        assert!((a.y - b.y).abs() < 1e-4);
+    }
+
+    #[test]
+    fn orthographic_f32_matches_integer_version() {
+        assert_eq!(
+            Transformation::orthographic(800, 600),
+            Transformation::orthographic_f32(800.0, 600.0)
+        );
+
+        let projection = Transformation::orthographic_f32(412.5, 300.25);
+
+        let corner = projection.transform_point(Point::new(412.5, 300.25));
+        let origin = projection.transform_point(Point::ORIGIN);
+
+        assert!((corner.x - 1.0).abs() < 1e-6);
+        assert!((corner.y + 1.0).abs() < 1e-6);
+        assert!((origin.x + 1.0).abs() < 1e-6);
+        assert!((origin.y - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn infinite_points_stay_infinite() {
+        let point = Point::new(f32::INFINITY, f32::NEG_INFINITY);
+
+        for scale in [0.0, 1.0, 2.0] {
+            let transformation =
+                TranslateScale::new(Vector::new(10.0, 20.0), scale);
+
+            assert_eq!(transformation.transform_point(point), point);
+        }
+
+        let zero = TranslateScale::new(Vector::new(10.0, 20.0), 0.0);
+
+        assert_eq!(
+            zero.transform_point(Point::new(5.0, 5.0)),
+            Point::new(10.0, 20.0)
+        );
+        assert_eq!(
+            zero.transform_rectangle(Rectangle::new(
+                Point::new(f32::NEG_INFINITY, 5.0),
+                Size::INFINITY
+            )),
+            Rectangle::new(Point::new(f32::NEG_INFINITY, 20.0), Size::INFINITY)
+        );
+    }
+
+    #[test]
+    fn translations_in_3d_keep_their_depth() {
+        let transformation =
+            Transformation::scale(2.0, 2.0).translated_3d(5.0, 10.0, 3.0);
+        let flat = Transformation::scale(2.0, 2.0)
+            .translated_vector(Vector::new(5.0, 10.0));
+
+        assert_eq!(Mat4::from(transformation).w_axis.z, 3.0);
+        assert_eq!(Mat4::from(flat).w_axis.z, 0.0);
+
+        let point = Point::new(1.0, 2.0);
+
+        assert_eq!(
+            transformation.transform_point(point),
+            flat.transform_point(point)
+        );
+        assert_eq!(
+            transformation.transform_point(point),
+            Point::new(12.0, 24.0)
+        );
+    }
+
+    #[test]
+    fn raw_matrices_round_trip() {
+        let matrix = Mat4::perspective_rh_gl(1.0, 1.5, 0.1, 100.0)
+            * Mat4::from_translation(Vec3::new(1.0, 2.0, -10.0));
+
+        assert_eq!(Mat4::from(Transformation::from_mat4(matrix)), matrix);
+        assert_eq!(
+            Transformation::from(matrix),
+            Transformation::from_mat4(matrix)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "NaN")]
+    fn nan_points_are_caught_in_debug_builds() {
+        let _ =
+            TranslateScale::IDENTITY.transform_point(Point::new(f32::NAN, 0.0));
+    }
+}
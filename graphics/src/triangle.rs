@@ -1,4 +1,6 @@
 //! Draw geometry using meshes of triangles.
+use crate::{Color, Point};
+
 use bytemuck::{Pod, Zeroable};
 
 /// A set of [`Vertex2D`] and indices representing a list of triangles.
@@ -11,6 +13,61 @@ pub struct Mesh2D<T> {
     ///
     /// Therefore, this list should always have a length that is a multiple of 3.
     pub indices: Vec<u32>,
+
+    /// The [`AlphaMode`] of the vertex colors of the mesh.
+    pub alpha_mode: AlphaMode,
+}
+
+impl<T> Mesh2D<T> {
+    /// Returns whether the [`Mesh2D`] has no triangles to draw; i.e. its
+    /// vertices or its indices are empty.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() || self.indices.is_empty()
+    }
+}
+
+/// A set of three-dimensional vertices and indices representing a list of
+/// triangles.
+///
+/// The buffers of a mesh do not depend on the dimensions of its vertices.
+pub type Mesh3D<T> = Mesh2D<T>;
+
+/// The way the alpha channel of a color is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// The color channels are independent of the alpha channel.
+    Straight,
+
+    /// The color channels have already been multiplied by the alpha channel.
+    Premultiplied,
+}
+
+impl AlphaMode {
+    /// Converts the given __linear__ RGBA color, encoded in this
+    /// [`AlphaMode`], into the `target` one.
+    pub fn convert(self, color: [f32; 4], target: AlphaMode) -> [f32; 4] {
+        let [r, g, b, a] = color;
+
+        match (self, target) {
+            (AlphaMode::Straight, AlphaMode::Premultiplied) => {
+                [r * a, g * a, b * a, a]
+            }
+            (AlphaMode::Premultiplied, AlphaMode::Straight) => {
+                if a > 0.0 {
+                    [r / a, g / a, b / a, a]
+                } else {
+                    [0.0, 0.0, 0.0, 0.0]
+                }
+            }
+            _ => color,
+        }
+    }
+}
+
+impl Default for AlphaMode {
+    fn default() -> Self {
+        AlphaMode::Straight
+    }
 }
 
 /// A two-dimensional vertex.
@@ -31,3 +88,25 @@ pub struct ColoredVertex2D {
     /// The color of the vertex in __linear__ RGBA.
     pub color: [f32; 4],
 }
+
+impl ColoredVertex2D {
+    /// Creates a new [`ColoredVertex2D`] at the given position, linearizing
+    /// the given [`Color`].
+    pub fn new(position: Point, color: Color) -> Self {
+        Self {
+            position: [position.x, position.y],
+            color: color.into_linear(),
+        }
+    }
+}
+
+/// A three-dimensional vertex with a color.
+#[derive(Copy, Clone, Debug, Zeroable, Pod)]
+#[repr(C)]
+pub struct ColoredVertex3D {
+    /// The vertex position in 3D space.
+    pub position: [f32; 3],
+
+    /// The color of the vertex in __linear__ RGBA.
+    pub color: [f32; 4],
+}
@@ -0,0 +1,14 @@
+/// The way text overflowing the width of its bounds is shortened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Truncate {
+    /// The text is clipped by its bounds
+    None,
+    /// The overflowing tail of the text is replaced with an ellipsis ("…")
+    Ellipsis,
+}
+
+impl Default for Truncate {
+    fn default() -> Self {
+        Truncate::None
+    }
+}
@@ -2,12 +2,14 @@ use crate::widget::canvas::{Frame, Geometry};
 use crate::Primitive;
 
 use iced_native::Size;
-use std::{cell::RefCell, sync::Arc};
+use std::cell::RefCell;
+use std::sync::Arc;
 
 enum State {
     Empty,
     Filled {
         bounds: Size,
+        key: u64,
         primitive: Arc<Primitive>,
     },
 }
@@ -54,11 +56,13 @@ impl Cache {
 
         if let State::Filled {
             bounds: cached_bounds,
+            key,
             primitive,
         } = self.state.borrow().deref()
         {
             if *cached_bounds == bounds {
                 return Geometry::from_primitive(Primitive::Cached {
+                    key: *key,
                     cache: primitive.clone(),
                 });
             }
@@ -73,12 +77,20 @@ impl Cache {
             Arc::new(geometry.into_primitive())
         };
 
+        // Refilling a cache with the same contents keeps its key, so any work
+        // done for them by a renderer can be reused
+        let key = primitive.content_hash();
+
         *self.state.borrow_mut() = State::Filled {
             bounds,
+            key,
             primitive: primitive.clone(),
         };
 
-        Geometry::from_primitive(Primitive::Cached { cache: primitive })
+        Geometry::from_primitive(Primitive::Cached {
+            key,
+            cache: primitive,
+        })
     }
 }
 
@@ -86,8 +98,13 @@ impl std::fmt::Debug for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             State::Empty => write!(f, "Empty"),
-            State::Filled { primitive, bounds } => f
+            State::Filled {
+                primitive,
+                key,
+                bounds,
+            } => f
                 .debug_struct("Filled")
+                .field("key", key)
                 .field("primitive", primitive)
                 .field("bounds", bounds)
                 .finish(),
@@ -49,24 +49,4 @@ impl From<Gradient> for Fill {
     }
 }
 
-/// The fill rule defines how to determine what is inside and what is outside of
-/// a shape.
-///
-/// See the [SVG specification][1].
-///
-/// [1]: https://www.w3.org/TR/SVG/painting.html#FillRuleProperty
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(missing_docs)]
-pub enum FillRule {
-    NonZero,
-    EvenOdd,
-}
-
-impl From<FillRule> for lyon::tessellation::FillRule {
-    fn from(rule: FillRule) -> lyon::tessellation::FillRule {
-        match rule {
-            FillRule::NonZero => lyon::tessellation::FillRule::NonZero,
-            FillRule::EvenOdd => lyon::tessellation::FillRule::EvenOdd,
-        }
-    }
-}
+pub use crate::path::FillRule;
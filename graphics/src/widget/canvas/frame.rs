@@ -1,7 +1,7 @@
 use crate::gradient::Gradient;
 use crate::triangle;
 use crate::widget::canvas::{path, Fill, Geometry, Path, Stroke, Style, Text};
-use crate::Primitive;
+use crate::{ClipMode, LineHeight, Primitive, Truncate};
 
 use iced_native::{Point, Rectangle, Size, Vector};
 
@@ -331,9 +331,16 @@ impl Frame {
             },
             color: text.color,
             size: text.size,
+            letter_spacing: 0.0,
+            line_height: LineHeight::default(),
             font: text.font,
             horizontal_alignment: text.horizontal_alignment,
             vertical_alignment: text.vertical_alignment,
+            truncate: Truncate::None,
+            max_lines: None,
+            background: None,
+            shadow: None,
+            snap_baselines: false,
         });
     }
 
@@ -381,6 +388,8 @@ impl Frame {
                     translation,
                     content: Box::new(Primitive::Clip {
                         bounds: Rectangle::with_size(region.size()),
+                        antialias: false,
+                        mode: ClipMode::Intersect,
                         content: Box::new(Primitive::Group {
                             primitives: text,
                         }),
@@ -439,6 +448,7 @@ impl Frame {
                             buffers: triangle::Mesh2D {
                                 vertices: buffer.vertices,
                                 indices: buffer.indices,
+                                alpha_mode: triangle::AlphaMode::Straight,
                             },
                             size: self.size,
                         })
@@ -450,6 +460,7 @@ impl Frame {
                             buffers: triangle::Mesh2D {
                                 vertices: buffer.vertices,
                                 indices: buffer.indices,
+                                alpha_mode: triangle::AlphaMode::Straight,
                             },
                             size: self.size,
                             gradient,
@@ -63,12 +63,9 @@ pub trait StyleSheet {
 
         Appearance {
             shadow_offset: Vector::default(),
-            background: active.background.map(|background| match background {
-                Background::Color(color) => Background::Color(Color {
-                    a: color.a * 0.5,
-                    ..color
-                }),
-            }),
+            background: active
+                .background
+                .map(|background| background.scale_alpha(0.5)),
             text_color: Color {
                 a: active.text_color.a * 0.5,
                 ..active.text_color
@@ -1,11 +1,13 @@
 use crate::quad;
+use crate::shadow;
 use crate::text;
 use crate::triangle;
 use crate::{Settings, Transformation};
 
 use iced_graphics::backend;
 use iced_graphics::font;
-use iced_graphics::layer::Layer;
+use iced_graphics::layer::{self, Layer};
+use iced_graphics::triangle::AlphaMode;
 use iced_graphics::{Primitive, Viewport};
 use iced_native::alignment;
 use iced_native::{Font, Size};
@@ -20,6 +22,7 @@ use crate::image;
 #[derive(Debug)]
 pub struct Backend {
     quad_pipeline: quad::Pipeline,
+    shadow_pipeline: shadow::Pipeline,
     text_pipeline: text::Pipeline,
     triangle_pipeline: triangle::Pipeline,
 
@@ -44,6 +47,7 @@ impl Backend {
         );
 
         let quad_pipeline = quad::Pipeline::new(device, format);
+        let shadow_pipeline = shadow::Pipeline::new(device, format);
         let triangle_pipeline =
             triangle::Pipeline::new(device, format, settings.antialiasing);
 
@@ -52,6 +56,7 @@ impl Backend {
 
         Self {
             quad_pipeline,
+            shadow_pipeline,
             text_pipeline,
             triangle_pipeline,
 
@@ -119,6 +124,19 @@ impl Backend {
             return;
         }
 
+        if !layer.shadows.is_empty() {
+            self.shadow_pipeline.draw(
+                device,
+                staging_belt,
+                encoder,
+                &layer.shadows,
+                transformation,
+                scale_factor,
+                bounds,
+                target,
+            );
+        }
+
         if !layer.quads.is_empty() {
             self.quad_pipeline.draw(
                 device,
@@ -132,6 +150,61 @@ impl Backend {
             );
         }
 
+        #[cfg(feature = "image")]
+        if !layer.quad_images.is_empty() {
+            let scaled = transformation
+                * Transformation::scale(scale_factor, scale_factor);
+
+            self.image_pipeline.draw_quad_images(
+                device,
+                staging_belt,
+                encoder,
+                &layer.quads,
+                &layer.quad_images,
+                scaled,
+                bounds,
+                target,
+                scale_factor,
+            );
+        }
+
+        // Every batch is drawn with a single instanced draw call
+        for batch in &layer.quad_batches {
+            self.quad_pipeline.draw(
+                device,
+                staging_belt,
+                encoder,
+                &batch.quads(),
+                transformation,
+                scale_factor,
+                bounds,
+                target,
+            );
+        }
+
+        // Layers are generated with the default settings
+        let arcs: Vec<_> = layer
+            .arcs
+            .iter()
+            .filter_map(|arc| arc.mesh(AlphaMode::default(), layer.bounds))
+            .collect();
+
+        if !arcs.is_empty() {
+            let scaled = transformation
+                * Transformation::scale(scale_factor, scale_factor);
+
+            self.triangle_pipeline.draw(
+                device,
+                staging_belt,
+                encoder,
+                target,
+                target_size,
+                scaled,
+                scale_factor,
+                &arcs,
+            );
+        }
+
         if !layer.meshes.is_empty() {
             let scaled = transformation
                 * Transformation::scale(scale_factor, scale_factor);
@@ -148,6 +221,28 @@ impl Backend {
             );
         }
 
+        if !layer.meshes_3d.is_empty() {
+            let scaled = transformation
+                * Transformation::scale(scale_factor, scale_factor);
+
+            let meshes: Vec<_> = layer
+                .meshes_3d
+                .iter()
+                .map(layer::Mesh3D::flattened)
+                .collect();
+
+            self.triangle_pipeline.draw(
+                device,
+                staging_belt,
+                encoder,
+                target,
+                target_size,
+                scaled,
+                scale_factor,
+                &meshes,
+            );
+        }
+
         #[cfg(any(feature = "image", feature = "svg"))]
         {
             if !layer.images.is_empty() {
@@ -168,7 +263,22 @@ impl Backend {
         }
 
         if !layer.text.is_empty() {
-            for text in layer.text.iter() {
+            // Shadows are drawn as copies of their text right behind it
+            //
+            // TODO: Blur text shadows
+            let texts = layer.text.iter().flat_map(|text| {
+                text.shadow
+                    .map(|shadow| layer::Text {
+                        bounds: text.bounds + shadow.offset,
+                        color: shadow.color,
+                        shadow: None,
+                        ..*text
+                    })
+                    .into_iter()
+                    .chain(std::iter::once(*text))
+            });
+
+            for text in texts {
                 // Target physical coordinates directly to avoid blurry text
                 let text = wgpu_glyph::Section {
                     // TODO: We `round` here to avoid rerasterizing text when
@@ -246,6 +356,19 @@ impl Backend {
                 },
             );
         }
+
+        if let Some(tint) = layer.tint_quad() {
+            self.quad_pipeline.draw(
+                device,
+                staging_belt,
+                encoder,
+                &[tint],
+                transformation,
+                scale_factor,
+                bounds,
+                target,
+            );
+        }
     }
 }
 
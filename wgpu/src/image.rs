@@ -9,8 +9,10 @@ use iced_graphics::image::vector;
 use crate::Transformation;
 use atlas::Atlas;
 
+use iced_graphics::image::storage::Entry as _;
+use iced_graphics::image::Filter;
 use iced_graphics::layer;
-use iced_native::{Rectangle, Size};
+use iced_native::{Point, Rectangle, Size};
 
 use std::cell::RefCell;
 use std::mem;
@@ -46,7 +48,7 @@ impl Pipeline {
     pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
         use wgpu::util::DeviceExt;
 
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        let linear_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             address_mode_u: wgpu::AddressMode::ClampToEdge,
             address_mode_v: wgpu::AddressMode::ClampToEdge,
             address_mode_w: wgpu::AddressMode::ClampToEdge,
@@ -56,6 +58,16 @@ impl Pipeline {
             ..Default::default()
         });
 
+        let nearest_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
         let constant_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("iced_wgpu::image constants layout"),
@@ -80,6 +92,14 @@ impl Pipeline {
                         ),
                         count: None,
                     },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(
+                            wgpu::SamplerBindingType::Filtering,
+                        ),
+                        count: None,
+                    },
                 ],
             });
 
@@ -107,7 +127,15 @@ impl Pipeline {
                     },
                     wgpu::BindGroupEntry {
                         binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&sampler),
+                        resource: wgpu::BindingResource::Sampler(
+                            &linear_sampler,
+                        ),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(
+                            &nearest_sampler,
+                        ),
                     },
                 ],
             });
@@ -170,6 +198,14 @@ impl Pipeline {
                                 3 => Float32x2,
                                 4 => Float32x2,
                                 5 => Sint32,
+                                6 => Float32x4,
+                                7 => Float32x2,
+                                8 => Float32,
+                                9 => Uint32,
+                                10 => Uint32,
+                                11 => Float32x2,
+                                12 => Float32x2,
+                                13 => Float32x4,
                             ),
                         },
                     ],
@@ -291,56 +327,184 @@ impl Pipeline {
     ) {
         let instances: &mut Vec<Instance> = &mut Vec::new();
 
-        #[cfg(feature = "image")]
-        let mut raster_cache = self.raster_cache.borrow_mut();
+        // The caches are borrowed until every instance is added
+        {
+            #[cfg(feature = "image")]
+            let mut raster_cache = self.raster_cache.borrow_mut();
 
-        #[cfg(feature = "svg")]
-        let mut vector_cache = self.vector_cache.borrow_mut();
+            #[cfg(feature = "svg")]
+            let mut vector_cache = self.vector_cache.borrow_mut();
+
+            for image in images {
+                match &image {
+                    #[cfg(feature = "image")]
+                    layer::Image::Raster {
+                        handle,
+                        tint,
+                        opacity,
+                        rotation,
+                        filter,
+                        ..
+                    } => {
+                        if let Some(atlas_entry) = raster_cache.upload(
+                            handle,
+                            &mut (device, encoder),
+                            &mut self.texture_atlas,
+                        ) {
+                            let [r, g, b, a] = *tint;
+
+                            add_instances(
+                                image.slices(size(atlas_entry)),
+                                image.bounds().center(),
+                                Style {
+                                    color: [r, g, b, a * opacity],
+                                    rotation: *rotation,
+                                    filter: *filter,
+                                    ..Style::default()
+                                },
+                                atlas_entry,
+                                instances,
+                            );
+                        }
+                    }
+                    #[cfg(feature = "image")]
+                    layer::Image::NinePatch { handle, .. } => {
+                        if let Some(atlas_entry) = raster_cache.upload(
+                            handle,
+                            &mut (device, encoder),
+                            &mut self.texture_atlas,
+                        ) {
+                            add_instances(
+                                image.slices(size(atlas_entry)),
+                                image.bounds().center(),
+                                Style::default(),
+                                atlas_entry,
+                                instances,
+                            );
+                        }
+                    }
+                    #[cfg(not(feature = "image"))]
+                    layer::Image::Raster { .. }
+                    | layer::Image::NinePatch { .. } => {}
 
-        for image in images {
-            match &image {
-                #[cfg(feature = "image")]
-                layer::Image::Raster { handle, bounds } => {
-                    if let Some(atlas_entry) = raster_cache.upload(
+                    #[cfg(feature = "svg")]
+                    layer::Image::Vector {
                         handle,
-                        &mut (device, encoder),
-                        &mut self.texture_atlas,
-                    ) {
-                        add_instances(
-                            [bounds.x, bounds.y],
+                        bounds,
+                        opacity,
+                        color,
+                        rotation,
+                    } => {
+                        if let Some(atlas_entry) = vector_cache.upload(
+                            handle,
                             [bounds.width, bounds.height],
-                            atlas_entry,
-                            instances,
-                        );
+                            _scale,
+                            &mut (device, encoder),
+                            &mut self.texture_atlas,
+                        ) {
+                            let [r, g, b, a] = color.unwrap_or([1.0; 4]);
+
+                            add_instances(
+                                image.slices(size(atlas_entry)),
+                                image.bounds().center(),
+                                Style {
+                                    color: [r, g, b, a * opacity],
+                                    replace_color: color.is_some(),
+                                    rotation: *rotation,
+                                    ..Style::default()
+                                },
+                                atlas_entry,
+                                instances,
+                            );
+                        }
                     }
+                    #[cfg(not(feature = "svg"))]
+                    layer::Image::Vector { .. } => {}
                 }
-                #[cfg(not(feature = "image"))]
-                layer::Image::Raster { .. } => {}
+            }
+        }
 
-                #[cfg(feature = "svg")]
-                layer::Image::Vector { handle, bounds } => {
-                    let size = [bounds.width, bounds.height];
+        self.render(
+            device,
+            staging_belt,
+            encoder,
+            instances,
+            transformation,
+            bounds,
+            target,
+        );
+    }
 
-                    if let Some(atlas_entry) = vector_cache.upload(
-                        handle,
-                        size,
-                        _scale,
-                        &mut (device, encoder),
-                        &mut self.texture_atlas,
-                    ) {
-                        add_instances(
-                            [bounds.x, bounds.y],
-                            size,
-                            atlas_entry,
-                            instances,
-                        );
-                    }
+    /// Draws the images filling the given quads, clipped by the inner edge
+    /// of their borders.
+    #[cfg(feature = "image")]
+    pub fn draw_quad_images(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        quads: &[layer::Quad],
+        images: &[layer::quad::Image],
+        transformation: Transformation,
+        bounds: Rectangle<u32>,
+        target: &wgpu::TextureView,
+        scale: f32,
+    ) {
+        let instances: &mut Vec<Instance> = &mut Vec::new();
+
+        {
+            let mut raster_cache = self.raster_cache.borrow_mut();
+
+            for image in images {
+                if let Some(atlas_entry) = raster_cache.upload(
+                    &image.handle,
+                    &mut (device, encoder),
+                    &mut self.texture_atlas,
+                ) {
+                    let quad = &quads[image.quad];
+                    let clip = image.clip(quad);
+
+                    add_instances(
+                        image.slices(quad, size(atlas_entry)),
+                        clip.bounds.center(),
+                        Style {
+                            // Fragments are clipped in physical pixels
+                            clip: Some(layer::RoundedClip {
+                                bounds: clip.bounds * scale,
+                                border_radius: clip
+                                    .border_radius
+                                    .map(|radius| radius * scale),
+                            }),
+                            ..Style::default()
+                        },
+                        atlas_entry,
+                        instances,
+                    );
                 }
-                #[cfg(not(feature = "svg"))]
-                layer::Image::Vector { .. } => {}
             }
         }
 
+        self.render(
+            device,
+            staging_belt,
+            encoder,
+            instances,
+            transformation,
+            bounds,
+            target,
+        );
+    }
+
+    fn render(
+        &mut self,
+        device: &wgpu::Device,
+        staging_belt: &mut wgpu::util::StagingBelt,
+        encoder: &mut wgpu::CommandEncoder,
+        instances: &[Instance],
+        transformation: Transformation,
+        bounds: Rectangle<u32>,
+        target: &wgpu::TextureView,
+    ) {
         if instances.is_empty() {
             return;
         }
@@ -493,81 +657,168 @@ struct Instance {
     _position_in_atlas: [f32; 2],
     _size_in_atlas: [f32; 2],
     _layer: u32,
+    _color: [f32; 4],
+    _center: [f32; 2],
+    _rotation: f32,
+    _replace_color: u32,
+    _nearest: u32,
+    _clip_position: [f32; 2],
+    _clip_size: [f32; 2],
+    _clip_radius: [f32; 4],
 }
 
 impl Instance {
     pub const MAX: usize = 1_000;
 }
 
+/// The color, rotation, filter, and clip shared by every instance of an
+/// image.
+#[derive(Debug, Clone, Copy)]
+struct Style {
+    /// The color multiplied with every texel, in __linear RGB__.
+    color: [f32; 4],
+
+    /// Whether `color` replaces the color of every texel, keeping only its
+    /// alpha.
+    replace_color: bool,
+
+    /// The rotation around the center of the image, in radians.
+    rotation: f32,
+
+    /// The [`Filter`] used to sample the image.
+    filter: Filter,
+
+    /// The rounded rectangle clipping the image, in physical pixels.
+    clip: Option<layer::RoundedClip>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            color: [1.0; 4],
+            replace_color: false,
+            rotation: 0.0,
+            filter: Filter::Linear,
+            clip: None,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Zeroable, Pod)]
 struct Uniforms {
     transform: [f32; 16],
 }
 
+/// Returns the size of the image stored in the given entry, in source pixels.
+fn size(entry: &atlas::Entry) -> Size {
+    let Size { width, height } = entry.size();
+
+    Size::new(width as f32, height as f32)
+}
+
 fn add_instances(
-    image_position: [f32; 2],
-    image_size: [f32; 2],
+    slices: impl Iterator<Item = layer::Slice>,
+    center: Point,
+    style: Style,
     entry: &atlas::Entry,
     instances: &mut Vec<Instance>,
 ) {
-    match entry {
-        atlas::Entry::Contiguous(allocation) => {
-            add_instance(image_position, image_size, allocation, instances);
-        }
-        atlas::Entry::Fragmented { fragments, size } => {
-            let scaling_x = image_size[0] / size.width as f32;
-            let scaling_y = image_size[1] / size.height as f32;
-
-            for fragment in fragments {
-                let allocation = &fragment.allocation;
-
-                let [x, y] = image_position;
-                let (fragment_x, fragment_y) = fragment.position;
-                let Size {
-                    width: fragment_width,
-                    height: fragment_height,
-                } = allocation.size();
-
-                let position = [
-                    x + fragment_x as f32 * scaling_x,
-                    y + fragment_y as f32 * scaling_y,
-                ];
-
-                let size = [
-                    fragment_width as f32 * scaling_x,
-                    fragment_height as f32 * scaling_y,
-                ];
-
-                add_instance(position, size, allocation, instances);
+    for slice in slices {
+        match entry {
+            atlas::Entry::Contiguous(allocation) => {
+                add_instance(
+                    slice,
+                    (0, 0),
+                    [center.x, center.y],
+                    style,
+                    allocation,
+                    instances,
+                );
+            }
+            atlas::Entry::Fragmented { fragments, .. } => {
+                for fragment in fragments {
+                    add_instance(
+                        slice,
+                        fragment.position,
+                        [center.x, center.y],
+                        style,
+                        &fragment.allocation,
+                        instances,
+                    );
+                }
             }
         }
     }
 }
 
+/// Adds an instance drawing the part of the given slice that is stored in
+/// the allocation of a fragment of an image, if any.
 #[inline]
 fn add_instance(
-    position: [f32; 2],
-    size: [f32; 2],
+    slice: layer::Slice,
+    fragment_position: (u32, u32),
+    center: [f32; 2],
+    style: Style,
     allocation: &atlas::Allocation,
     instances: &mut Vec<Instance>,
 ) {
-    let (x, y) = allocation.position();
+    let layer::Slice { source, bounds } = slice;
+    let (fragment_x, fragment_y) = fragment_position;
     let Size { width, height } = allocation.size();
+
+    let fragment = Rectangle {
+        x: fragment_x as f32,
+        y: fragment_y as f32,
+        width: width as f32,
+        height: height as f32,
+    };
+
+    let region = match source.intersection(&fragment) {
+        Some(region) => region,
+        None => return,
+    };
+
+    let scale_x = bounds.width / source.width;
+    let scale_y = bounds.height / source.height;
+
+    // Only the centers of the texels of the region are sampled, so linear
+    // filtering never bleeds into its neighbors in the atlas
+    let inset_x = (region.width / 2.0).min(0.5);
+    let inset_y = (region.height / 2.0).min(0.5);
+
+    let (x, y) = allocation.position();
     let layer = allocation.layer();
 
+    // An empty clip never discards any fragment
+    let clip = style.clip.unwrap_or(layer::RoundedClip {
+        bounds: Rectangle::default(),
+        border_radius: [0.0; 4],
+    });
+
     let instance = Instance {
-        _position: position,
-        _size: size,
+        _position: [
+            bounds.x + (region.x - source.x) * scale_x,
+            bounds.y + (region.y - source.y) * scale_y,
+        ],
+        _size: [region.width * scale_x, region.height * scale_y],
         _position_in_atlas: [
-            (x as f32 + 0.5) / atlas::SIZE as f32,
-            (y as f32 + 0.5) / atlas::SIZE as f32,
+            (x as f32 + region.x - fragment.x + inset_x) / atlas::SIZE as f32,
+            (y as f32 + region.y - fragment.y + inset_y) / atlas::SIZE as f32,
         ],
         _size_in_atlas: [
-            (width as f32 - 1.0) / atlas::SIZE as f32,
-            (height as f32 - 1.0) / atlas::SIZE as f32,
+            (region.width - inset_x * 2.0) / atlas::SIZE as f32,
+            (region.height - inset_y * 2.0) / atlas::SIZE as f32,
         ],
         _layer: layer as u32,
+        _color: style.color,
+        _center: center,
+        _rotation: style.rotation,
+        _replace_color: u32::from(style.replace_color),
+        _nearest: u32::from(style.filter == Filter::Nearest),
+        _clip_position: [clip.bounds.x, clip.bounds.y],
+        _clip_size: [clip.bounds.width, clip.bounds.height],
+        _clip_radius: clip.border_radius,
     };
 
     instances.push(instance);
@@ -44,6 +44,7 @@ pub mod window;
 mod backend;
 mod buffer;
 mod quad;
+mod shadow;
 mod text;
 mod triangle;
 
@@ -92,7 +92,7 @@ impl Pipeline {
                                 3 => Float32x4,
                                 4 => Float32x4,
                                 5 => Float32x4,
-                                6 => Float32,
+                                6 => Float32x4,
                             ),
                         },
                     ],
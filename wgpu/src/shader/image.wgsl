@@ -4,6 +4,7 @@ struct Globals {
 
 @group(0) @binding(0) var<uniform> globals: Globals;
 @group(0) @binding(1) var u_sampler: sampler;
+@group(0) @binding(2) var u_nearest_sampler: sampler;
 @group(1) @binding(0) var u_texture: texture_2d_array<f32>;
 
 struct VertexInput {
@@ -13,12 +14,26 @@ struct VertexInput {
     @location(3) atlas_pos: vec2<f32>,
     @location(4) atlas_scale: vec2<f32>,
     @location(5) layer: i32,
+    @location(6) color: vec4<f32>,
+    @location(7) center: vec2<f32>,
+    @location(8) rotation: f32,
+    @location(9) replace_color: u32,
+    @location(10) nearest: u32,
+    @location(11) clip_pos: vec2<f32>,
+    @location(12) clip_scale: vec2<f32>,
+    @location(13) clip_radius: vec4<f32>,
 }
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
     @location(1) layer: f32, // this should be an i32, but naga currently reads that as requiring interpolation.
+    @location(2) color: vec4<f32>,
+    @location(3) replace_color: f32,
+    @location(4) nearest: f32,
+    @location(5) clip_pos: vec2<f32>,
+    @location(6) clip_scale: vec2<f32>,
+    @location(7) clip_radius: vec4<f32>,
 }
 
 @vertex
@@ -27,6 +42,12 @@ fn vs_main(input: VertexInput) -> VertexOutput {
 
     out.uv = vec2<f32>(input.v_pos * input.atlas_scale + input.atlas_pos);
     out.layer = f32(input.layer);
+    out.color = input.color;
+    out.replace_color = f32(input.replace_color);
+    out.nearest = f32(input.nearest);
+    out.clip_pos = input.clip_pos;
+    out.clip_scale = input.clip_scale;
+    out.clip_radius = input.clip_radius;
 
     var transform: mat4x4<f32> = mat4x4<f32>(
         vec4<f32>(input.scale.x, 0.0, 0.0, 0.0),
@@ -35,12 +56,98 @@ fn vs_main(input: VertexInput) -> VertexOutput {
         vec4<f32>(input.pos, 0.0, 1.0)
     );
 
-    out.position = globals.transform * transform * vec4<f32>(input.v_pos, 0.0, 1.0);
+    var position: vec4<f32> = transform * vec4<f32>(input.v_pos, 0.0, 1.0);
+
+    // Rotate around the center of the whole image, not of this fragment
+    var offset: vec2<f32> = position.xy - input.center;
+    var c: f32 = cos(input.rotation);
+    var s: f32 = sin(input.rotation);
+
+    position = vec4<f32>(
+        input.center + vec2<f32>(
+            offset.x * c - offset.y * s,
+            offset.x * s + offset.y * c
+        ),
+        0.0,
+        1.0
+    );
+
+    out.position = globals.transform * position;
 
     return out;
 }
 
+fn distance_alg(
+    frag_coord: vec2<f32>,
+    position: vec2<f32>,
+    size: vec2<f32>,
+    radius: f32
+) -> f32 {
+    var inner_size: vec2<f32> = size - vec2<f32>(radius, radius) * 2.0;
+    var top_left: vec2<f32> = position + vec2<f32>(radius, radius);
+    var bottom_right: vec2<f32> = top_left + inner_size;
+
+    var top_left_distance: vec2<f32> = top_left - frag_coord;
+    var bottom_right_distance: vec2<f32> = frag_coord - bottom_right;
+
+    var dist: vec2<f32> = vec2<f32>(
+        max(max(top_left_distance.x, bottom_right_distance.x), 0.0),
+        max(max(top_left_distance.y, bottom_right_distance.y), 0.0)
+    );
+
+    return sqrt(dist.x * dist.x + dist.y * dist.y);
+}
+
+// Order matches CSS border radius attribute:
+// radi.x = top-left, radi.y = top-right, radi.z = bottom-right, radi.w = bottom-left
+fn select_border_radius(radi: vec4<f32>, position: vec2<f32>, center: vec2<f32>) -> f32 {
+    var rx = radi.x;
+    var ry = radi.y;
+    rx = select(radi.x, radi.y, position.x > center.x);
+    ry = select(radi.w, radi.z, position.x > center.x);
+    rx = select(rx, ry, position.y > center.y);
+    return rx;
+}
+
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(u_texture, u_sampler, input.uv, i32(input.layer));
+    var color: vec4<f32>;
+
+    // The atlas has no mipmaps, so sampling its first level explicitly is
+    // the same, while allowing the sampler to be chosen per instance
+    if (input.nearest > 0.5) {
+        color = textureSampleLevel(u_texture, u_nearest_sampler, input.uv, i32(input.layer), 0.0);
+    } else {
+        color = textureSampleLevel(u_texture, u_sampler, input.uv, i32(input.layer), 0.0);
+    }
+
+    if (input.replace_color > 0.5) {
+        color = vec4<f32>(input.color.rgb, color.a * input.color.a);
+    } else {
+        color = color * input.color;
+    }
+
+    // Images filling a quad are clipped by the inner edge of its border
+    if (input.clip_scale.x > 0.0) {
+        var radius: f32 = select_border_radius(
+            input.clip_radius,
+            input.position.xy,
+            input.clip_pos + input.clip_scale * 0.5
+        );
+
+        var dist: f32 = distance_alg(
+            input.position.xy,
+            input.clip_pos,
+            input.clip_scale,
+            radius
+        );
+
+        color.a = color.a * (1.0 - smoothstep(
+            max(radius - 0.5, 0.0),
+            radius + 0.5,
+            dist
+        ));
+    }
+
+    return color;
 }
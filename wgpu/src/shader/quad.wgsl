@@ -12,7 +12,7 @@ struct VertexInput {
     @location(3) color: vec4<f32>,
     @location(4) border_color: vec4<f32>,
     @location(5) border_radius: vec4<f32>,
-    @location(6) border_width: f32,
+    @location(6) border_width: vec4<f32>,
 }
 
 struct VertexOutput {
@@ -22,7 +22,7 @@ struct VertexOutput {
     @location(2) pos: vec2<f32>,
     @location(3) scale: vec2<f32>,
     @location(4) border_radius: vec4<f32>,
-    @location(5) border_width: f32,
+    @location(5) border_width: vec4<f32>,
 }
 
 @vertex
@@ -104,13 +104,18 @@ fn fs_main(
         (input.pos + input.scale * 0.5).xy
     );
 
-    if (input.border_width > 0.0) {
-        var internal_border: f32 = max(border_radius - input.border_width, 0.0);
+    // Order matches CSS border width attribute:
+    // width.x = top, width.y = right, width.z = bottom, width.w = left
+    var width: vec4<f32> = input.border_width;
+    var max_border_width: f32 = max(max(width.x, width.y), max(width.z, width.w));
+
+    if (max_border_width > 0.0) {
+        var internal_border: f32 = max(border_radius - max_border_width, 0.0);
 
         var internal_distance: f32 = distance_alg(
             input.position.xy,
-            input.pos + vec2<f32>(input.border_width, input.border_width),
-            input.scale - vec2<f32>(input.border_width * 2.0, input.border_width * 2.0),
+            input.pos + vec2<f32>(width.w, width.x),
+            input.scale - vec2<f32>(width.y + width.w, width.x + width.z),
             internal_border
         );
 
@@ -0,0 +1,125 @@
+struct Globals {
+    transform: mat4x4<f32>,
+    scale: f32,
+}
+
+@group(0) @binding(0) var<uniform> globals: Globals;
+
+struct VertexInput {
+    @location(0) v_pos: vec2<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) scale: vec2<f32>,
+    @location(3) color: vec4<f32>,
+    @location(4) bounds: vec4<f32>,
+    @location(5) bounds_radius: vec4<f32>,
+    @location(6) shape: vec4<f32>,
+    @location(7) shape_radius: vec4<f32>,
+    @location(8) blur_radius: f32,
+    @location(9) inset: f32,
+    @location(10) glow: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) bounds: vec4<f32>,
+    @location(2) bounds_radius: vec4<f32>,
+    @location(3) shape: vec4<f32>,
+    @location(4) shape_radius: vec4<f32>,
+    @location(5) blur_radius: f32,
+    @location(6) inset: f32,
+    @location(7) glow: f32,
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    var pos: vec2<f32> = input.pos * globals.scale;
+    var scale: vec2<f32> = input.scale * globals.scale;
+
+    var transform: mat4x4<f32> = mat4x4<f32>(
+        vec4<f32>(scale.x, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, scale.y, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, 1.0, 0.0),
+        vec4<f32>(pos, 0.0, 1.0)
+    );
+
+    // Fragments are shaded in physical pixels
+    out.color = input.color;
+    out.bounds = input.bounds * globals.scale;
+    out.bounds_radius = input.bounds_radius * globals.scale;
+    out.shape = input.shape * globals.scale;
+    out.shape_radius = input.shape_radius * globals.scale;
+    out.blur_radius = input.blur_radius * globals.scale;
+    out.inset = input.inset;
+    out.glow = input.glow;
+    out.position = globals.transform * transform * vec4<f32>(input.v_pos, 0.0, 1.0);
+
+    return out;
+}
+
+// Based on the fragment position and the center of the box, select one of the 4 radi.
+// Order matches CSS border radius attribute:
+// radi.x = top-left, radi.y = top-right, radi.z = bottom-right, radi.w = bottom-left
+fn select_border_radius(radi: vec4<f32>, position: vec2<f32>, center: vec2<f32>) -> f32 {
+    var rx = radi.x;
+    var ry = radi.y;
+    rx = select(radi.x, radi.y, position.x > center.x);
+    ry = select(radi.w, radi.z, position.x > center.x);
+    rx = select(rx, ry, position.y > center.y);
+    return rx;
+}
+
+// The signed distance to a rounded rectangle, negative inside of it
+fn rounded_box_distance(
+    frag_coord: vec2<f32>,
+    box: vec4<f32>,
+    radi: vec4<f32>
+) -> f32 {
+    var half_size: vec2<f32> = box.zw * 0.5;
+    var center: vec2<f32> = box.xy + half_size;
+
+    var radius: f32 = min(
+        select_border_radius(radi, frag_coord, center),
+        min(half_size.x, half_size.y)
+    );
+
+    var q: vec2<f32> = abs(frag_coord - center) - half_size + vec2<f32>(radius, radius);
+
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0, 0.0))) - radius;
+}
+
+// The coverage of a shape whose edges fade out over the given extent
+fn coverage(d: f32, extent: f32) -> f32 {
+    var fade: f32 = max(extent, 0.5);
+
+    return 1.0 - smoothstep(-fade, fade, d);
+}
+
+@fragment
+fn fs_main(
+    input: VertexOutput
+) -> @location(0) vec4<f32> {
+    var shape: f32 = coverage(
+        rounded_box_distance(input.position.xy, input.shape, input.shape_radius),
+        input.blur_radius
+    );
+
+    var bounds: f32 = coverage(
+        rounded_box_distance(input.position.xy, input.bounds, input.bounds_radius),
+        0.0
+    );
+
+    var alpha: f32 = shape;
+
+    if (input.inset > 0.5) {
+        // Inset shadows fill the bounds around their blurred hole
+        alpha = (1.0 - shape) * bounds;
+    } else if (input.glow > 0.5) {
+        // Glows leave their bounds unfilled
+        alpha = shape * (1.0 - bounds);
+    }
+
+    return vec4<f32>(input.color.x, input.color.y, input.color.z, input.color.w * alpha);
+}
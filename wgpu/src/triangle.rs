@@ -134,7 +134,7 @@ impl Pipeline {
 
                     gradient_vertex_offset += written_bytes;
 
-                    match gradient {
+                    match gradient.as_ref() {
                         iced_graphics::Gradient::Linear(linear) => {
                             use glam::{IVec4, Vec4};
 